@@ -0,0 +1,297 @@
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use serde_json::json;
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use crate::logging::{LogFormat, LogRotation, LoggingConfig};
+use crate::registry::config::{self, ConfigLayers, CONFIG_FILE};
+use crate::registry::init::InitOptions;
+use crate::registry::Registry;
+use crate::tools::{
+    build_actor, create_actor, get_actor_info, get_actor_path, init_registry, list_actors,
+};
+
+/// Registry used when neither `--registry`, `THEATER_ACTORS_PATH` nor the
+/// user config name one: `$XDG_DATA_HOME/actor-registry`, or
+/// `~/.local/share/actor-registry`
+pub fn default_registry_path() -> Option<PathBuf> {
+    let non_empty = |var: &str| {
+        env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+
+    non_empty("XDG_DATA_HOME")
+        .or_else(|| non_empty("HOME").map(|home| home.join(".local").join("share")))
+        .map(|dir| dir.join("actor-registry"))
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "actor-registry",
+    version,
+    about = "Manage a Theater actor registry"
+)]
+pub struct Cli {
+    /// Registry roots separated like PATH; the first one is writable
+    #[arg(long, env = "THEATER_ACTORS_PATH", global = true, value_name = "PATHS")]
+    pub registry: Option<OsString>,
+
+    /// User config file (defaults to `~/.config/actor-registry/config.toml`)
+    #[arg(
+        long,
+        env = "ACTOR_REGISTRY_CONFIG",
+        global = true,
+        value_name = "PATH"
+    )]
+    pub config: Option<PathBuf>,
+
+    /// Theater CLI used for builds
+    #[arg(long, env = "THEATER_BINARY", global = true, value_name = "PATH")]
+    pub theater_binary: Option<PathBuf>,
+
+    /// Directory of WIT files copied into new actors
+    #[arg(long, env = "THEATER_WIT_PATH", global = true, value_name = "DIR")]
+    pub wit_source: Option<PathBuf>,
+
+    /// Address `serve --http` listens on when it is given none
+    #[arg(
+        long,
+        env = "ACTOR_REGISTRY_SERVER_ADDR",
+        global = true,
+        value_name = "ADDR"
+    )]
+    pub server_addr: Option<String>,
+
+    /// Management address of the Theater server actors are started in
+    #[arg(long, env = "THEATER_SERVER_ADDR", global = true, value_name = "ADDR")]
+    pub theater_addr: Option<String>,
+
+    /// Log file (defaults to `$XDG_STATE_HOME/actor-registry/actor-registry.log`)
+    #[arg(
+        long,
+        env = "ACTOR_REGISTRY_LOG_FILE",
+        global = true,
+        value_name = "PATH"
+    )]
+    pub log_file: Option<PathBuf>,
+
+    /// Log level filter, e.g. `info,actor_registry_mcp=debug`
+    #[arg(long, env = "RUST_LOG", global = true, value_name = "FILTER")]
+    pub log_filter: Option<String>,
+
+    /// Format of log records
+    #[arg(long, env = "ACTOR_REGISTRY_LOG_FORMAT", global = true, value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// Start a new log file every hour or day
+    #[arg(long, env = "ACTOR_REGISTRY_LOG_ROTATION", global = true, value_enum)]
+    pub log_rotation: Option<LogRotation>,
+
+    /// Also send log records to the MCP client as notifications
+    #[arg(long, env = "ACTOR_REGISTRY_LOG_TO_CLIENT", global = true)]
+    pub log_to_client: bool,
+
+    /// What to do; without a command the MCP server runs over stdio
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// List the actors in the registry
+    List {
+        /// Only list actors in this namespace
+        #[arg(long)]
+        namespace: Option<String>,
+    },
+    /// Create a new actor
+    Create {
+        name: String,
+        /// Template to use (defaults to the registry's default_template)
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Build an actor with `theater build`
+    Build {
+        name: String,
+        /// Build in release mode
+        #[arg(long)]
+        release: bool,
+        /// Clean the target directory before building
+        #[arg(long)]
+        clean: bool,
+        /// Force a rebuild even if the component is up to date
+        #[arg(long)]
+        force: bool,
+        /// Turn on verbose build output
+        #[arg(long)]
+        verbose: bool,
+    },
+    /// Show detailed information about an actor
+    Info { name: String },
+    /// Print the directory of an actor
+    Path { name: String },
+    /// Set up a registry directory; safe to run on an existing registry
+    Init {
+        /// Registry directory (defaults to the first registry root)
+        path: Option<PathBuf>,
+        /// Directory of Theater WIT files to vendor (defaults to wit_source)
+        #[arg(long, value_name = "DIR")]
+        wit: Option<PathBuf>,
+        /// Do not initialize a git repository
+        #[arg(long)]
+        no_git: bool,
+    },
+    /// Run the MCP server over stdio, or serve the registry over HTTP
+    Serve {
+        /// Serve the HTTP registry protocol on ADDR (default: the configured
+        /// server_addr) instead of MCP
+        #[arg(long, value_name = "ADDR", num_args = 0..=1)]
+        http: Option<Option<String>>,
+    },
+}
+
+impl Cli {
+    /// The registry roots in lookup order. `--registry` and
+    /// `THEATER_ACTORS_PATH` win over the `registry` key of the user config.
+    pub fn registry_roots(&self, user_registry: Option<String>) -> Vec<PathBuf> {
+        let paths = match (&self.registry, user_registry) {
+            (Some(paths), _) => paths.clone(),
+            (None, Some(paths)) => OsString::from(paths),
+            (None, None) => return default_registry_path().into_iter().collect(),
+        };
+
+        env::split_paths(&paths)
+            .filter(|p| !p.as_os_str().is_empty())
+            .collect()
+    }
+
+    /// The config layers around the registry's own file: the user config
+    /// below it, flags and their environment variables above it
+    pub fn config_layers(&self) -> ConfigLayers {
+        let mut overrides = toml::Table::new();
+        let mut set = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                overrides.insert(key.to_string(), toml::Value::String(value));
+            }
+        };
+
+        let path = |p: &PathBuf| p.to_string_lossy().into_owned();
+        set("theater_binary", self.theater_binary.as_ref().map(path));
+        set("wit_source", self.wit_source.as_ref().map(path));
+        set("server_addr", self.server_addr.clone());
+        set("theater_addr", self.theater_addr.clone());
+
+        ConfigLayers {
+            user_file: self.config.clone().or_else(config::user_config_path),
+            overrides,
+        }
+    }
+
+    /// Applies the logging flags and their environment variables on top of
+    /// the registry config's `[logging]` section
+    pub fn logging_config(&self, mut config: LoggingConfig) -> LoggingConfig {
+        if let Some(file) = &self.log_file {
+            config.file = Some(file.clone());
+        }
+        if let Some(filter) = &self.log_filter {
+            config.filter = Some(filter.clone());
+        }
+        if let Some(format) = self.log_format {
+            config.format = format;
+        }
+        if let Some(rotation) = self.log_rotation {
+            config.rotation = rotation;
+        }
+        config.forward_to_client |= self.log_to_client;
+        config
+    }
+}
+
+impl Command {
+    /// Runs a one-shot command through the same handler as the matching MCP
+    /// tool
+    pub fn run(&self, registry: &Registry) -> Result<ToolCallResult> {
+        match self {
+            Command::List { namespace } => {
+                list_actors::handle(registry, &json!({ "namespace": namespace }))
+            }
+            Command::Create { name, template } => {
+                create_actor::handle(registry, &json!({ "name": name, "template": template }))
+            }
+            Command::Build {
+                name,
+                release,
+                clean,
+                force,
+                verbose,
+            } => build_actor::handle(
+                registry,
+                &json!({
+                    "name": name,
+                    "release": release,
+                    "clean": clean,
+                    "force": force,
+                    "verbose": verbose,
+                }),
+            ),
+            Command::Info { name } => get_actor_info::handle(registry, &json!({ "name": name })),
+            Command::Path { name } => get_actor_path::handle(registry, &json!({ "name": name })),
+            Command::Init { .. } => Err(anyhow!("`init` runs before the registry is opened")),
+            Command::Serve { .. } => Err(anyhow!("`serve` is not a one-shot command")),
+        }
+    }
+
+    /// Runs `init`, which works on a registry that may not exist yet
+    pub fn init(&self, roots: &[PathBuf], layers: &ConfigLayers) -> Result<Option<ToolCallResult>> {
+        let Command::Init { path, wit, no_git } = self else {
+            return Ok(None);
+        };
+
+        let path = path
+            .clone()
+            .or_else(|| roots.first().cloned())
+            .ok_or_else(|| anyhow!("No registry path given"))?;
+
+        // Fall back to the WIT source the config layers name
+        let wit_source = wit.clone().or_else(|| {
+            layers
+                .resolve(&path.join(CONFIG_FILE))
+                .ok()
+                .and_then(|resolved| resolved.config.wit_source)
+                .map(|dir| path.join(dir))
+        });
+
+        let options = InitOptions {
+            wit_source,
+            git: !no_git,
+        };
+        init_registry::handle(&path, &options).map(Some)
+    }
+}
+
+/// Prints a tool result for a shell: output to stdout, or to stderr if the
+/// tool failed. Returns whether the tool succeeded.
+pub fn print_result(result: &ToolCallResult) -> bool {
+    let failed = result.is_error.unwrap_or(false);
+
+    for content in &result.content {
+        let text = match content {
+            ToolContent::Text { text } => text.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        let text = text.trim_end();
+
+        if failed {
+            eprintln!("{}", text);
+        } else {
+            println!("{}", text);
+        }
+    }
+
+    !failed
+}
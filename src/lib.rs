@@ -1,4 +1,7 @@
+pub mod cli;
+pub mod logging;
 pub mod registry;
 pub mod templates;
+pub mod theater_client;
 pub mod tools;
 pub mod utils;
@@ -0,0 +1,328 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use mcp_protocol::messages::JsonRpcMessage;
+use modelcontextprotocol_server::Transport;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+/// Directory under the XDG state directory that holds the log file
+const STATE_DIR_NAME: &str = "actor-registry";
+
+const LOG_FILE_NAME: &str = "actor-registry.log";
+
+/// Filter used when neither `RUST_LOG` nor the config sets one
+pub const DEFAULT_FILTER: &str = "info";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    #[default]
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl From<LogRotation> for Rotation {
+    fn from(rotation: LogRotation) -> Self {
+        match rotation {
+            LogRotation::Never => Rotation::NEVER,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+        }
+    }
+}
+
+/// The `[logging]` section of the registry config. Command-line flags and
+/// environment variables override it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Log file, defaults to `$XDG_STATE_HOME/actor-registry/actor-registry.log`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// Level filter in `RUST_LOG` syntax, e.g. `info,actor_registry_mcp=debug`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub format: LogFormat,
+    #[serde(default)]
+    pub rotation: LogRotation,
+    /// Forward log records to the MCP client as `notifications/message`
+    #[serde(default)]
+    pub forward_to_client: bool,
+}
+
+impl LoggingConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn log_file(&self) -> PathBuf {
+        self.file.clone().unwrap_or_else(default_log_file)
+    }
+}
+
+/// `$XDG_STATE_HOME/actor-registry`, falling back to `~/.local/state` and
+/// finally the temp directory
+pub fn default_log_dir() -> PathBuf {
+    let non_empty = |var: &str| {
+        env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+
+    if let Some(state) = non_empty("XDG_STATE_HOME") {
+        return state.join(STATE_DIR_NAME);
+    }
+    if let Some(home) = non_empty("HOME") {
+        return home.join(".local").join("state").join(STATE_DIR_NAME);
+    }
+    env::temp_dir().join(STATE_DIR_NAME)
+}
+
+pub fn default_log_file() -> PathBuf {
+    default_log_dir().join(LOG_FILE_NAME)
+}
+
+/// Log records waiting to be sent to the MCP client
+pub type ClientLogReceiver = mpsc::UnboundedReceiver<JsonRpcMessage>;
+
+/// Keeps the background log writer alive; dropping it flushes the log
+pub struct LoggingGuard {
+    _worker: Option<WorkerGuard>,
+    destination: String,
+    client_log: Option<ClientLogReceiver>,
+}
+
+impl LoggingGuard {
+    /// Where log records are written, for diagnostics
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// The records to forward to the MCP client, if forwarding is enabled.
+    /// Hand them to a [`ClientLogTransport`].
+    pub fn take_client_log(&mut self) -> Option<ClientLogReceiver> {
+        self.client_log.take()
+    }
+}
+
+fn open_log_file(path: &Path, rotation: LogRotation) -> Result<RollingFileAppender> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(LOG_FILE_NAME);
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+
+    RollingFileAppender::builder()
+        .rotation(rotation.into())
+        .filename_prefix(file_name)
+        .build(dir)
+        .with_context(|| format!("Failed to open log file {:?}", path))
+}
+
+/// Installs the global subscriber. Logging never goes to stdout, which
+/// carries the MCP protocol; if the log file cannot be opened records go to
+/// stderr instead.
+pub fn init(config: &LoggingConfig) -> Result<LoggingGuard> {
+    let filter_spec = config.filter.as_deref().unwrap_or(DEFAULT_FILTER);
+    let filter = EnvFilter::try_new(filter_spec).unwrap_or_else(|e| {
+        eprintln!(
+            "Invalid log filter '{}' ({}), using '{}'",
+            filter_spec, e, DEFAULT_FILTER
+        );
+        EnvFilter::new(DEFAULT_FILTER)
+    });
+
+    let path = config.log_file();
+    let (writer, worker, destination, ansi) = match open_log_file(&path, config.rotation) {
+        Ok(appender) => {
+            let (writer, worker) = tracing_appender::non_blocking(appender);
+            let destination = path.display().to_string();
+            (BoxMakeWriter::new(writer), Some(worker), destination, false)
+        }
+        Err(e) => {
+            eprintln!("{:#}; logging to stderr", e);
+            (
+                BoxMakeWriter::new(io::stderr),
+                None,
+                "stderr".to_string(),
+                true,
+            )
+        }
+    };
+
+    let output = match config.format {
+        LogFormat::Text => fmt::layer().with_writer(writer).with_ansi(ansi).boxed(),
+        LogFormat::Json => fmt::layer().json().with_writer(writer).boxed(),
+    };
+
+    let (forward, client_log) = if config.forward_to_client {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Some(McpNotificationLayer { sender }), Some(receiver))
+    } else {
+        (None, None)
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(output)
+        .with(forward)
+        .try_init()
+        .context("Failed to install the log subscriber")?;
+
+    Ok(LoggingGuard {
+        _worker: worker,
+        destination,
+        client_log,
+    })
+}
+
+/// Turns log records into MCP `notifications/message` for a
+/// [`ClientLogTransport`] to send
+struct McpNotificationLayer {
+    sender: mpsc::UnboundedSender<JsonRpcMessage>,
+}
+
+/// The server crate logs every message its transport sends; forwarding
+/// those would produce a new notification for each one sent
+const TRANSPORT_TARGET: &str = "modelcontextprotocol_server";
+
+/// MCP's syslog-style name for a tracing level
+fn mcp_level(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "error",
+        Level::WARN => "warning",
+        Level::INFO => "info",
+        Level::DEBUG | Level::TRACE => "debug",
+    }
+}
+
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+}
+
+impl<S: Subscriber> Layer<S> for McpNotificationLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: LayerContext<'_, S>) {
+        let metadata = event.metadata();
+        if metadata.target().starts_with(TRANSPORT_TARGET) {
+            return;
+        }
+
+        let mut fields = JsonVisitor::default();
+        event.record(&mut fields);
+
+        let notification = JsonRpcMessage::notification(
+            "notifications/message",
+            Some(json!({
+                "level": mcp_level(metadata.level()),
+                "logger": metadata.target(),
+                "data": fields.0,
+            })),
+        );
+        // Nobody is listening once the server has shut down
+        let _ = self.sender.send(notification);
+    }
+}
+
+/// Wraps the server's transport so forwarded log records go out through it.
+/// Every send holds the same lock, so a notification is never written in the
+/// middle of a response.
+#[derive(Clone)]
+pub struct ClientLogTransport {
+    inner: Arc<dyn Transport>,
+    send_lock: Arc<tokio::sync::Mutex<()>>,
+    records: Arc<Mutex<Option<ClientLogReceiver>>>,
+}
+
+impl ClientLogTransport {
+    pub fn new<T: Transport>(inner: T, records: ClientLogReceiver) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            send_lock: Arc::new(tokio::sync::Mutex::new(())),
+            records: Arc::new(Mutex::new(Some(records))),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ClientLogTransport {
+    async fn start(&self, message_tx: mpsc::Sender<JsonRpcMessage>) -> Result<()> {
+        self.inner.start(message_tx).await?;
+
+        // Records logged before the server started are queued and go out now
+        if let Some(mut records) = self.records.lock().unwrap().take() {
+            let transport = self.clone();
+            tokio::spawn(async move {
+                while let Some(record) = records.recv().await {
+                    if transport.send(record).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn send(&self, message: JsonRpcMessage) -> Result<()> {
+        let _guard = self.send_lock.lock().await;
+        self.inner.send(message).await
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    fn box_clone(&self) -> Box<dyn Transport> {
+        Box::new(self.clone())
+    }
+}
@@ -1,71 +1,127 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::Parser;
 use modelcontextprotocol_server::{transport::StdioTransport, ServerBuilder};
-use std::env;
-use std::fs::OpenOptions;
-use std::io;
-use std::path::PathBuf;
-use tracing::{debug, info, Level};
-use tracing_subscriber::fmt;
-
-// Import theater for manifest types
-use theater;
-
-// Import our tool implementations
-mod registry;
-mod templates;
-mod tools;
-mod utils;
-
-use tools::{
-    build_actor::register_build_actor_tool, create_actor::register_create_actor_tool,
+use tracing::{debug, info};
+
+use actor_registry_mcp::cli::{self, Cli, Command};
+use actor_registry_mcp::tools::{
+    add_dependency::register_add_dependency_tool, align_dependency::register_align_dependency_tool,
+    build_actor::register_build_actor_tool, check_compatibility::register_check_compatibility_tool,
+    clone_actor::register_clone_actor_tool, commit_actor::register_commit_actor_tool,
+    create_actor::register_create_actor_tool, dependency_report::register_dependency_report_tool,
+    export_actor::register_export_actor_tool, get_actor_changes::register_get_actor_changes_tool,
+    get_actor_events::register_get_actor_events_tool,
+    get_actor_history::register_get_actor_history_tool,
     get_actor_info::register_get_actor_info_tool, get_actor_path::register_get_actor_path_tool,
-    list_actors::register_list_actors_tool,
+    get_manifest::register_get_manifest_tool,
+    get_registry_config::register_get_registry_config_tool,
+    import_actor::register_import_actor_tool, init_registry::register_init_registry_tool,
+    list_actors::register_list_actors_tool, list_dependencies::register_list_dependencies_tool,
+    list_remote_actors::register_list_remote_actors_tool,
+    list_running_actors::register_list_running_actors_tool,
+    list_versions::register_list_versions_tool, publish_actor::register_publish_actor_tool,
+    release_actor::register_release_actor_tool, remove_dependency::register_remove_dependency_tool,
+    request_actor::register_request_actor_tool, send_message::register_send_message_tool,
+    set_registry_config::register_set_registry_config_tool,
+    smoke_test_actor::register_smoke_test_actor_tool, start_actor::register_start_actor_tool,
+    stop_actor::register_stop_actor_tool, tag_actor_release::register_tag_actor_release_tool,
+    test_actor::register_test_actor_tool,
+    update_actor_from_source::register_update_actor_from_source_tool,
+    update_manifest::register_update_manifest_tool,
 };
+use actor_registry_mcp::{logging, registry};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    let log_file = "/Users/colinrozzi/work/mcp-servers/actor-registry-mcp/actor-registry-mcp.log";
-    let subscriber = fmt::Subscriber::builder()
-        .with_max_level(Level::DEBUG)
-        .with_writer(move || -> Box<dyn io::Write> {
-            Box::new(io::BufWriter::new(
-                OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(log_file)
-                    .unwrap(),
-            ))
-        })
-        .with_ansi(true)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set default tracing subscriber");
-
-    debug!("Logging initialized to {}", log_file);
+    let cli = Cli::parse();
 
-    // Set up registry configuration
-    let registry_path = match env::var("THEATER_ACTORS_PATH") {
-        Ok(path) => PathBuf::from(path),
-        Err(_) => {
-            let default_path = PathBuf::from("/Users/colinrozzi/work/actor-registry");
-            info!(
-                "THEATER_ACTORS_PATH not set, using default path: {:?}",
-                default_path
-            );
-            default_path
+    let command = cli.command.clone().unwrap_or(Command::Serve { http: None });
+    let layers = cli.config_layers();
+    let user_registry = layers.user_registry()?;
+    let from_user_config = cli.registry.is_none() && user_registry.is_some();
+    let roots = cli.registry_roots(user_registry);
+
+    // Logging comes up before the registry, so resolve the config for its
+    // section directly; a broken config is reported once the registry loads
+    let file_config = roots
+        .first()
+        .map(|root| root.join(registry::config::CONFIG_FILE))
+        .and_then(|path| layers.resolve(&path).ok())
+        .map(|resolved| resolved.config.logging)
+        .unwrap_or_default();
+    let mut log_config = cli.logging_config(file_config);
+
+    // Notifications only make sense while an MCP client reads stdout
+    let serving_mcp = matches!(command, Command::Serve { http: None });
+    log_config.forward_to_client &= serving_mcp;
+
+    let mut log_guard = logging::init(&log_config)?;
+    debug!("Logging initialized to {}", log_guard.destination());
+
+    if cli.registry.is_none() && !from_user_config {
+        match roots.first() {
+            Some(root) => info!(
+                "THEATER_ACTORS_PATH not set, using default path: {}",
+                root.display()
+            ),
+            None => {
+                return Err(anyhow!(
+                    "No registry path given; set THEATER_ACTORS_PATH or pass --registry"
+                ))
+            }
         }
-    };
+    }
+
+    if let Some(result) = command.init(&roots, &layers)? {
+        if !cli::print_result(&result) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Set up registry configuration
+    let registry = registry::Registry::with_config(roots, layers)?;
+    debug!("Registry roots: {:?}", registry.roots());
+
+    match command {
+        Command::Serve { http: None } => serve_mcp(registry, log_guard.take_client_log()).await,
+        Command::Serve { http: Some(addr) } => {
+            let addr = addr.unwrap_or_else(|| registry.config().server_addr().to_string());
+            let server = registry::server::RegistryServer::bind(registry, &addr)?.spawn()?;
+            println!("Serving actor registry on {}", server.url());
 
-    // Initialize the registry (shared state)
-    let registry = registry::Registry::new(registry_path)?;
+            tokio::signal::ctrl_c().await?;
+            info!("Stopping actor registry server");
+            server.shutdown();
+            Ok(())
+        }
+        command => {
+            let result = command.run(&registry)?;
+            if !cli::print_result(&result) {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+    }
+}
 
+/// Runs the MCP server over stdio until the client disconnects. With
+/// `client_log`, log records are forwarded to the client as well.
+async fn serve_mcp(
+    registry: registry::Registry,
+    client_log: Option<logging::ClientLogReceiver>,
+) -> Result<()> {
     info!("Starting Actor Registry MCP server");
-    debug!("Registry path: {:?}", registry.path());
 
     // Create server builder
-    let mut server_builder =
-        ServerBuilder::new("theater-actor-registry", "0.1.0").with_transport(StdioTransport::new());
+    let server_builder = ServerBuilder::new("theater-actor-registry", "0.1.0");
+    let mut server_builder = match client_log {
+        Some(records) => server_builder.with_transport(logging::ClientLogTransport::new(
+            StdioTransport::new(),
+            records,
+        )),
+        None => server_builder.with_transport(StdioTransport::new()),
+    };
 
     // Register tools
     server_builder = register_create_actor_tool(server_builder, registry.clone());
@@ -73,6 +129,37 @@ async fn main() -> Result<()> {
     server_builder = register_build_actor_tool(server_builder, registry.clone());
     server_builder = register_get_actor_info_tool(server_builder, registry.clone());
     server_builder = register_get_actor_path_tool(server_builder, registry.clone());
+    server_builder = register_check_compatibility_tool(server_builder, registry.clone());
+    server_builder = register_get_manifest_tool(server_builder, registry.clone());
+    server_builder = register_update_manifest_tool(server_builder, registry.clone());
+    server_builder = register_list_dependencies_tool(server_builder, registry.clone());
+    server_builder = register_add_dependency_tool(server_builder, registry.clone());
+    server_builder = register_remove_dependency_tool(server_builder, registry.clone());
+    server_builder = register_dependency_report_tool(server_builder, registry.clone());
+    server_builder = register_align_dependency_tool(server_builder, registry.clone());
+    server_builder = register_release_actor_tool(server_builder, registry.clone());
+    server_builder = register_list_versions_tool(server_builder, registry.clone());
+    server_builder = register_export_actor_tool(server_builder, registry.clone());
+    server_builder = register_import_actor_tool(server_builder, registry.clone());
+    server_builder = register_list_remote_actors_tool(server_builder, registry.clone());
+    server_builder = register_publish_actor_tool(server_builder, registry.clone());
+    server_builder = register_get_registry_config_tool(server_builder, registry.clone());
+    server_builder = register_set_registry_config_tool(server_builder, registry.clone());
+    server_builder = register_init_registry_tool(server_builder, registry.clone());
+    server_builder = register_get_actor_changes_tool(server_builder, registry.clone());
+    server_builder = register_commit_actor_tool(server_builder, registry.clone());
+    server_builder = register_get_actor_history_tool(server_builder, registry.clone());
+    server_builder = register_tag_actor_release_tool(server_builder, registry.clone());
+    server_builder = register_clone_actor_tool(server_builder, registry.clone());
+    server_builder = register_update_actor_from_source_tool(server_builder, registry.clone());
+    server_builder = register_start_actor_tool(server_builder, registry.clone());
+    server_builder = register_stop_actor_tool(server_builder, registry.clone());
+    server_builder = register_list_running_actors_tool(server_builder, registry.clone());
+    server_builder = register_send_message_tool(server_builder, registry.clone());
+    server_builder = register_request_actor_tool(server_builder, registry.clone());
+    server_builder = register_get_actor_events_tool(server_builder, registry.clone());
+    server_builder = register_smoke_test_actor_tool(server_builder, registry.clone());
+    server_builder = register_test_actor_tool(server_builder, registry.clone());
 
     // Build the server
     let server = server_builder.build()?;
@@ -4,35 +4,63 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::SystemTime;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 // Import Theater types
 use theater::config::{HandlerConfig, ManifestConfig, RuntimeHostConfig};
 
-use crate::templates::templates;
+use super::compat;
+use super::document::TomlDocument;
+use super::manifest::ManifestDocument;
+use crate::templates::{templates, wit_world_name};
 use crate::utils;
 
 // Use Theater's ManifestConfig instead of our own ActorManifest
 pub type ActorManifest = ManifestConfig;
 
+// Keys we don't model (features, profiles, workspace settings, ...) are kept
+// in `extra` so that reading and writing a Cargo.toml never drops them
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActorCargoConfig {
     pub package: CargoPackage,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub lib: Option<CargoLib>,
-    pub dependencies: std::collections::HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub dependencies: std::collections::BTreeMap<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CargoPackage {
     pub name: String,
     pub version: String,
+    #[serde(default = "default_edition")]
     pub edition: String,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+fn default_edition() -> String {
+    "2015".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CargoLib {
-    #[serde(rename = "crate-type")]
+    #[serde(rename = "crate-type", default)]
     pub crate_type: Vec<String>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+impl ActorCargoConfig {
+    /// Writes the config back to `path`, preserving the formatting and
+    /// comments of the existing file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut doc = TomlDocument::load_or_default(path)?;
+        doc.merge_from(self)?;
+        doc.save()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,22 +153,9 @@ impl Actor {
             None
         };
 
-        // For now, we use a simple build status check
-        // In the future, this would be stored in a build_info.json file
-        let build_status = if let Some(m) = &manifest {
-            let component_path = &m.component;
-            if !component_path.is_empty() && Path::new(component_path).exists() {
-                BuildStatus::Success
-            } else {
-                BuildStatus::NotBuilt
-            }
-        } else {
-            BuildStatus::NotBuilt
-        };
-
         let build_info = BuildInfo {
             last_build_time: None, // Would be populated from metadata
-            build_status,
+            build_status: BuildStatus::NotBuilt,
             component_hash: None,
             build_log: None,
             build_duration: None,
@@ -148,31 +163,117 @@ impl Actor {
             error_message: None,
         };
 
-        Ok(Self {
+        let mut actor = Self {
             name,
             path,
             manifest,
             cargo_config,
             build_info,
-        })
+        };
+
+        // For now, we use a simple build status check
+        // In the future, this would be stored in a build_info.json file.
+        // The component path is resolved against the actor directory, so
+        // release snapshots with relative paths are seen as built.
+        if compat::component_path(&actor).map_or(false, |p| p.exists()) {
+            actor.build_info.build_status = BuildStatus::Success;
+        }
+
+        Ok(actor)
     }
 
-    pub fn create<P: AsRef<Path>>(name: &str, path: P, template: Option<&str>) -> Result<Self> {
+    /// Creates an actor from a template at `path`, copying the WIT files in
+    /// `wit_source` into its `wit` directory. The files are written to a
+    /// staging directory next to `path` and renamed into place, so a failure
+    /// leaves nothing behind.
+    pub fn create<P: AsRef<Path>>(
+        name: &str,
+        path: P,
+        template: Option<&str>,
+        wit_source: Option<&Path>,
+    ) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
 
         if path.exists() {
             return Err(anyhow!("Actor path already exists: {:?}", path));
         }
 
-        // Create the base directory structure
-        fs::create_dir_all(&path)?;
-        fs::create_dir_all(path.join("src"))?;
-        fs::create_dir_all(path.join("wit"))?;
-
         // Select template (default to "basic")
         let template_name = template.unwrap_or("basic");
         debug!("Using template '{}' for actor '{}'", template_name, name);
 
+        let (lib_rs_template, wit_template) = match template_name {
+            "basic" => (templates::BASIC_LIB_RS, templates::BASIC_WIT),
+            "http" => (templates::HTTP_LIB_RS, templates::HTTP_WIT),
+            "supervisor" => (templates::SUPERVISOR_LIB_RS, templates::SUPERVISOR_WIT),
+            _ => return Err(anyhow!("Unknown template: {}", template_name)),
+        };
+
+        let parent = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(_)) => parent,
+            _ => return Err(anyhow!("Invalid actor path: {:?}", path)),
+        };
+
+        // Namespace directories created for this actor are removed again if
+        // creating it fails
+        let created_parent = parent
+            .ancestors()
+            .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            .last()
+            .map(Path::to_path_buf);
+        fs::create_dir_all(parent)?;
+
+        // Stage next to the target so the final move is a rename on one
+        // filesystem. The staging directory is removed when dropped, which
+        // only matters if something fails before the rename.
+        let result = tempfile::Builder::new()
+            .prefix(".create-")
+            .tempdir_in(parent)
+            .with_context(|| format!("Failed to create a staging directory in {:?}", parent))
+            .and_then(|staging| {
+                Self::write_files(
+                    name,
+                    staging.path(),
+                    template_name,
+                    lib_rs_template,
+                    wit_template,
+                    wit_source,
+                )?;
+                move_into_place(staging.path(), &path)
+            });
+
+        if let Err(e) = result {
+            if let Some(created_parent) = created_parent {
+                let mut dir = parent.to_path_buf();
+                while dir.starts_with(&created_parent) && fs::remove_dir(&dir).is_ok() {
+                    if !dir.pop() {
+                        break;
+                    }
+                }
+            }
+            return Err(e);
+        }
+
+        info!("Actor '{}' created at {:?}", name, path);
+
+        // Return the created actor
+        Self::from_path(path)
+    }
+
+    /// Writes the files of a new actor into `path`
+    fn write_files(
+        name: &str,
+        path: &Path,
+        template_name: &str,
+        lib_rs_template: &str,
+        wit_template: &str,
+        wit_source: Option<&Path>,
+    ) -> Result<()> {
+        // Create the base directory structure
+        fs::create_dir_all(path)?;
+        fs::create_dir_all(path.join("src"))?;
+        fs::create_dir_all(path.join("wit"))?;
+
         // Create manifest.toml using Theater's ManifestConfig structure
         let manifest = ManifestConfig {
             name: name.to_string(),
@@ -189,8 +290,7 @@ impl Actor {
             handlers: vec![HandlerConfig::Runtime(RuntimeHostConfig {})],
         };
 
-        let manifest_content = toml::to_string(&manifest)?;
-        fs::write(path.join("manifest.toml"), manifest_content)?;
+        ManifestDocument::create(path.join("manifest.toml"), &manifest)?.save()?;
 
         // Create Cargo.toml
         let cargo_config = ActorCargoConfig {
@@ -198,9 +298,11 @@ impl Actor {
                 name: name.to_string(),
                 version: "0.1.0".to_string(),
                 edition: "2021".to_string(),
+                extra: toml::Table::new(),
             },
             lib: Some(CargoLib {
                 crate_type: vec!["cdylib".to_string()],
+                extra: toml::Table::new(),
             }),
             dependencies: [
                 (
@@ -215,33 +317,26 @@ impl Actor {
             ]
             .into_iter()
             .collect(),
+            extra: toml::Table::new(),
         };
 
-        let cargo_content = toml::to_string(&cargo_config)?;
-        fs::write(path.join("Cargo.toml"), cargo_content)?;
+        TomlDocument::from_serializable(path.join("Cargo.toml"), &cargo_config)?.save()?;
 
         // Create a basic lib.rs file based on the template
-        let lib_rs_content = match template_name {
-            "basic" => templates::BASIC_LIB_RS,
-            _ => return Err(anyhow!("Unknown template: {}", template_name)),
-        };
-
-        let lib_rs_content = lib_rs_content.replace("{{actor_name}}", name);
+        let lib_rs_content = lib_rs_template.replace("{{actor_name}}", name);
         fs::write(path.join("src").join("lib.rs"), lib_rs_content)?;
 
         // Create the WIT world based on the template
-        let wit_content = match template_name {
-            "basic" => templates::BASIC_WIT,
-            _ => return Err(anyhow!("Unknown template: {}", template_name)),
-        };
-
-        let wit_content = wit_content.replace("{{actor_name}}", name);
+        let wit_content = wit_template
+            .replace("{{world_name}}", &wit_world_name(name))
+            .replace("{{actor_name}}", name);
         fs::write(path.join("wit").join("world.wit"), wit_content)?;
 
-        // copy the files from /Users/colinrozzi/work/theater/wit to the actor's wit directory
-        let wit_dir = Path::new("/Users/colinrozzi/work/theater/wit");
+        // Copy the shared WIT files into the actor's wit directory
         let actor_wit_dir = path.join("wit");
-        if wit_dir.exists() {
+        if let Some(wit_dir) = wit_source.filter(|dir| !dir.is_dir()) {
+            warn!("WIT source {:?} is not a directory, skipping", wit_dir);
+        } else if let Some(wit_dir) = wit_source {
             for entry in fs::read_dir(wit_dir)? {
                 let entry = entry?;
                 let file_name = entry.file_name();
@@ -256,7 +351,7 @@ impl Actor {
 
         // Create a README.md
         let readme_content = format!(
-            "# {}\n\nA Theater actor created from the {} template.\n\n## Building\n\nTo build the actor:\n\n```bash\ncargo build --target wasm32-unknown-unknown --release\n```\n\n## Running\n\nTo run the actor with Theater:\n\n```bash\ntheater start manifest.toml\n```\n",
+            "# {}\n\nA Theater actor created from the {} template.\n\n## Building\n\nTo build the actor:\n\n```bash\ncargo build --target wasm32-unknown-unknown --release\n```\n\n## Running\n\nTo run the actor with Theater:\n\n```bash\ntheater start manifest.toml\n```\n\nor, from the actor registry, with the `start-actor` tool.\n",
             name, template_name
         );
         fs::write(path.join("README.md"), readme_content)?;
@@ -265,10 +360,7 @@ impl Actor {
         let flake_nix_content = templates::FLAKE_NIX.replace("{{actor_name}}", name);
         fs::write(path.join("flake.nix"), flake_nix_content)?;
 
-        info!("Actor '{}' created at {:?}", name, path);
-
-        // Return the created actor
-        Self::from_path(path)
+        Ok(())
     }
 
     // This method is no longer used as we now use the `theater build` command directly
@@ -395,7 +487,7 @@ impl Actor {
             // Write output to log file
             let mut log_content = format!("=== Build Log for {} ===\n", self.name);
             log_content.push_str(&format!("Date: {}\n", timestamp));
-            log_content.push_str(&format!("Builder: nix\n"));
+            log_content.push_str("Builder: nix\n");
             log_content.push_str(&format!("Duration: {} seconds\n\n", build_duration));
 
             log_content.push_str("=== STDOUT ===\n");
@@ -471,12 +563,11 @@ impl Actor {
         }
 
         // Update the manifest.toml with the new component path
-        if let Some(mut manifest) = self.manifest.clone() {
-            manifest.component = wasm_path.clone();
-
-            match toml::to_string(&manifest) {
-                Ok(manifest_content) => {
-                    if let Err(e) = fs::write(self.path.join("manifest.toml"), manifest_content) {
+        if self.manifest.is_some() {
+            match ManifestDocument::load(self.path.join("manifest.toml")) {
+                Ok(mut manifest) => {
+                    manifest.set_component(&wasm_path);
+                    if let Err(e) = manifest.save() {
                         error!("Failed to write manifest.toml: {}", e);
                         // Continue anyway to ensure we record build success
                     } else {
@@ -484,7 +575,7 @@ impl Actor {
                     }
                 }
                 Err(e) => {
-                    error!("Failed to serialize manifest: {}", e);
+                    error!("Failed to load manifest: {}", e);
                     // Continue anyway to ensure we record build success
                 }
             }
@@ -500,6 +591,37 @@ impl Actor {
         }
 
         info!("Build completed successfully");
-        return Ok(());
+        Ok(())
+    }
+}
+
+/// Renames the staged actor to `path`. The target is claimed with
+/// `create_dir` first, which fails if anything already exists there, so the
+/// rename only ever replaces that empty directory of our own.
+///
+/// The staging directory is owner-only, so the actor takes over the
+/// permissions of the claimed directory, i.e. the process default.
+fn move_into_place(staging: &Path, path: &Path) -> Result<()> {
+    fs::create_dir(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AlreadyExists {
+            anyhow!("Actor path already exists: {:?}", path)
+        } else {
+            anyhow!(e).context(format!("Failed to create {:?}", path))
+        }
+    })?;
+
+    let permissions = match fs::metadata(path) {
+        Ok(metadata) => metadata.permissions(),
+        Err(e) => {
+            let _ = fs::remove_dir(path);
+            return Err(e).with_context(|| format!("Failed to read permissions of {:?}", path));
+        }
+    };
+
+    if let Err(e) = fs::rename(staging, path) {
+        let _ = fs::remove_dir(path);
+        return Err(e).with_context(|| format!("Failed to move new actor to {:?}", path));
     }
+    fs::set_permissions(path, permissions)
+        .with_context(|| format!("Failed to set permissions of {:?}", path))
 }
@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::config::DEFAULT_THEATER_BINARY;
+
+/// Options forwarded to `theater build`
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    pub release: bool,
+    pub clean: bool,
+    pub force: bool,
+    pub verbose: bool,
+    /// Theater CLI to run, `theater` on the PATH if unset
+    pub theater_binary: Option<PathBuf>,
+}
+
+impl BuildOptions {
+    /// Reads the build flags from tool arguments
+    pub fn from_args(args: &serde_json::Value) -> Self {
+        let flag = |key: &str| args.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+
+        Self {
+            release: flag("release"),
+            clean: flag("clean"),
+            force: flag("force"),
+            verbose: flag("verbose"),
+            theater_binary: None,
+        }
+    }
+
+    pub fn with_theater_binary(mut self, binary: PathBuf) -> Self {
+        self.theater_binary = Some(binary);
+        self
+    }
+}
+
+/// Prepares the `theater build` command for the actor at `actor_path`
+pub fn theater_build_command(actor_path: &Path, options: &BuildOptions) -> Command {
+    let binary = options
+        .theater_binary
+        .as_deref()
+        .unwrap_or(Path::new(DEFAULT_THEATER_BINARY));
+    let mut cmd = Command::new(binary);
+    cmd.arg("build");
+
+    if options.release {
+        cmd.arg("--release");
+    }
+    if options.clean {
+        cmd.arg("--clean");
+    }
+    if options.force {
+        cmd.arg("--force");
+    }
+    if options.verbose {
+        cmd.arg("--verbose");
+    }
+
+    // Add the actor path as the final argument
+    cmd.arg(actor_path);
+    cmd
+}
@@ -0,0 +1,502 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use super::actor::Actor;
+use super::compat;
+use super::manifest::ManifestDocument;
+use super::namespace;
+
+/// Version of the bundle layout written by `export_actor`
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Metadata file at the root of every bundle
+pub const BUNDLE_METADATA_FILE: &str = "bundle.toml";
+
+/// Checksum listing in `sha256sum` format
+pub const CHECKSUMS_FILE: &str = "CHECKSUMS.sha256";
+
+/// Directory inside the bundle (and the imported actor) holding the component
+pub const COMPONENT_DIR: &str = "component";
+
+/// Upper bound on the total unpacked size of a bundle. Bundles are read into
+/// memory, and a few compressed megabytes can expand to far more.
+pub const MAX_UNPACKED_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Directories that are build output or local state and never exported
+const EXCLUDED_DIRS: &[&str] = &["target", ".build_info", "releases", ".git", "result"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleMetadata {
+    pub format_version: u32,
+    pub name: String,
+    pub version: Option<String>,
+    pub component: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub include_component: bool,
+    pub checksums: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportInfo {
+    pub path: PathBuf,
+    pub files: usize,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Collision {
+    /// Refuse to import over an existing actor
+    #[default]
+    Error,
+    /// Import under the first free `<name>-<n>` name
+    Rename,
+    /// Replace the existing actor
+    Overwrite,
+}
+
+impl std::str::FromStr for Collision {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "error" => Ok(Collision::Error),
+            "rename" => Ok(Collision::Rename),
+            "overwrite" => Ok(Collision::Overwrite),
+            _ => Err(anyhow!(
+                "Unknown collision strategy '{}' (expected error, rename or overwrite)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Name to import the actor under, defaults to the bundled name
+    pub name: Option<String>,
+    pub on_collision: Collision,
+    /// Import bundles that have no checksum listing to verify them against
+    pub allow_unverified: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub verified: bool,
+    pub component: Option<PathBuf>,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Collects the files of an actor that belong in a bundle, keyed by their
+/// path relative to the actor directory so the order is deterministic
+fn collect_sources(actor: &Actor) -> Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+
+    let walker = WalkDir::new(&actor.path)
+        .min_depth(1)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.file_type().is_dir()
+                && e.file_name()
+                    .to_str()
+                    .map(|n| EXCLUDED_DIRS.contains(&n))
+                    .unwrap_or(false))
+        });
+
+    for entry in walker {
+        let entry = entry?;
+        // Symlinks (e.g. nix `result` links) point outside the actor
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(&actor.path)?;
+        let relative = relative
+            .to_str()
+            .ok_or_else(|| anyhow!("Non UTF-8 path in actor: {:?}", relative))?
+            .replace('\\', "/");
+        files.insert(relative, entry.path().to_path_buf());
+    }
+
+    Ok(files)
+}
+
+fn append_file<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+    executable: bool,
+) -> Result<()> {
+    // Normalize everything that would make the archive depend on the machine
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(if executable { 0o755 } else { 0o644 });
+    header.set_mtime(0);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_entry_type(tar::EntryType::Regular);
+    builder.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+/// Writes a deterministic `.tar.zst` bundle of the actor to `dest`
+pub fn export_actor(actor: &Actor, dest: &Path, options: &ExportOptions) -> Result<ExportInfo> {
+    debug!("Exporting actor '{}' to {:?}", actor.name, dest);
+
+    let mut entries: BTreeMap<String, (Vec<u8>, bool)> = BTreeMap::new();
+    for (relative, path) in collect_sources(actor)? {
+        let data = fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        entries.insert(relative, (data, is_executable(&path)));
+    }
+
+    let component = if options.include_component {
+        let component = compat::component_path(actor)?;
+        let data = fs::read(&component)
+            .with_context(|| format!("Failed to read built component {}", component.display()))?;
+        let bundled = format!(
+            "{}/{}.wasm",
+            COMPONENT_DIR,
+            namespace::base_name(&actor.name)
+        );
+        entries.insert(bundled.clone(), (data, false));
+        Some(bundled)
+    } else {
+        None
+    };
+
+    let metadata = BundleMetadata {
+        format_version: BUNDLE_FORMAT_VERSION,
+        name: actor.name.clone(),
+        version: actor.manifest.as_ref().map(|m| m.version.clone()),
+        component,
+    };
+
+    entries.insert(
+        BUNDLE_METADATA_FILE.to_string(),
+        (toml::to_string(&metadata)?.into_bytes(), false),
+    );
+
+    // Every file is listed, metadata included, so the listing itself is the
+    // only file an importer takes on trust
+    if options.checksums {
+        let checksums = entries
+            .iter()
+            .map(|(path, (data, _))| format!("{}  {}\n", sha256_hex(data), path))
+            .collect::<String>();
+        entries.insert(CHECKSUMS_FILE.to_string(), (checksums.into_bytes(), false));
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::File::create(dest).with_context(|| format!("Failed to create {:?}", dest))?;
+    let encoder = zstd::stream::write::Encoder::new(file, 0)?;
+    let mut builder = tar::Builder::new(encoder);
+
+    for (path, (data, executable)) in &entries {
+        append_file(&mut builder, path, data, *executable)?;
+    }
+
+    builder.into_inner()?.finish()?;
+
+    let bytes = fs::read(dest)?;
+    info!("Exported actor '{}' to {:?}", actor.name, dest);
+
+    Ok(ExportInfo {
+        path: dest.to_path_buf(),
+        files: entries.len(),
+        size: bytes.len() as u64,
+        sha256: sha256_hex(&bytes),
+    })
+}
+
+/// Reads every file of a bundle into memory, rejecting entries that would
+/// escape the bundle root
+fn read_bundle(bundle: &Path) -> Result<BTreeMap<String, (Vec<u8>, u32)>> {
+    let file = fs::File::open(bundle).with_context(|| format!("Failed to open {:?}", bundle))?;
+    let decoder = zstd::stream::read::Decoder::new(file)?;
+    let mut archive = tar::Archive::new(decoder);
+
+    let mut files = BTreeMap::new();
+    let mut total: u64 = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            continue;
+        }
+
+        let path = entry.path()?.into_owned();
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(anyhow!("Bundle contains an unsafe path: {:?}", path));
+        }
+        let path = path
+            .to_str()
+            .ok_or_else(|| anyhow!("Bundle contains a non UTF-8 path: {:?}", path))?
+            .to_string();
+
+        // Only keep the executable bit, as the exporter does, so a bundle
+        // can't create setuid or world-writable files
+        let mode = match entry.header().mode() {
+            Ok(mode) if mode & 0o111 != 0 => 0o755,
+            _ => 0o644,
+        };
+        let mut data = Vec::new();
+        (&mut entry)
+            .take(MAX_UNPACKED_SIZE - total + 1)
+            .read_to_end(&mut data)?;
+        total += data.len() as u64;
+        if total > MAX_UNPACKED_SIZE {
+            return Err(anyhow!(
+                "Bundle {:?} unpacks to more than {} bytes",
+                bundle,
+                MAX_UNPACKED_SIZE
+            ));
+        }
+        files.insert(path, (data, mode));
+    }
+
+    Ok(files)
+}
+
+/// Checks every file against the bundle's checksum listing
+fn verify_checksums(files: &BTreeMap<String, (Vec<u8>, u32)>, listing: &str) -> Result<()> {
+    let mut expected = BTreeMap::new();
+    for line in listing.lines().filter(|l| !l.trim().is_empty()) {
+        let (hash, path) = line
+            .split_once("  ")
+            .ok_or_else(|| anyhow!("Malformed checksum line: {}", line))?;
+        expected.insert(path.to_string(), hash.to_string());
+    }
+
+    for (path, (data, _)) in files {
+        if path == CHECKSUMS_FILE {
+            continue;
+        }
+        match expected.remove(path) {
+            Some(hash) if hash == sha256_hex(data) => {}
+            Some(_) => return Err(anyhow!("Checksum mismatch for {}", path)),
+            None => return Err(anyhow!("{} is not listed in {}", path, CHECKSUMS_FILE)),
+        }
+    }
+
+    if let Some(path) = expected.keys().next() {
+        return Err(anyhow!(
+            "{} is listed in {} but missing",
+            path,
+            CHECKSUMS_FILE
+        ));
+    }
+
+    Ok(())
+}
+
+/// Picks the directory name to import under according to the collision
+/// strategy
+fn import_target(root: &Path, name: &str, on_collision: Collision) -> Result<(String, PathBuf)> {
+    let path = root.join(name);
+    if !path.exists() {
+        return Ok((name.to_string(), path));
+    }
+
+    match on_collision {
+        Collision::Error => Err(anyhow!("Actor '{}' already exists at {:?}", name, path)),
+        // Only ever replace an actor, never a namespace or stray directory
+        Collision::Overwrite if path.join("manifest.toml").is_file() => {
+            Ok((name.to_string(), path))
+        }
+        Collision::Overwrite => Err(anyhow!(
+            "{:?} exists but is not an actor; refusing to overwrite it",
+            path
+        )),
+        Collision::Rename => (2..)
+            .map(|n| format!("{}-{}", name, n))
+            .find(|candidate| !root.join(candidate).exists())
+            .map(|candidate| {
+                let path = root.join(&candidate);
+                (candidate, path)
+            })
+            .ok_or_else(|| anyhow!("No free name for actor '{}'", name)),
+    }
+}
+
+/// Unpacks a bundle into `root`, verifying its checksums and pointing the
+/// manifest at the bundled component (if any)
+pub fn import_bundle(bundle: &Path, root: &Path, options: &ImportOptions) -> Result<ImportInfo> {
+    debug!("Importing bundle {:?} into {:?}", bundle, root);
+
+    let mut files = read_bundle(bundle)?;
+
+    let metadata: BundleMetadata = files
+        .get(BUNDLE_METADATA_FILE)
+        .ok_or_else(|| {
+            anyhow!(
+                "{:?} is not an actor bundle (no {})",
+                bundle,
+                BUNDLE_METADATA_FILE
+            )
+        })
+        .and_then(|(data, _)| Ok(toml::from_str(std::str::from_utf8(data)?)?))?;
+
+    if metadata.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Bundle format version {} is newer than supported version {}",
+            metadata.format_version,
+            BUNDLE_FORMAT_VERSION
+        ));
+    }
+
+    let verified = match files.get(CHECKSUMS_FILE) {
+        Some((listing, _)) => {
+            verify_checksums(&files, std::str::from_utf8(listing)?)?;
+            true
+        }
+        None if options.allow_unverified => {
+            warn!(
+                "Bundle {:?} has no checksums, skipping verification",
+                bundle
+            );
+            false
+        }
+        None => {
+            return Err(anyhow!(
+                "Bundle {:?} has no {} to verify it against; allow unverified bundles to import it anyway",
+                bundle,
+                CHECKSUMS_FILE
+            ))
+        }
+    };
+
+    if !files.contains_key("manifest.toml") {
+        return Err(anyhow!("Bundle {:?} has no manifest.toml", bundle));
+    }
+
+    // The bundled name ends up in the manifest and, without a name override,
+    // in the target path, so it must be a valid actor name either way
+    namespace::validate_name(&metadata.name)
+        .with_context(|| format!("Bundle {:?} has an invalid actor name", bundle))?;
+    let requested = options.name.as_deref().unwrap_or(&metadata.name);
+    namespace::validate_name(requested)?;
+    let (name, target) = import_target(root, requested, options.on_collision)?;
+
+    // Stage next to the target so the final move is a rename on one filesystem
+    let staging = root.join(format!(
+        ".import-{}-{}",
+        name.replace(namespace::SEPARATOR, "-"),
+        std::process::id()
+    ));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+
+    files.remove(BUNDLE_METADATA_FILE);
+    files.remove(CHECKSUMS_FILE);
+
+    // The component path is written into the imported manifest, so it must
+    // name one of the bundled files
+    if let Some(component) = &metadata.component {
+        let relative = Path::new(component)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !relative || !files.contains_key(component) {
+            return Err(anyhow!(
+                "Bundle {:?} names component {:?}, which is not a file in the bundle",
+                bundle,
+                component
+            ));
+        }
+    }
+
+    let unpack = || -> Result<Option<PathBuf>> {
+        for (path, (data, mode)) in &files {
+            let dest = staging.join(path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&dest, data)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&dest, fs::Permissions::from_mode(*mode))?;
+            }
+            #[cfg(not(unix))]
+            let _ = mode;
+        }
+
+        // The original component path points at the exporting machine
+        let component = metadata.component.as_ref().map(|c| target.join(c));
+        let mut manifest = ManifestDocument::load(staging.join("manifest.toml"))?;
+        match &component {
+            Some(component) => manifest.set_component(&component.to_string_lossy()),
+            None => manifest.set_component(""),
+        }
+        if name != metadata.name {
+            manifest.set_name(namespace::base_name(&name));
+        }
+        manifest.save()?;
+
+        if target.exists() {
+            if !target.join("manifest.toml").is_file() {
+                return Err(anyhow!(
+                    "{:?} exists but is not an actor; refusing to overwrite it",
+                    target
+                ));
+            }
+            info!("Overwriting existing actor at {:?}", target);
+            fs::remove_dir_all(&target)?;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&staging, &target)
+            .with_context(|| format!("Failed to move imported actor to {:?}", target))?;
+
+        Ok(component)
+    };
+
+    match unpack() {
+        Ok(component) => {
+            info!("Imported actor '{}' to {:?}", name, target);
+            Ok(ImportInfo {
+                name,
+                path: target,
+                verified,
+                component,
+            })
+        }
+        Err(e) => {
+            if staging.exists() {
+                let _ = fs::remove_dir_all(&staging);
+            }
+            Err(e)
+        }
+    }
+}
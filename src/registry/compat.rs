@@ -0,0 +1,313 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+use wasmparser::{Encoding, Parser, Payload};
+
+use theater::config::HandlerConfig;
+
+use super::actor::Actor;
+
+/// Package prefix of the interfaces provided by Theater handlers
+pub const THEATER_PACKAGE: &str = "ntwk:theater";
+
+/// Interfaces in the Theater package that only define types and are
+/// satisfied without any handler
+const TYPE_ONLY_INTERFACES: &[&str] = &["types", "http-types", "websocket-types"];
+
+/// Maps a handler type (as written in manifest.toml) to the Theater
+/// interfaces it provides to the component (imports) and the interfaces
+/// it drives on the component (exports)
+struct HandlerInterfaces {
+    handler: &'static str,
+    imports: &'static [&'static str],
+    exports: &'static [&'static str],
+}
+
+const HANDLER_INTERFACES: &[HandlerInterfaces] = &[
+    HandlerInterfaces {
+        handler: "runtime",
+        imports: &["runtime"],
+        exports: &[],
+    },
+    HandlerInterfaces {
+        handler: "message-server",
+        imports: &["message-server-host"],
+        exports: &["message-server-client"],
+    },
+    HandlerInterfaces {
+        handler: "http-client",
+        imports: &["http-client"],
+        exports: &[],
+    },
+    HandlerInterfaces {
+        handler: "http-framework",
+        imports: &["http-framework"],
+        exports: &["http-handlers"],
+    },
+    HandlerInterfaces {
+        handler: "filesystem",
+        imports: &["filesystem"],
+        exports: &[],
+    },
+    HandlerInterfaces {
+        handler: "supervisor",
+        imports: &["supervisor"],
+        exports: &["supervisor-handlers"],
+    },
+    HandlerInterfaces {
+        handler: "store",
+        imports: &["store"],
+        exports: &[],
+    },
+    HandlerInterfaces {
+        handler: "timing",
+        imports: &["timing"],
+        exports: &[],
+    },
+    HandlerInterfaces {
+        handler: "process",
+        imports: &["process"],
+        exports: &["process-handlers"],
+    },
+    HandlerInterfaces {
+        handler: "environment",
+        imports: &["environment"],
+        exports: &[],
+    },
+    HandlerInterfaces {
+        handler: "random",
+        imports: &["random"],
+        exports: &[],
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingHandler {
+    pub handler: String,
+    pub interface: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatibilityReport {
+    pub component: PathBuf,
+    pub imports: Vec<String>,
+    pub exports: Vec<String>,
+    pub configured_handlers: Vec<String>,
+    pub missing_handlers: Vec<MissingHandler>,
+    pub unused_handlers: Vec<String>,
+    pub unknown_imports: Vec<String>,
+}
+
+impl CompatibilityReport {
+    /// A component is compatible when every imported Theater interface has
+    /// a handler configured; unused handlers are only reported as warnings
+    pub fn is_compatible(&self) -> bool {
+        self.missing_handlers.is_empty() && self.unknown_imports.is_empty()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("Component: {}\n", self.component.display());
+
+        text.push_str("\n### Imports\n\n");
+        for import in &self.imports {
+            text.push_str(&format!("- {}\n", import));
+        }
+
+        text.push_str("\n### Configured Handlers\n\n");
+        for handler in &self.configured_handlers {
+            text.push_str(&format!("- {}\n", handler));
+        }
+
+        if !self.missing_handlers.is_empty() {
+            text.push_str("\n### Missing Handlers (actor will fail to start)\n\n");
+            for missing in &self.missing_handlers {
+                text.push_str(&format!(
+                    "- {} (required by import {})\n",
+                    missing.handler, missing.interface
+                ));
+            }
+        }
+
+        if !self.unknown_imports.is_empty() {
+            text.push_str("\n### Unknown Theater Imports\n\n");
+            for import in &self.unknown_imports {
+                text.push_str(&format!("- {}\n", import));
+            }
+        }
+
+        if !self.unused_handlers.is_empty() {
+            text.push_str("\n### Unused Handlers\n\n");
+            for handler in &self.unused_handlers {
+                text.push_str(&format!("- {}\n", handler));
+            }
+        }
+
+        if self.is_compatible() {
+            text.push_str("\nResult: compatible\n");
+        } else {
+            text.push_str("\nResult: incompatible\n");
+        }
+
+        text
+    }
+}
+
+/// Returns the handler type name of a handler config, i.e. the `type` key
+/// it is written with in manifest.toml
+pub fn handler_type(handler: &HandlerConfig) -> Option<String> {
+    serde_json::to_value(handler)
+        .ok()?
+        .get("type")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Reads the top-level imports and exports of a WebAssembly component
+pub fn component_interfaces<P: AsRef<Path>>(path: P) -> Result<(Vec<String>, Vec<String>)> {
+    let path = path.as_ref();
+    let bytes =
+        fs::read(path).with_context(|| format!("Failed to read component from {:?}", path))?;
+
+    let mut imports = Vec::new();
+    let mut exports = Vec::new();
+
+    // Nested modules and components show up in the payload stream as well,
+    // so track the nesting depth and only record the outermost items
+    let mut depth = 0usize;
+    for payload in Parser::new(0).parse_all(&bytes) {
+        let payload = payload.with_context(|| format!("Failed to parse component {:?}", path))?;
+        match payload {
+            Payload::Version { encoding, .. } if depth == 0 && encoding != Encoding::Component => {
+                return Err(anyhow!(
+                    "{:?} is a core WebAssembly module, not a component",
+                    path
+                ));
+            }
+            Payload::ModuleSection { .. } | Payload::ComponentSection { .. } => depth += 1,
+            Payload::End(_) => depth = depth.saturating_sub(1),
+            Payload::ComponentImportSection(reader) if depth == 0 => {
+                for import in reader {
+                    imports.push(import?.name.0.to_string());
+                }
+            }
+            Payload::ComponentExportSection(reader) if depth == 0 => {
+                for export in reader {
+                    exports.push(export?.name.0.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    debug!(
+        "Component {:?} has {} imports and {} exports",
+        path,
+        imports.len(),
+        exports.len()
+    );
+
+    Ok((imports, exports))
+}
+
+/// Strips the package prefix and version from a Theater interface name,
+/// e.g. `ntwk:theater/runtime@0.1.0` becomes `runtime`
+fn theater_interface(name: &str) -> Option<&str> {
+    let interface = name.strip_prefix(THEATER_PACKAGE)?.strip_prefix('/')?;
+    Some(interface.split('@').next().unwrap_or(interface))
+}
+
+/// Resolves the component path of an actor's manifest, relative paths being
+/// relative to the actor directory
+pub fn component_path(actor: &Actor) -> Result<PathBuf> {
+    let manifest = actor
+        .manifest
+        .as_ref()
+        .ok_or_else(|| anyhow!("Actor '{}' has no manifest.toml", actor.name))?;
+
+    if manifest.component.is_empty() {
+        return Err(anyhow!(
+            "Actor '{}' has not been built yet (manifest has no component path)",
+            actor.name
+        ));
+    }
+
+    let component = PathBuf::from(&manifest.component);
+    if component.is_absolute() {
+        Ok(component)
+    } else {
+        Ok(actor.path.join(component))
+    }
+}
+
+/// Cross-checks the handlers in an actor's manifest against the interfaces
+/// its built component imports and exports
+pub fn check_actor(actor: &Actor) -> Result<CompatibilityReport> {
+    let component = component_path(actor)?;
+    let (imports, exports) = component_interfaces(&component)?;
+
+    let configured_handlers: Vec<String> = actor
+        .manifest
+        .as_ref()
+        .map(|m| m.handlers.iter().filter_map(handler_type).collect())
+        .unwrap_or_default();
+    let configured: BTreeSet<&str> = configured_handlers.iter().map(|h| h.as_str()).collect();
+
+    let imported: BTreeSet<&str> = imports
+        .iter()
+        .filter_map(|i| theater_interface(i))
+        .collect();
+    let exported: BTreeSet<&str> = exports
+        .iter()
+        .filter_map(|e| theater_interface(e))
+        .collect();
+
+    let mut missing_handlers = Vec::new();
+    let mut unknown_imports = Vec::new();
+    for interface in &imported {
+        if TYPE_ONLY_INTERFACES.contains(interface) {
+            continue;
+        }
+
+        match HANDLER_INTERFACES
+            .iter()
+            .find(|h| h.imports.contains(interface))
+        {
+            Some(mapping) if !configured.contains(mapping.handler) => {
+                missing_handlers.push(MissingHandler {
+                    handler: mapping.handler.to_string(),
+                    interface: format!("{}/{}", THEATER_PACKAGE, interface),
+                });
+            }
+            Some(_) => {}
+            None => unknown_imports.push(format!("{}/{}", THEATER_PACKAGE, interface)),
+        }
+    }
+
+    let unused_handlers = configured
+        .iter()
+        .filter(|handler| {
+            match HANDLER_INTERFACES.iter().find(|h| h.handler == **handler) {
+                Some(mapping) => {
+                    !mapping.imports.iter().any(|i| imported.contains(i))
+                        && !mapping.exports.iter().any(|e| exported.contains(e))
+                }
+                // We don't know what this handler provides, so don't flag it
+                None => false,
+            }
+        })
+        .map(|h| h.to_string())
+        .collect();
+
+    Ok(CompatibilityReport {
+        component,
+        imports,
+        exports,
+        configured_handlers,
+        missing_handlers,
+        unused_handlers,
+        unknown_imports,
+    })
+}
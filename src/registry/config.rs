@@ -1,21 +1,137 @@
-use std::path::Path;
-use std::fs;
-use anyhow::{Result, Context};
+use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::{env, fmt, fs};
+use anyhow::{anyhow, Result, Context};
 use serde::{Serialize, Deserialize};
 
+use super::document::TomlDocument;
+use super::migrations::CURRENT_SCHEMA_VERSION;
+use super::namespace;
+use super::remote::DEFAULT_SERVER_ADDR;
+use crate::logging::LoggingConfig;
+use crate::theater_client::DEFAULT_THEATER_ADDR;
+
+/// Name of the config file at the top of the registry directory
+pub const CONFIG_FILE: &str = ".registry.config.toml";
+
+/// Theater CLI used for builds when `theater_binary` is not set
+pub const DEFAULT_THEATER_BINARY: &str = "theater";
+
+/// Templates `default_template` may name
+pub const TEMPLATES: &[&str] = &["basic", "http", "supervisor"];
+
+/// Key of the user config naming the registry, like `THEATER_ACTORS_PATH`
+pub const USER_REGISTRY_KEY: &str = "registry";
+
+/// Keys `set-registry-config` accepts; `logging` also takes its own keys,
+/// e.g. `logging.filter`
+pub const KEYS: &[&str] = &[
+    "default_template",
+    "default_interfaces",
+    "build_cache_enabled",
+    "extra_roots",
+    "remotes",
+    "theater_binary",
+    "wit_source",
+    "server_addr",
+    "theater_addr",
+    "chain_store",
+    "logging",
+];
+
+/// Commented examples of the keys without a default value
+const OPTIONAL_KEYS_EXAMPLE: &str = r#"
+# Theater CLI used by builds, `theater` on the PATH if unset
+# theater_binary = "/usr/local/bin/theater"
+
+# WIT files copied into new actors, relative to the registry unless absolute
+# wit_source = "wit"
+
+# Address `serve --http` listens on
+# server_addr = "127.0.0.1:7878"
+
+# Management socket of the Theater server actors are started in
+# theater_addr = "127.0.0.1:9000"
+
+# Where Theater saves event chains, `$THEATER_HOME/chains` if unset
+# chain_store = "/home/me/.theater/chains"
+
+# Read-only actor roots searched after this registry
+# extra_roots = ["/opt/shared-actors"]
+
+# Remote registries consulted for actors not found locally
+# [[remotes]]
+# name = "team"
+# url = "http://127.0.0.1:7878"
+
+# [logging]
+# filter = "info"
+# format = "text"
+# rotation = "never"
+"#;
+
+const LOGGING_KEYS: &[&str] = &["file", "filter", "format", "rotation", "forward_to_client"];
+
+/// Missing keys take their default, so adding a field never breaks older
+/// files; changes that need more than a default go through `migrations`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RegistryConfig {
+    /// Version of the file's schema, see `migrations`
+    pub schema_version: u32,
     pub default_template: String,
     pub default_interfaces: Vec<String>,
     pub build_cache_enabled: bool,
+    /// Read-only actor roots searched after the registry directory,
+    /// relative to it unless absolute
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_roots: Vec<PathBuf>,
+    /// Remote registries consulted, in order, for actors not found locally
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remotes: Vec<RemoteConfig>,
+    /// Theater CLI used by builds, defaults to `theater` on the PATH
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theater_binary: Option<PathBuf>,
+    /// Directory of WIT files copied into new actors, relative to the
+    /// registry unless absolute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wit_source: Option<PathBuf>,
+    /// Address `serve --http` listens on when none is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_addr: Option<String>,
+    /// Management address of the Theater server `start-actor` talks to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theater_addr: Option<String>,
+    /// Directory Theater saves actor event chains in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_store: Option<PathBuf>,
+    /// Where and how the server logs; see `crate::logging`
+    #[serde(default, skip_serializing_if = "LoggingConfig::is_default")]
+    pub logging: LoggingConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteConfig {
+    pub name: String,
+    /// Base URL of the remote, e.g. `http://127.0.0.1:7878`
+    pub url: String,
 }
 
 impl Default for RegistryConfig {
     fn default() -> Self {
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             default_template: "basic".to_string(),
             default_interfaces: vec!["ntwk:theater/actor".to_string()],
             build_cache_enabled: true,
+            extra_roots: Vec::new(),
+            remotes: Vec::new(),
+            theater_binary: None,
+            wit_source: None,
+            server_addr: None,
+            theater_addr: None,
+            chain_store: None,
+            logging: LoggingConfig::default(),
         }
     }
 }
@@ -32,12 +148,303 @@ impl RegistryConfig {
     }
     
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let content = toml::to_string(self)
+        // Merge into the existing file so user comments survive
+        let mut doc = TomlDocument::load_or_default(&path)?;
+        doc.merge_from(self)
             .with_context(|| "Failed to serialize config")?;
         
-        fs::write(&path, content)
+        doc.save()
             .with_context(|| format!("Failed to write config to {:?}", path.as_ref()))?;
         
         Ok(())
+    }    
+    pub fn theater_binary(&self) -> PathBuf {
+        self.theater_binary
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_THEATER_BINARY))
+    }
+    
+    pub fn server_addr(&self) -> &str {
+        self.server_addr.as_deref().unwrap_or(DEFAULT_SERVER_ADDR)
+    }
+    
+    pub fn theater_addr(&self) -> &str {
+        self.theater_addr.as_deref().unwrap_or(DEFAULT_THEATER_ADDR)
     }
-}
\ No newline at end of file
+    
+    /// Checks the values a config file cannot express through its types,
+    /// reporting every problem at once
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        
+        if self.schema_version > CURRENT_SCHEMA_VERSION {
+            problems.push(format!(
+                "schema_version {} is newer than this build supports ({})",
+                self.schema_version, CURRENT_SCHEMA_VERSION
+            ));
+        }
+        
+        if !TEMPLATES.contains(&self.default_template.as_str()) {
+            problems.push(format!(
+                "default_template '{}' is not one of {}",
+                self.default_template,
+                TEMPLATES.join(", ")
+            ));
+        }
+        
+        for interface in &self.default_interfaces {
+            let valid = interface
+                .split_once(':')
+                .and_then(|(_, rest)| rest.split_once('/'))
+                .map(|(package, name)| !package.is_empty() && !name.is_empty())
+                .unwrap_or(false);
+            if !valid {
+                problems.push(format!(
+                    "default_interfaces entry '{}' is not of the form namespace:package/interface",
+                    interface
+                ));
+            }
+        }
+        
+        if self.extra_roots.iter().any(|root| root.as_os_str().is_empty()) {
+            problems.push("extra_roots cannot contain an empty path".to_string());
+        }
+        
+        let mut names = Vec::new();
+        for remote in &self.remotes {
+            // Remote names become directories of the offline cache
+            if !namespace::is_valid_segment(&remote.name) {
+                problems.push(format!("remote name '{}' is not a valid name", remote.name));
+            } else if names.contains(&remote.name.as_str()) {
+                problems.push(format!("remote '{}' is configured twice", remote.name));
+            }
+            names.push(remote.name.as_str());
+            
+            if !remote.url.starts_with("http://") && !remote.url.starts_with("https://") {
+                problems.push(format!(
+                    "remote '{}' has url '{}', expected http:// or https://",
+                    remote.name, remote.url
+                ));
+            }
+        }
+        
+        if let Some(store) = &self.chain_store {
+            if store.as_os_str().is_empty() {
+                problems.push("chain_store cannot be empty".to_string());
+            }
+        }
+        
+        if let Some(binary) = &self.theater_binary {
+            if binary.as_os_str().is_empty() {
+                problems.push("theater_binary cannot be empty".to_string());
+            }
+        }
+        
+        for (key, addr) in [
+            ("server_addr", &self.server_addr),
+            ("theater_addr", &self.theater_addr),
+        ] {
+            if let Some(addr) = addr {
+                let valid = addr
+                    .rsplit_once(':')
+                    .map(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+                    .unwrap_or(false);
+                if !valid {
+                    problems.push(format!("{} '{}' is not of the form host:port", key, addr));
+                }
+            }
+        }
+        
+        if let Some(filter) = &self.logging.filter {
+            if let Err(e) = tracing_subscriber::EnvFilter::try_new(filter) {
+                problems.push(format!("logging.filter '{}' is invalid: {}", filter, e));
+            }
+        }
+        
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Invalid registry config:\n- {}", problems.join("\n- ")))
+        }
+    }
+    
+    /// A config file with every default commented out, so values from the
+    /// user config are not shadowed by a freshly created registry
+    pub fn commented_defaults() -> Result<String> {
+        let defaults = toml::to_string(&Self::default())
+            .with_context(|| "Failed to serialize config")?;
+        
+        let mut content = String::from(
+            "# Actor registry config. Keys left unset fall back to the user config\n\
+             # (~/.config/actor-registry/config.toml) and then to these defaults.\n\n",
+        );
+        content.push_str(&format!("schema_version = {}\n\n", CURRENT_SCHEMA_VERSION));
+        for line in defaults.lines().filter(|line| !line.starts_with("schema_version")) {
+            content.push_str("# ");
+            content.push_str(line);
+            content.push('\n');
+        }
+        content.push_str(OPTIONAL_KEYS_EXAMPLE);
+        Ok(content)
+    }
+}
+
+/// The user-wide config, `$XDG_CONFIG_HOME/actor-registry/config.toml` or
+/// `~/.config/actor-registry/config.toml`
+pub fn user_config_path() -> Option<PathBuf> {
+    let non_empty = |var: &str| env::var_os(var).filter(|v| !v.is_empty()).map(PathBuf::from);
+    
+    non_empty("XDG_CONFIG_HOME")
+        .or_else(|| non_empty("HOME").map(|home| home.join(".config")))
+        .map(|dir| dir.join("actor-registry").join("config.toml"))
+}
+
+/// Where a config value came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    Default,
+    UserFile(PathBuf),
+    RegistryFile(PathBuf),
+    /// Environment variables and command-line flags
+    Overrides,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "built-in default"),
+            ConfigSource::UserFile(path) => write!(f, "user config {}", path.display()),
+            ConfigSource::RegistryFile(path) => write!(f, "registry config {}", path.display()),
+            ConfigSource::Overrides => write!(f, "environment or command line"),
+        }
+    }
+}
+
+/// The layers of the effective config, lowest precedence first: built-in
+/// defaults, the user config, the registry's own config file, then values
+/// from the environment and command line
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    pub user_file: Option<PathBuf>,
+    pub overrides: toml::Table,
+}
+
+/// The effective config and the layer each of its top-level keys came from
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub config: RegistryConfig,
+    pub sources: BTreeMap<String, ConfigSource>,
+}
+
+impl ConfigLayers {
+    fn user_table(&self) -> Result<Option<toml::Table>> {
+        match &self.user_file {
+            Some(path) if path.exists() => read_table(path).map(Some),
+            _ => Ok(None),
+        }
+    }
+    
+    /// The registry the user config names, if any
+    pub fn user_registry(&self) -> Result<Option<String>> {
+        let Some(table) = self.user_table()? else {
+            return Ok(None);
+        };
+        
+        match table.get(USER_REGISTRY_KEY) {
+            None => Ok(None),
+            Some(toml::Value::String(path)) => Ok(Some(path.clone())),
+            Some(_) => Err(anyhow!(
+                "'{}' in {:?} must be a string",
+                USER_REGISTRY_KEY,
+                self.user_file
+            )),
+        }
+    }
+    
+    /// Resolves the effective config of the registry whose config file is
+    /// `registry_file`
+    pub fn resolve(&self, registry_file: &Path) -> Result<ResolvedConfig> {
+        let registry = if registry_file.exists() {
+            read_table(registry_file)?
+        } else {
+            toml::Table::new()
+        };
+        self.resolve_with(registry_file, registry)
+    }
+    
+    /// Resolves the effective config as if the registry config file held
+    /// `registry`
+    pub fn resolve_with(&self, registry_file: &Path, registry: toml::Table) -> Result<ResolvedConfig> {
+        let defaults = toml::Table::try_from(RegistryConfig::default())
+            .with_context(|| "Failed to serialize config")?;
+        
+        let mut layers = vec![(ConfigSource::Default, defaults)];
+        if let (Some(path), Some(mut table)) = (&self.user_file, self.user_table()?) {
+            table.remove(USER_REGISTRY_KEY);
+            layers.push((ConfigSource::UserFile(path.clone()), table));
+        }
+        layers.push((ConfigSource::RegistryFile(registry_file.to_path_buf()), registry));
+        layers.push((ConfigSource::Overrides, self.overrides.clone()));
+        
+        let mut merged = toml::Table::new();
+        let mut sources = BTreeMap::new();
+        for (source, table) in layers {
+            for key in table.keys() {
+                sources.insert(key.clone(), source.clone());
+            }
+            merge_tables(&mut merged, table);
+        }
+        
+        let config: RegistryConfig = toml::Value::Table(merged)
+            .try_into()
+            .with_context(|| format!("Failed to load config for {:?}", registry_file))?;
+        config.validate()?;
+        
+        Ok(ResolvedConfig { config, sources })
+    }
+}
+
+fn read_table(path: &Path) -> Result<toml::Table> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config from {:?}", path))?;
+    
+    toml::from_str(&content).with_context(|| format!("Failed to parse config from {:?}", path))
+}
+
+/// Merges `layer` over `base`: tables merge key by key, anything else is
+/// replaced
+fn merge_tables(base: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(table)) => {
+                merge_tables(existing, table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Checks that `key` names a config value, e.g. `server_addr` or
+/// `logging.filter`
+pub fn validate_key(key: &str) -> Result<Vec<&str>> {
+    let path: Vec<&str> = key.split('.').collect();
+    
+    let known = match path.as_slice() {
+        [key] => KEYS.contains(key),
+        ["logging", key] => LOGGING_KEYS.contains(key),
+        _ => false,
+    };
+    
+    if known {
+        Ok(path)
+    } else {
+        Err(anyhow!(
+            "Unknown config key '{}'; expected one of {} or logging.{}",
+            key,
+            KEYS.join(", "),
+            LOGGING_KEYS.join("|")
+        ))
+    }
+}
@@ -0,0 +1,685 @@
+use anyhow::{anyhow, Result};
+use semver::{Comparator, Op, Version, VersionReq};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use similar::TextDiff;
+use std::collections::{BTreeMap, BTreeSet};
+use toml_edit::{value, Array, InlineTable, Item, Table};
+use tracing::{debug, warn};
+
+use super::actor::Actor;
+use super::document::TomlDocument;
+
+/// Crates known not to build for `wasm32-unknown-unknown`, which is the
+/// target Theater actors are compiled for
+const WASM_INCOMPATIBLE: &[(&str, &str)] = &[
+    ("mio", "requires OS networking and polling APIs"),
+    ("async-std", "requires an OS-backed async runtime"),
+    (
+        "reqwest",
+        "requires native networking; use the http-client handler instead",
+    ),
+    (
+        "hyper",
+        "requires native networking; use the http-client or http-framework handlers instead",
+    ),
+    ("openssl", "links against the native OpenSSL library"),
+    ("openssl-sys", "links against the native OpenSSL library"),
+    ("native-tls", "links against the platform TLS library"),
+    ("rusqlite", "links against the native SQLite library"),
+    ("libsqlite3-sys", "links against the native SQLite library"),
+    ("nix", "wraps Unix system calls"),
+    (
+        "ring",
+        "needs a C toolchain for wasm32 and has no entropy source",
+    ),
+    ("rayon", "requires OS threads"),
+    (
+        "notify",
+        "requires OS filesystem events; use the filesystem handler instead",
+    ),
+];
+
+/// Returns why a crate is known not to compile for wasm32-unknown-unknown,
+/// if it is on the known-incompatible list
+pub fn wasm_incompatibility(name: &str) -> Option<&'static str> {
+    WASM_INCOMPATIBLE
+        .iter()
+        .find(|(crate_name, _)| *crate_name == name)
+        .map(|(_, reason)| *reason)
+}
+
+/// Parses the version requirement of dependency `name`, so a typo is
+/// reported here rather than by cargo on the next build
+pub fn parse_requirement(name: &str, requirement: &str) -> Result<VersionReq> {
+    VersionReq::parse(requirement).map_err(|e| {
+        anyhow!(
+            "Invalid version requirement '{}' for dependency '{}': {}",
+            requirement,
+            name,
+            e
+        )
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DependencySpec {
+    pub name: String,
+    pub version: Option<String>,
+    pub features: Vec<String>,
+    pub path: Option<String>,
+    pub git: Option<String>,
+    pub branch: Option<String>,
+    pub tag: Option<String>,
+    pub rev: Option<String>,
+    pub optional: bool,
+    pub default_features: Option<bool>,
+}
+
+impl DependencySpec {
+    /// Builds a spec from tool arguments: `name` plus optional `version`,
+    /// `features`, `path`, `git`, `branch`, `tag`, `rev`, `optional` and
+    /// `default_features`
+    pub fn from_json(args: &JsonValue) -> Result<Self> {
+        let string = |key: &str| {
+            args.get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+
+        let name = string("name").ok_or_else(|| anyhow!("Dependency is missing a name"))?;
+
+        let features = match args.get("features") {
+            Some(JsonValue::Array(features)) => features
+                .iter()
+                .map(|f| {
+                    f.as_str()
+                        .map(|s| s.to_string())
+                        .ok_or_else(|| anyhow!("Dependency features must be strings"))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Some(_) => return Err(anyhow!("Dependency features must be a list")),
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            name,
+            version: string("version"),
+            features,
+            path: string("path"),
+            git: string("git"),
+            branch: string("branch"),
+            tag: string("tag"),
+            rev: string("rev"),
+            optional: args
+                .get("optional")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            default_features: args.get("default_features").and_then(|v| v.as_bool()),
+        })
+    }
+
+    /// Reads a spec back from a `[dependencies]` entry
+    pub fn from_item(name: &str, item: &Item) -> Self {
+        let mut spec = Self {
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        if let Some(version) = item.as_str() {
+            spec.version = Some(version.to_string());
+            return spec;
+        }
+
+        if let Some(table) = item.as_table_like() {
+            let string = |key: &str| {
+                table
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+            };
+
+            spec.version = string("version");
+            spec.path = string("path");
+            spec.git = string("git");
+            spec.branch = string("branch");
+            spec.tag = string("tag");
+            spec.rev = string("rev");
+            spec.optional = table
+                .get("optional")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            spec.default_features = table.get("default-features").and_then(|v| v.as_bool());
+            spec.features = table
+                .get("features")
+                .and_then(|v| v.as_array())
+                .map(|features| {
+                    features
+                        .iter()
+                        .filter_map(|f| f.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+
+        spec
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("Dependency name cannot be empty"));
+        }
+
+        if self.version.is_none() && self.path.is_none() && self.git.is_none() {
+            return Err(anyhow!(
+                "Dependency '{}' needs a version, path or git source",
+                self.name
+            ));
+        }
+
+        if let Some(version) = &self.version {
+            parse_requirement(&self.name, version)?;
+        }
+
+        if self.path.is_some() && self.git.is_some() {
+            return Err(anyhow!(
+                "Dependency '{}' cannot have both a path and a git source",
+                self.name
+            ));
+        }
+
+        let git_refs = [&self.branch, &self.tag, &self.rev]
+            .iter()
+            .filter(|r| r.is_some())
+            .count();
+        if git_refs > 0 && self.git.is_none() {
+            return Err(anyhow!(
+                "Dependency '{}' has a branch, tag or rev but no git source",
+                self.name
+            ));
+        }
+        if git_refs > 1 {
+            return Err(anyhow!(
+                "Dependency '{}' can only specify one of branch, tag or rev",
+                self.name
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the dependency against the known-incompatible list for the
+    /// wasm32-unknown-unknown target
+    pub fn check_wasm_compatible(&self) -> Result<()> {
+        match wasm_incompatibility(&self.name) {
+            Some(reason) => Err(anyhow!(
+                "Dependency '{}' does not compile for wasm32-unknown-unknown: {}",
+                self.name,
+                reason
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Renders the spec as Cargo would write it: a plain version string when
+    /// that's all there is, an inline table otherwise
+    fn to_item(&self) -> Item {
+        let simple = self.features.is_empty()
+            && self.path.is_none()
+            && self.git.is_none()
+            && !self.optional
+            && self.default_features.is_none();
+        if let (true, Some(version)) = (simple, &self.version) {
+            return value(version.as_str());
+        }
+
+        let mut table = InlineTable::new();
+        let mut insert = |key: &str, v: &Option<String>| {
+            if let Some(v) = v {
+                table.insert(key, v.as_str().into());
+            }
+        };
+        insert("version", &self.version);
+        insert("path", &self.path);
+        insert("git", &self.git);
+        insert("branch", &self.branch);
+        insert("tag", &self.tag);
+        insert("rev", &self.rev);
+
+        if let Some(default_features) = self.default_features {
+            table.insert("default-features", default_features.into());
+        }
+        if !self.features.is_empty() {
+            let features: Array = self.features.iter().map(|f| f.as_str()).collect();
+            table.insert("features", features.into());
+        }
+        if self.optional {
+            table.insert("optional", true.into());
+        }
+
+        value(table)
+    }
+
+    fn git_ref(&self) -> Option<&String> {
+        self.branch
+            .as_ref()
+            .or(self.tag.as_ref())
+            .or(self.rev.as_ref())
+    }
+
+    /// Short description of where the dependency comes from, used to group
+    /// actors in the drift report
+    pub fn source_label(&self) -> String {
+        match (&self.version, &self.path, &self.git) {
+            (_, Some(path), _) => format!("path:{}", path),
+            (_, _, Some(git)) => match self.git_ref() {
+                Some(r) => format!("git:{}#{}", git, r),
+                None => format!("git:{}", git),
+            },
+            (Some(version), _, _) => version.clone(),
+            _ => "*".to_string(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut source = Vec::new();
+        if let Some(version) = &self.version {
+            source.push(version.clone());
+        }
+        if let Some(path) = &self.path {
+            source.push(format!("path: {}", path));
+        }
+        if let Some(git) = &self.git {
+            match self.git_ref() {
+                Some(r) => source.push(format!("git: {} ({})", git, r)),
+                None => source.push(format!("git: {}", git)),
+            }
+        }
+
+        let mut text = format!("{}: {}", self.name, source.join(", "));
+        if !self.features.is_empty() {
+            text.push_str(&format!(" [features: {}]", self.features.join(", ")));
+        }
+        if self.default_features == Some(false) {
+            text.push_str(" [no default features]");
+        }
+        if self.optional {
+            text.push_str(" [optional]");
+        }
+        text
+    }
+}
+
+fn cargo_document(actor: &Actor) -> Result<TomlDocument> {
+    let cargo_path = actor.path.join("Cargo.toml");
+    if !cargo_path.exists() {
+        return Err(anyhow!("Actor '{}' has no Cargo.toml", actor.name));
+    }
+    TomlDocument::load(cargo_path)
+}
+
+fn dependencies_table(doc: &mut TomlDocument) -> Result<&mut Table> {
+    let doc = doc.doc_mut();
+    if !doc.contains_key("dependencies") {
+        doc["dependencies"] = toml_edit::table();
+    }
+
+    doc["dependencies"]
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("[dependencies] in Cargo.toml is not a table"))
+}
+
+pub fn list_dependencies(actor: &Actor) -> Result<Vec<DependencySpec>> {
+    let doc = cargo_document(actor)?;
+
+    Ok(doc
+        .doc()
+        .get("dependencies")
+        .and_then(|d| d.as_table_like())
+        .map(|deps| {
+            deps.iter()
+                .map(|(name, item)| DependencySpec::from_item(name, item))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Adds a dependency to the actor's Cargo.toml, replacing any existing
+/// entry with the same name. Returns the previous entry if there was one.
+/// Known wasm-incompatible crates are rejected unless `force` is set.
+pub fn add_dependency(
+    actor: &Actor,
+    spec: &DependencySpec,
+    force: bool,
+) -> Result<Option<DependencySpec>> {
+    spec.validate()?;
+    if !force {
+        spec.check_wasm_compatible()?;
+    }
+    debug!(
+        "Adding dependency '{}' to actor '{}'",
+        spec.name, actor.name
+    );
+
+    let mut doc = cargo_document(actor)?;
+    let deps = dependencies_table(&mut doc)?;
+
+    let previous = deps
+        .get(&spec.name)
+        .map(|item| DependencySpec::from_item(&spec.name, item));
+
+    match deps.get_mut(&spec.name) {
+        // Keep the entry's position and trailing comment when replacing it
+        Some(Item::Value(current)) => {
+            let decor = current.decor().clone();
+            if let Ok(new_value) = spec.to_item().into_value() {
+                *current = new_value;
+                *current.decor_mut() = decor;
+            }
+        }
+        _ => {
+            deps.insert(&spec.name, spec.to_item());
+        }
+    }
+
+    doc.save()?;
+    Ok(previous)
+}
+
+/// Removes a dependency from the actor's Cargo.toml. Returns the removed
+/// entry, or `None` if the actor didn't depend on it.
+pub fn remove_dependency(actor: &Actor, name: &str) -> Result<Option<DependencySpec>> {
+    debug!("Removing dependency '{}' from actor '{}'", name, actor.name);
+
+    let mut doc = cargo_document(actor)?;
+    let removed = dependencies_table(&mut doc)?
+        .remove(name)
+        .map(|item| DependencySpec::from_item(name, &item));
+
+    if removed.is_some() {
+        doc.save()?;
+    }
+
+    Ok(removed)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyUsage {
+    pub actor: String,
+    pub spec: DependencySpec,
+}
+
+/// Dependencies aggregated across actors, keyed by crate name
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyReport {
+    pub dependencies: BTreeMap<String, Vec<DependencyUsage>>,
+}
+
+impl DependencyReport {
+    pub fn build(actors: &[Actor]) -> Self {
+        let mut report = Self::default();
+
+        for actor in actors {
+            let deps = match list_dependencies(actor) {
+                Ok(deps) => deps,
+                Err(e) => {
+                    warn!(
+                        "Skipping actor '{}' in dependency report: {}",
+                        actor.name, e
+                    );
+                    continue;
+                }
+            };
+
+            for spec in deps {
+                report
+                    .dependencies
+                    .entry(spec.name.clone())
+                    .or_default()
+                    .push(DependencyUsage {
+                        actor: actor.name.clone(),
+                        spec,
+                    });
+            }
+        }
+
+        report
+    }
+
+    /// Distinct sources (version requirements, paths or git refs) in use for
+    /// a crate, with the actors using each. Version requirements that match
+    /// the same versions, such as `0.39` and `0.39.0`, count as one source
+    /// and are listed under the first spelling seen.
+    pub fn sources(&self, name: &str) -> BTreeMap<String, Vec<String>> {
+        let mut sources: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut ranges: Vec<(VersionRange, String)> = Vec::new();
+
+        for usage in self.dependencies.get(name).into_iter().flatten() {
+            let mut label = usage.spec.source_label();
+            let range = match (&usage.spec.version, &usage.spec.path, &usage.spec.git) {
+                (Some(version), None, None) => version_range(version),
+                _ => None,
+            };
+            if let Some(range) = range {
+                match ranges.iter().find(|(known, _)| *known == range) {
+                    Some((_, known_label)) => label = known_label.clone(),
+                    None => ranges.push((range, label.clone())),
+                }
+            }
+
+            sources.entry(label).or_default().push(usage.actor.clone());
+        }
+        sources
+    }
+
+    /// Crates that are not used with the same source by every actor
+    pub fn drifted(&self) -> BTreeSet<String> {
+        self.dependencies
+            .keys()
+            .filter(|name| self.sources(name).len() > 1)
+            .cloned()
+            .collect()
+    }
+
+    pub fn to_text(&self, drift_only: bool) -> String {
+        let drifted = self.drifted();
+        let mut text = format!(
+            "{} dependencies across the registry, {} with version drift\n\n",
+            self.dependencies.len(),
+            drifted.len()
+        );
+
+        for name in self.dependencies.keys() {
+            let is_drifted = drifted.contains(name);
+            if drift_only && !is_drifted {
+                continue;
+            }
+
+            if is_drifted {
+                text.push_str(&format!("## {} (drift)\n\n", name));
+            } else {
+                text.push_str(&format!("## {}\n\n", name));
+            }
+            for (source, actors) in self.sources(name) {
+                text.push_str(&format!("- {}: {}\n", source, actors.join(", ")));
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+/// One end of a version range and whether it includes that version
+type Bound = Option<(Version, bool)>;
+
+/// The versions a requirement matches, as lower and upper bounds
+#[derive(Debug, Clone, PartialEq)]
+struct VersionRange {
+    lower: Bound,
+    upper: Bound,
+}
+
+/// Parses a Cargo version requirement into the range of versions it
+/// matches, so differently written requirements can be compared. Returns
+/// `None` for requirements that don't parse or involve pre-releases.
+fn version_range(requirement: &str) -> Option<VersionRange> {
+    let requirement = match VersionReq::parse(requirement) {
+        Ok(requirement) => requirement,
+        Err(e) => {
+            warn!(
+                "Cannot compare version requirement '{}': {}",
+                requirement, e
+            );
+            return None;
+        }
+    };
+    let mut range = VersionRange {
+        lower: None,
+        upper: None,
+    };
+
+    for comparator in &requirement.comparators {
+        if !comparator.pre.is_empty() {
+            return None;
+        }
+        // Every comparator must hold, so keep the tightest bound on each side;
+        // at the same version an exclusive bound is the tighter one
+        let (lower, upper) = comparator_bounds(comparator)?;
+        if let Some((version, inclusive)) = lower {
+            let tighter = match &range.lower {
+                Some((current, current_inclusive)) => {
+                    (&version, !inclusive) > (current, !current_inclusive)
+                }
+                None => true,
+            };
+            if tighter {
+                range.lower = Some((version, inclusive));
+            }
+        }
+        if let Some((version, inclusive)) = upper {
+            let tighter = match &range.upper {
+                Some((current, current_inclusive)) => {
+                    (&version, inclusive) < (current, *current_inclusive)
+                }
+                None => true,
+            };
+            if tighter {
+                range.upper = Some((version, inclusive));
+            }
+        }
+    }
+
+    Some(range)
+}
+
+/// The bounds of a single comparator, following Cargo's rules for
+/// requirements with the minor or patch version left out
+fn comparator_bounds(c: &Comparator) -> Option<(Bound, Bound)> {
+    let v = |major, minor, patch| Version::new(major, minor, patch);
+    let (major, minor, patch) = (c.major, c.minor, c.patch);
+    let floor = v(major, minor.unwrap_or(0), patch.unwrap_or(0));
+
+    // The first version past the ones the given parts pin down
+    let next_major = Some((v(major + 1, 0, 0), false));
+    let next_minor = |minor| Some((v(major, minor + 1, 0), false));
+    let past_given = match (minor, patch) {
+        (None, _) => next_major.clone(),
+        (Some(minor), None) => next_minor(minor),
+        (Some(minor), Some(patch)) => Some((v(major, minor, patch), true)),
+    };
+
+    let bounds = match c.op {
+        Op::Exact | Op::Wildcard => (Some((floor, true)), past_given),
+        Op::Greater => match past_given {
+            Some((version, true)) => (Some((version, false)), None),
+            upper => (upper.map(|(version, _)| (version, true)), None),
+        },
+        Op::GreaterEq => (Some((floor, true)), None),
+        Op::Less => (None, Some((floor, false))),
+        Op::LessEq => (None, past_given),
+        Op::Tilde => match minor {
+            Some(minor) => (Some((floor, true)), next_minor(minor)),
+            None => (Some((floor, true)), next_major),
+        },
+        Op::Caret => {
+            let upper = match (major, minor, patch) {
+                (0, Some(0), Some(patch)) => Some((v(0, 0, patch + 1), false)),
+                (0, Some(minor), _) => next_minor(minor),
+                _ => next_major,
+            };
+            (Some((floor, true)), upper)
+        }
+        _ => return None,
+    };
+
+    Some(bounds)
+}
+
+/// Sets the version requirement of a dependency in an actor's Cargo.toml,
+/// keeping its features and other keys. Returns a unified diff of the
+/// change, or `None` if the actor doesn't use the crate or is already on
+/// that version. Nothing is written when `dry_run` is set.
+pub fn align_dependency(
+    actor: &Actor,
+    name: &str,
+    version: &str,
+    dry_run: bool,
+) -> Result<Option<String>> {
+    parse_requirement(name, version)?;
+
+    let mut doc = cargo_document(actor)?;
+    let before = doc.to_string();
+
+    let deps = dependencies_table(&mut doc)?;
+    let entry = match deps.get_mut(name) {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+
+    if entry.is_str() {
+        let decor = entry.as_value().map(|v| v.decor().clone());
+        *entry = value(version);
+        if let (Some(decor), Some(v)) = (decor, entry.as_value_mut()) {
+            *v.decor_mut() = decor;
+        }
+    } else if let Some(table) = entry.as_table_like_mut() {
+        if table.contains_key("path") || table.contains_key("git") {
+            return Err(anyhow!(
+                "Dependency '{}' of actor '{}' comes from a path or git source",
+                name,
+                actor.name
+            ));
+        }
+        table.insert("version", value(version));
+    } else {
+        return Err(anyhow!(
+            "Dependency '{}' of actor '{}' has an unsupported format",
+            name,
+            actor.name
+        ));
+    }
+
+    let after = doc.to_string();
+    if before == after {
+        return Ok(None);
+    }
+
+    let diff = TextDiff::from_lines(&before, &after)
+        .unified_diff()
+        .header(
+            &format!("a/{}/Cargo.toml", actor.name),
+            &format!("b/{}/Cargo.toml", actor.name),
+        )
+        .to_string();
+
+    if !dry_run {
+        debug!(
+            "Aligning dependency '{}' of actor '{}' to {}",
+            name, actor.name, version
+        );
+        doc.save()?;
+    }
+
+    Ok(Some(diff))
+}
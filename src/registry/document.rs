@@ -0,0 +1,256 @@
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table};
+use tracing::debug;
+
+/// A TOML file opened for format-preserving edits. Every write the registry
+/// makes to manifest.toml, Cargo.toml or its own config goes through this
+/// type so comments, key ordering and unknown sections survive.
+pub struct TomlDocument {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+impl TomlDocument {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+
+        let doc = content
+            .parse::<DocumentMut>()
+            .with_context(|| format!("Failed to parse {:?}", path))?;
+
+        Ok(Self { path, doc })
+    }
+
+    /// Loads the document at `path`, or starts an empty one if the file
+    /// does not exist yet
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self {
+                path: path.to_path_buf(),
+                doc: DocumentMut::new(),
+            })
+        }
+    }
+
+    /// Creates a new document at `path` from a serializable value
+    pub fn from_serializable<P: AsRef<Path>, T: Serialize>(path: P, value: &T) -> Result<Self> {
+        let doc = serialize_document(value)
+            .with_context(|| format!("Failed to serialize {:?}", path.as_ref()))?;
+
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            doc,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn doc(&self) -> &DocumentMut {
+        &self.doc
+    }
+
+    pub fn doc_mut(&mut self) -> &mut DocumentMut {
+        &mut self.doc
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T> {
+        toml::from_str(&self.doc.to_string())
+            .with_context(|| format!("Failed to deserialize {:?}", self.path))
+    }
+
+    /// Updates the document to match a serializable value. Keys whose value
+    /// is unchanged keep their formatting and comments, changed values are
+    /// replaced in place and keys missing from `value` are removed.
+    pub fn merge_from<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        let updated = serialize_document(value)
+            .with_context(|| format!("Failed to serialize {:?}", self.path))?;
+
+        merge_table(self.doc.as_table_mut(), updated.as_table());
+        Ok(())
+    }
+
+    /// Sets the value at a dotted key path such as `["logging", "filter"]`,
+    /// creating tables along the way. `None` removes the key.
+    pub fn set_value<T: Serialize>(&mut self, key: &[&str], value: Option<&T>) -> Result<()> {
+        let (last, parents) = key
+            .split_last()
+            .ok_or_else(|| anyhow!("Empty key for {:?}", self.path))?;
+
+        let new_item = match value {
+            Some(value) => Some(serialize_item(key, last, value)?),
+            None => None,
+        };
+
+        if new_item.is_some() {
+            self.hoist_header();
+        }
+
+        let path = &self.path;
+        let mut table = self.doc.as_table_mut();
+        for parent in parents {
+            let item = table
+                .entry(parent)
+                .or_insert_with(|| Item::Table(Table::new()));
+            if let Some(inline) = item.as_inline_table() {
+                *item = Item::Table(inline.clone().into_table());
+            }
+            table = item
+                .as_table_mut()
+                .ok_or_else(|| anyhow!("'{}' in {:?} is not a table", parent, path))?;
+        }
+
+        let Some(new_item) = new_item else {
+            table.remove(last);
+            return Ok(());
+        };
+
+        match table.get_mut(last) {
+            Some(Item::Value(current)) if new_item.is_value() => {
+                let decor = current.decor().clone();
+                if let Some(value) = new_item.as_value() {
+                    *current = value.clone();
+                    *current.decor_mut() = decor;
+                }
+            }
+            _ => {
+                table.insert(last, new_item);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Comments at the top of a file without top-level values belong to its
+    /// first table, or trail the document if it has no keys at all. Moves
+    /// them to the document itself so they stay on top as keys are added.
+    fn hoist_header(&mut self) {
+        let root = self.doc.as_table();
+        if root.iter().any(|(_, item)| item.is_value()) {
+            return;
+        }
+
+        let comments = if root.is_empty() {
+            let trailing = self.doc.trailing().as_str().unwrap_or_default().to_string();
+            self.doc.set_trailing("");
+            trailing
+        } else {
+            let first = self.doc.as_table_mut().iter_mut().next();
+            match first {
+                Some((_, Item::Table(table))) => {
+                    let prefix = table.decor().prefix().and_then(|p| p.as_str());
+                    let prefix = prefix.unwrap_or_default().to_string();
+                    table.decor_mut().set_prefix("");
+                    prefix
+                }
+                _ => return,
+            }
+        };
+
+        self.doc.as_table_mut().decor_mut().set_prefix(comments);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        debug!("Writing {:?}", self.path);
+        fs::write(&self.path, self.doc.to_string())
+            .with_context(|| format!("Failed to write {:?}", self.path))?;
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TomlDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
+
+/// Serializes `value` as the item for `last`. Going through a wrapper table
+/// makes arrays of tables come out as `[[key]]` sections.
+fn serialize_item<T: Serialize>(key: &[&str], last: &str, value: &T) -> Result<Item> {
+    let mut wrapper = toml_edit::ser::to_document(&BTreeMap::from([(last, value)]))
+        .with_context(|| format!("Failed to serialize '{}'", key.join(".")))?;
+
+    wrapper
+        .as_table_mut()
+        .remove(last)
+        .ok_or_else(|| anyhow!("Failed to serialize '{}'", key.join(".")))
+}
+
+fn merge_table(existing: &mut Table, updated: &Table) {
+    existing.retain(|key, _| updated.contains_key(key));
+
+    for (key, new_item) in updated.iter() {
+        match existing.get_mut(key) {
+            Some(current) if same_item(current, new_item) => {}
+            Some(Item::Table(current)) if as_table(new_item).is_some() => {
+                if let Some(new_table) = as_table(new_item) {
+                    merge_table(current, &new_table);
+                }
+            }
+            Some(Item::Value(toml_edit::Value::InlineTable(current))) if new_item.is_table() => {
+                // Tables written inline stay inline
+                if let Some(new_table) = as_table(new_item) {
+                    let mut table = current.clone().into_table();
+                    merge_table(&mut table, &new_table);
+                    let decor = current.decor().clone();
+                    *current = table.into_inline_table();
+                    *current.decor_mut() = decor;
+                }
+            }
+            Some(Item::Value(current)) if new_item.is_value() => {
+                // Keep the surrounding whitespace and trailing comments
+                let decor = current.decor().clone();
+                if let Some(value) = new_item.as_value() {
+                    *current = value.clone();
+                    *current.decor_mut() = decor;
+                }
+            }
+            _ => {
+                existing.insert(key, new_item.clone());
+            }
+        }
+    }
+}
+
+/// Serializes `value` the way `toml::to_string` lays it out, with nested
+/// tables as `[section]` headers rather than inline tables
+fn serialize_document<T: Serialize>(value: &T) -> Result<DocumentMut> {
+    let content = toml::to_string(value)?;
+    Ok(content.parse::<DocumentMut>()?)
+}
+
+fn as_table(item: &Item) -> Option<Table> {
+    match item {
+        Item::Table(table) => Some(table.clone()),
+        Item::Value(toml_edit::Value::InlineTable(table)) => Some(table.clone().into_table()),
+        _ => None,
+    }
+}
+
+/// Compares two items by value, ignoring their formatting
+fn same_item(a: &Item, b: &Item) -> bool {
+    let parsed = |item: &Item| {
+        let mut doc = DocumentMut::new();
+        doc["v"] = item.clone();
+        toml::from_str::<toml::Table>(&doc.to_string())
+            .ok()
+            .and_then(|mut table| table.remove("v"))
+    };
+
+    match (parsed(a), parsed(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
@@ -0,0 +1,580 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+use wasmtime::component::types::ComponentItem;
+use wasmtime::component::{
+    Component, Func, Instance, Linker, LinkerInstance, ResourceType, Type, Val,
+};
+use wasmtime::{Config, Engine, Store};
+
+use super::actor::Actor;
+use super::compat;
+use super::smoke::{
+    self, check_response, skipped, step_result, SmokeReport, SmokeScript, StepOutcome, StepResult,
+    SMOKE_SCRIPT,
+};
+use crate::theater_client::describe_payload;
+
+/// Interface whose `log` the harness captures
+const RUNTIME_INTERFACE: &str = "ntwk:theater/runtime";
+const ACTOR_INTERFACE: &str = "ntwk:theater/actor";
+const MESSAGE_INTERFACE: &str = "ntwk:theater/message-server-client";
+
+/// Fuel each call may burn before it is stopped, so a looping actor fails
+/// its step instead of hanging the registry
+const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// Id the harness passes to `init`, where Theater would pass the actor's
+pub const HARNESS_ACTOR_ID: &str = "test-actor";
+
+#[derive(Debug, Clone, Default)]
+pub struct HarnessOptions {
+    /// Overrides the script's `init_state`
+    pub init_state: Option<Value>,
+    /// Script to run instead of the actor's `tests/smoke.toml`
+    pub script: Option<PathBuf>,
+}
+
+/// What the mocked imports saw
+#[derive(Debug, Default)]
+struct HostState {
+    logs: Vec<String>,
+    /// Calls to imports other than `log`, as `interface#function`
+    calls: Vec<String>,
+}
+
+/// A built actor component instantiated without Theater, its imports
+/// mocked: `log` is captured and every other function returns an empty
+/// value of its result type
+struct Harness {
+    store: Store<HostState>,
+    instance: Instance,
+    /// Names of the instances the component exports
+    exports: Vec<String>,
+    state: Option<Vec<u8>>,
+    requests: u64,
+}
+
+/// Result of one call into the actor
+struct CallResult {
+    response: Option<Vec<u8>>,
+    logs: Vec<String>,
+    calls: Vec<String>,
+}
+
+impl Harness {
+    fn new(component_path: &Path) -> Result<Self> {
+        let mut config = Config::new();
+        config.wasm_component_model(true);
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+
+        let component = Component::from_file(&engine, component_path)
+            .with_context(|| format!("Failed to load component {}", component_path.display()))?;
+
+        let exports = component
+            .component_type()
+            .exports(&engine)
+            .filter(|(_, item)| matches!(item, ComponentItem::ComponentInstance(_)))
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        let mut linker = Linker::new(&engine);
+        mock_imports(&engine, &component, &mut linker)?;
+
+        let mut store = Store::new(&engine, HostState::default());
+        store.set_fuel(FUEL_PER_CALL)?;
+        let instance = linker
+            .instantiate(&mut store, &component)
+            .context("Failed to instantiate component")?;
+
+        Ok(Self {
+            store,
+            instance,
+            exports,
+            state: None,
+            requests: 0,
+        })
+    }
+
+    fn export(&mut self, interface: &str, name: &str) -> Result<Func> {
+        let instance_name = self
+            .instance_export_name(interface)
+            .ok_or_else(|| anyhow!("Component does not export {}", interface))?;
+        let parent = self
+            .instance
+            .get_export(&mut self.store, None, &instance_name)
+            .ok_or_else(|| anyhow!("Component does not export {}", interface))?;
+        let index = self
+            .instance
+            .get_export(&mut self.store, Some(&parent), name)
+            .ok_or_else(|| anyhow!("{} has no {} function", interface, name))?;
+        self.instance
+            .get_func(&mut self.store, index)
+            .ok_or_else(|| anyhow!("{}#{} is not a function", interface, name))
+    }
+
+    /// The exported instance name for `interface`, which may carry a version
+    fn instance_export_name(&self, interface: &str) -> Option<String> {
+        self.exports
+            .iter()
+            .find(|name| is_interface(name, interface))
+            .cloned()
+    }
+
+    /// Calls an actor handler with the current state and `params`, keeping
+    /// the state it returns
+    fn call(&mut self, interface: &str, name: &str, params: Params) -> Result<CallResult> {
+        let func = self.export(interface, name)?;
+        let args = func
+            .params(&self.store)
+            .iter()
+            .enumerate()
+            .map(|(index, (_, ty))| match index {
+                0 => Ok(option_bytes(self.state.clone())),
+                _ => params.to_val(ty),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut results: Vec<Val> = func
+            .results(&self.store)
+            .iter()
+            .map(|_| Val::Bool(false))
+            .collect();
+
+        self.store.set_fuel(FUEL_PER_CALL)?;
+        let (logs_before, calls_before) = {
+            let host = self.store.data();
+            (host.logs.len(), host.calls.len())
+        };
+
+        let outcome = func.call(&mut self.store, &args, &mut results);
+        if outcome.is_ok() {
+            func.post_return(&mut self.store)?;
+        }
+
+        let host = self.store.data();
+        let logs = host.logs[logs_before..].to_vec();
+        let calls = host.calls[calls_before..].to_vec();
+        outcome.with_context(|| format!("{}#{} trapped", interface, name))?;
+
+        let (state, response) = match results.first() {
+            Some(Val::Result(Ok(value))) => {
+                let mut found = Vec::new();
+                if let Some(value) = value {
+                    collect_option_bytes(value, &mut found);
+                }
+                let mut found = found.into_iter();
+                (found.next().flatten(), found.next().flatten())
+            }
+            Some(Val::Result(Err(error))) => {
+                let message = match error.as_deref() {
+                    Some(Val::String(message)) => message.clone(),
+                    Some(other) => format!("{:?}", other),
+                    None => "no message".to_string(),
+                };
+                return Err(anyhow!(
+                    "{} returned an error: {}{}",
+                    name,
+                    message,
+                    format_logs(&logs)
+                ));
+            }
+            other => {
+                return Err(anyhow!(
+                    "{} returned an unexpected value: {:?}",
+                    name,
+                    other
+                ))
+            }
+        };
+
+        self.state = state;
+        Ok(CallResult {
+            response,
+            logs,
+            calls,
+        })
+    }
+}
+
+/// Whether `name` is `interface`, possibly with a version
+fn is_interface(name: &str, interface: &str) -> bool {
+    name == interface || name.starts_with(&format!("{}@", interface))
+}
+
+/// Arguments after the state, filled into the handler's parameter tuple
+/// by type: strings take `strings` in order and byte lists take `data`
+struct Params {
+    strings: Vec<String>,
+    data: Vec<u8>,
+}
+
+impl Params {
+    fn to_val(&self, ty: &Type) -> Result<Val> {
+        let mut strings = self.strings.iter();
+        let mut fill = |ty: Type| -> Result<Val> {
+            match ty {
+                Type::String => Ok(Val::String(strings.next().cloned().unwrap_or_default())),
+                Type::List(list) if list.ty() == Type::U8 => Ok(bytes(self.data.clone())),
+                ty => default_val(&ty),
+            }
+        };
+
+        match ty {
+            Type::Tuple(tuple) => Ok(Val::Tuple(
+                tuple.types().map(&mut fill).collect::<Result<_>>()?,
+            )),
+            ty => fill(ty.clone()),
+        }
+    }
+}
+
+fn bytes(data: Vec<u8>) -> Val {
+    Val::List(data.into_iter().map(Val::U8).collect())
+}
+
+fn option_bytes(data: Option<Vec<u8>>) -> Val {
+    Val::Option(data.map(|data| Box::new(bytes(data))))
+}
+
+/// Finds every `option<list<u8>>` in a returned value, depth first: a
+/// handler returns its new state first and a response second
+fn collect_option_bytes(value: &Val, found: &mut Vec<Option<Vec<u8>>>) {
+    match value {
+        Val::Option(None) => found.push(None),
+        Val::Option(Some(inner)) => match inner.as_ref() {
+            Val::List(items) if items.iter().all(|v| matches!(v, Val::U8(_))) => found.push(Some(
+                items
+                    .iter()
+                    .map(|v| match v {
+                        Val::U8(b) => *b,
+                        _ => 0,
+                    })
+                    .collect(),
+            )),
+            other => collect_option_bytes(other, found),
+        },
+        Val::Tuple(items) => {
+            for item in items {
+                collect_option_bytes(item, found);
+            }
+        }
+        Val::Record(fields) => {
+            for (_, item) in fields {
+                collect_option_bytes(item, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An empty value of `ty`, returned by mocked imports
+fn default_val(ty: &Type) -> Result<Val> {
+    Ok(match ty {
+        Type::Bool => Val::Bool(false),
+        Type::S8 => Val::S8(0),
+        Type::U8 => Val::U8(0),
+        Type::S16 => Val::S16(0),
+        Type::U16 => Val::U16(0),
+        Type::S32 => Val::S32(0),
+        Type::U32 => Val::U32(0),
+        Type::S64 => Val::S64(0),
+        Type::U64 => Val::U64(0),
+        Type::Float32 => Val::Float32(0.0),
+        Type::Float64 => Val::Float64(0.0),
+        Type::Char => Val::Char('\0'),
+        Type::String => Val::String(String::new()),
+        Type::List(_) => Val::List(Vec::new()),
+        Type::Record(record) => Val::Record(
+            record
+                .fields()
+                .map(|field| Ok((field.name.to_string(), default_val(&field.ty)?)))
+                .collect::<Result<_>>()?,
+        ),
+        Type::Tuple(tuple) => Val::Tuple(
+            tuple
+                .types()
+                .map(|ty| default_val(&ty))
+                .collect::<Result<_>>()?,
+        ),
+        Type::Variant(variant) => {
+            let case = variant
+                .cases()
+                .next()
+                .ok_or_else(|| anyhow!("Variant has no cases"))?;
+            let payload = case.ty.as_ref().map(default_val).transpose()?;
+            Val::Variant(case.name.to_string(), payload.map(Box::new))
+        }
+        Type::Enum(enum_type) => Val::Enum(
+            enum_type
+                .names()
+                .next()
+                .ok_or_else(|| anyhow!("Enum has no cases"))?
+                .to_string(),
+        ),
+        Type::Option(_) => Val::Option(None),
+        Type::Result(result) => {
+            let ok = result.ok().as_ref().map(default_val).transpose()?;
+            Val::Result(Ok(ok.map(Box::new)))
+        }
+        Type::Flags(_) => Val::Flags(Vec::new()),
+        Type::Own(_) | Type::Borrow(_) => return Err(anyhow!("Cannot mock a resource")),
+    })
+}
+
+/// Defines everything the component imports: `log` from the runtime
+/// interface records its message, other functions record the call and
+/// return empty values, or trap when their results cannot be made up, and
+/// resources get a host type
+fn mock_imports(
+    engine: &Engine,
+    component: &Component,
+    linker: &mut Linker<HostState>,
+) -> Result<()> {
+    let mut root = linker.root();
+    for (name, item) in component.component_type().imports(engine) {
+        mock_item(engine, &mut root, None, name, item)?;
+    }
+    Ok(())
+}
+
+fn mock_item(
+    engine: &Engine,
+    linker: &mut LinkerInstance<HostState>,
+    interface: Option<&str>,
+    name: &str,
+    item: ComponentItem,
+) -> Result<()> {
+    match item {
+        ComponentItem::ComponentInstance(instance) => {
+            let mut linker = linker.instance(name)?;
+            for (export, item) in instance.exports(engine) {
+                mock_item(engine, &mut linker, Some(name), export, item)?;
+            }
+        }
+        ComponentItem::ComponentFunc(func) => {
+            let qualified = match interface {
+                Some(interface) => format!("{}#{}", interface, name),
+                None => name.to_string(),
+            };
+
+            if interface.is_some_and(|i| is_interface(i, RUNTIME_INTERFACE)) && name == "log" {
+                linker.func_new(name, |mut store, params, _| {
+                    let message = match params.first() {
+                        Some(Val::String(message)) => message.clone(),
+                        other => format!("{:?}", other),
+                    };
+                    debug!("Actor log: {}", message);
+                    store.data_mut().logs.push(message);
+                    Ok(())
+                })?;
+                return Ok(());
+            }
+
+            match func
+                .results()
+                .map(|ty| default_val(&ty))
+                .collect::<Result<Vec<_>>>()
+            {
+                Ok(results) => linker.func_new(name, move |mut store, _, out| {
+                    store.data_mut().calls.push(qualified.clone());
+                    out.clone_from_slice(&results);
+                    Ok(())
+                })?,
+                Err(e) => {
+                    debug!("Mocking {} as a trap: {}", qualified, e);
+                    linker.func_new(name, move |_, _, _| {
+                        Err(anyhow!(
+                            "{} is not available in the test harness",
+                            qualified
+                        ))
+                    })?
+                }
+            }
+        }
+        ComponentItem::Resource(_) => {
+            linker.resource(name, ResourceType::host::<()>(), |_, _| Ok(()))?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn format_logs(logs: &[String]) -> String {
+    if logs.is_empty() {
+        String::new()
+    } else {
+        format!("\nLogs:\n{}", logs.join("\n"))
+    }
+}
+
+/// Instantiates the actor's built component with mocked imports, calls
+/// `init` and then its handlers for each step of its smoke script,
+/// checking responses and logs as `smoke-test-actor` does against Theater
+pub fn run(actor: &Actor, options: &HarnessOptions) -> Result<SmokeReport> {
+    let script_path = options
+        .script
+        .clone()
+        .unwrap_or_else(|| actor.path.join(SMOKE_SCRIPT));
+    let script = if script_path.exists() {
+        SmokeScript::load(&script_path)?
+    } else {
+        // Still worth checking that the actor loads and initializes
+        SmokeScript::default()
+    };
+
+    let component = compat::component_path(actor)?;
+    if !component.exists() {
+        return Err(anyhow!(
+            "Component {} does not exist; build the actor with build-actor first",
+            component.display()
+        ));
+    }
+
+    let mut report = SmokeReport {
+        kind: "Harness test",
+        actor: actor.name.clone(),
+        script: script_path,
+        actor_id: None,
+        steps: Vec::new(),
+    };
+    let skip_rest = |report: &mut SmokeReport, reason: &str| {
+        for (index, step) in script.steps.iter().enumerate() {
+            report.steps.push(skipped(step.label(index), reason));
+        }
+    };
+
+    let mut harness = match Harness::new(&component) {
+        Ok(harness) => {
+            report.steps.push(step_result("instantiate", Ok(None)));
+            harness
+        }
+        Err(e) => {
+            report.steps.push(step_result("instantiate", Err(e)));
+            report
+                .steps
+                .push(skipped("init".to_string(), "not instantiated"));
+            skip_rest(&mut report, "not instantiated");
+            return Ok(report);
+        }
+    };
+
+    let init_state = options.init_state.as_ref().or(script.init_state.as_ref());
+    harness.state = init_state.map(serde_json::to_vec).transpose()?;
+    let init = harness.call(
+        ACTOR_INTERFACE,
+        "init",
+        Params {
+            strings: vec![HARNESS_ACTOR_ID.to_string()],
+            data: Vec::new(),
+        },
+    );
+    match init {
+        Ok(result) => report.steps.push(StepResult {
+            name: "init".to_string(),
+            outcome: StepOutcome::Passed,
+            detail: describe_call(&result, None),
+        }),
+        Err(e) => {
+            report.steps.push(step_result("init", Err(e)));
+            skip_rest(&mut report, "init failed");
+            return Ok(report);
+        }
+    }
+
+    for (index, step) in script.steps.iter().enumerate() {
+        let result = run_step(&mut harness, step);
+        report.steps.push(match result {
+            Ok(detail) => StepResult {
+                name: step.label(index),
+                outcome: StepOutcome::Passed,
+                detail,
+            },
+            Err((e, detail)) => StepResult {
+                name: step.label(index),
+                outcome: StepOutcome::Failed(format!("{:#}", e)),
+                detail,
+            },
+        });
+    }
+
+    info!(
+        "Harness test of '{}' {}",
+        actor.name,
+        if report.passed() { "passed" } else { "failed" }
+    );
+    Ok(report)
+}
+
+/// Runs a step, returning its detail either way so failures still show
+/// what the actor logged
+fn run_step(
+    harness: &mut Harness,
+    step: &smoke::SmokeStep,
+) -> std::result::Result<Option<String>, (anyhow::Error, Option<String>)> {
+    let mut logs = Vec::new();
+    let mut details = Vec::new();
+
+    let mut attempt = || -> Result<()> {
+        if let Some(message) = &step.send {
+            let data = smoke::payload(message)?;
+            let result = harness.call(
+                MESSAGE_INTERFACE,
+                "handle-send",
+                Params {
+                    strings: Vec::new(),
+                    data,
+                },
+            )?;
+            details.push(format!("Sent {}", message));
+            details.extend(describe_call(&result, None));
+            logs.extend(result.logs);
+        }
+
+        if let Some(request) = &step.request {
+            harness.requests += 1;
+            let data = smoke::payload(request)?;
+            let result = harness.call(
+                MESSAGE_INTERFACE,
+                "handle-request",
+                Params {
+                    strings: vec![format!("request-{}", harness.requests)],
+                    data,
+                },
+            )?;
+            let response = result.response.clone().unwrap_or_default();
+            details.extend(describe_call(&result, Some(&response)));
+            logs.extend(result.logs);
+            check_response(step, &response)?;
+        }
+
+        if let Some(needle) = &step.expect_log {
+            if !logs.iter().any(|line| line.contains(needle.as_str())) {
+                return Err(anyhow!("no log line contains '{}'", needle));
+            }
+        }
+        Ok(())
+    };
+
+    let outcome = attempt();
+    let detail = (!details.is_empty()).then(|| details.join("\n"));
+    match outcome {
+        Ok(()) => Ok(detail),
+        Err(e) => Err((e, detail)),
+    }
+}
+
+fn describe_call(result: &CallResult, response: Option<&[u8]>) -> Option<String> {
+    let mut lines = Vec::new();
+    if let Some(response) = response {
+        lines.push(format!("Response: {}", describe_payload(response)));
+    }
+    for log in &result.logs {
+        lines.push(format!("Log: {}", log));
+    }
+    for call in &result.calls {
+        lines.push(format!("Called {}", call));
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
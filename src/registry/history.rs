@@ -0,0 +1,573 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use git2::{
+    Commit, Delta, DiffFormat, DiffOptions, ErrorCode, Index, IndexAddOption, Oid, Repository,
+    Signature, Sort, Status, StatusOptions, Tree,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tracing::{debug, info, warn};
+
+use super::actor::Actor;
+
+/// Identity for commits and tags when git has no `user.name` and
+/// `user.email` configured
+const FALLBACK_NAME: &str = "Actor Registry";
+const FALLBACK_EMAIL: &str = "actor-registry@localhost";
+
+/// Longest patch `diff` returns before truncating it
+const MAX_DIFF_LEN: usize = 20_000;
+
+/// Changed files listed in a generated commit message body
+const MAX_BODY_FILES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    TypeChanged,
+    Conflicted,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Deleted => "deleted",
+            ChangeKind::Renamed => "renamed",
+            ChangeKind::TypeChanged => "type changed",
+            ChangeKind::Conflicted => "conflicted",
+        }
+    }
+}
+
+/// A changed file, with its path relative to the actor directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Whether the change is in the index rather than only the work tree
+    pub staged: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub id: String,
+    pub message: String,
+    pub author: String,
+    pub changes: Vec<FileChange>,
+}
+
+/// A commit that touched the actor
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub time: Option<DateTime<Utc>>,
+    pub author: String,
+    pub summary: String,
+    /// Release tags of the actor pointing at the commit
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TagInfo {
+    pub name: String,
+    pub target: String,
+    pub summary: String,
+    /// Whether the actor had uncommitted changes the tag doesn't include
+    pub dirty: bool,
+}
+
+/// The git repository an actor lives in, scoped to the actor's directory
+pub struct ActorRepo {
+    repo: Repository,
+    name: String,
+    /// Actor directory relative to the work tree, `/`-separated; empty if
+    /// the actor is the whole repository
+    dir: String,
+}
+
+impl ActorRepo {
+    /// Opens the repository containing the actor, which may be the registry
+    /// root or any directory above it
+    pub fn open(actor: &Actor) -> Result<Self> {
+        let repo = Repository::discover(&actor.path)
+            .with_context(|| format!("Actor '{}' is not in a git repository", actor.name))?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow!("The repository at {:?} has no work tree", repo.path()))?;
+
+        let workdir = fs::canonicalize(workdir)?;
+        let path = fs::canonicalize(&actor.path)?;
+        let relative = path
+            .strip_prefix(&workdir)
+            .with_context(|| format!("Actor {:?} is outside the work tree {:?}", path, workdir))?;
+        let dir = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        debug!("Actor '{}' is at '{}' in {:?}", actor.name, dir, workdir);
+        Ok(Self {
+            repo,
+            name: actor.name.clone(),
+            dir,
+        })
+    }
+
+    fn pathspec(&self) -> &str {
+        if self.dir.is_empty() {
+            "."
+        } else {
+            &self.dir
+        }
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        self.dir.is_empty()
+            || path == self.dir
+            || path
+                .strip_prefix(&self.dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// A repository path relative to the actor directory
+    fn relative<'a>(&self, path: &'a str) -> &'a str {
+        if self.dir.is_empty() {
+            path
+        } else {
+            path.strip_prefix(&self.dir)
+                .map(|rest| rest.trim_start_matches('/'))
+                .unwrap_or(path)
+        }
+    }
+
+    fn head_commit(&self) -> Option<Commit<'_>> {
+        self.repo.head().ok().and_then(|h| h.peel_to_commit().ok())
+    }
+
+    /// Id of the actor's directory in `tree`, if it has one
+    fn subtree_id(&self, tree: &Tree) -> Option<Oid> {
+        if self.dir.is_empty() {
+            Some(tree.id())
+        } else {
+            tree.get_path(Path::new(&self.dir)).ok().map(|e| e.id())
+        }
+    }
+
+    fn signature(&self) -> Result<Signature<'static>> {
+        match self.repo.signature() {
+            Ok(signature) => Ok(signature.to_owned()),
+            Err(e) => {
+                warn!(
+                    "No git identity configured ({}), using {}",
+                    e, FALLBACK_NAME
+                );
+                Ok(Signature::now(FALLBACK_NAME, FALLBACK_EMAIL)?)
+            }
+        }
+    }
+
+    /// Uncommitted changes in the actor directory, staged ones first
+    pub fn status(&self) -> Result<Vec<FileChange>> {
+        let mut options = StatusOptions::new();
+        options
+            .pathspec(self.pathspec())
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .renames_head_to_index(true);
+
+        let statuses = self.repo.statuses(Some(&mut options))?;
+        let mut staged = Vec::new();
+        let mut unstaged = Vec::new();
+
+        for entry in statuses.iter() {
+            let Some(path) = entry.path() else { continue };
+            if !self.contains(path) {
+                continue;
+            }
+            let path = self.relative(path).to_string();
+            let status = entry.status();
+
+            if status.contains(Status::CONFLICTED) {
+                unstaged.push(FileChange {
+                    path,
+                    kind: ChangeKind::Conflicted,
+                    staged: false,
+                });
+                continue;
+            }
+
+            let index_kind = if status.contains(Status::INDEX_NEW) {
+                Some(ChangeKind::Added)
+            } else if status.contains(Status::INDEX_MODIFIED) {
+                Some(ChangeKind::Modified)
+            } else if status.contains(Status::INDEX_DELETED) {
+                Some(ChangeKind::Deleted)
+            } else if status.contains(Status::INDEX_RENAMED) {
+                Some(ChangeKind::Renamed)
+            } else if status.contains(Status::INDEX_TYPECHANGE) {
+                Some(ChangeKind::TypeChanged)
+            } else {
+                None
+            };
+            let worktree_kind = if status.contains(Status::WT_NEW) {
+                Some(ChangeKind::Added)
+            } else if status.contains(Status::WT_MODIFIED) {
+                Some(ChangeKind::Modified)
+            } else if status.contains(Status::WT_DELETED) {
+                Some(ChangeKind::Deleted)
+            } else if status.contains(Status::WT_RENAMED) {
+                Some(ChangeKind::Renamed)
+            } else if status.contains(Status::WT_TYPECHANGE) {
+                Some(ChangeKind::TypeChanged)
+            } else {
+                None
+            };
+
+            if let Some(kind) = index_kind {
+                staged.push(FileChange {
+                    path: path.clone(),
+                    kind,
+                    staged: true,
+                });
+            }
+            if let Some(kind) = worktree_kind {
+                unstaged.push(FileChange {
+                    path,
+                    kind,
+                    staged: false,
+                });
+            }
+        }
+
+        staged.extend(unstaged);
+        Ok(staged)
+    }
+
+    /// Patch of the uncommitted changes against HEAD, untracked files
+    /// included, truncated to a readable length
+    pub fn diff(&self) -> Result<String> {
+        let head_tree = match self.head_commit() {
+            Some(commit) => Some(commit.tree()?),
+            None => None,
+        };
+
+        let mut options = DiffOptions::new();
+        options
+            .pathspec(self.pathspec())
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        let diff = self
+            .repo
+            .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))?;
+
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+            patch.len() <= MAX_DIFF_LEN
+        })
+        .or_else(|e| {
+            // Stopping early is reported as a user error
+            if e.code() == ErrorCode::User {
+                Ok(())
+            } else {
+                Err(e)
+            }
+        })?;
+
+        if patch.len() > MAX_DIFF_LEN {
+            let mut end = MAX_DIFF_LEN;
+            while !patch.is_char_boundary(end) {
+                end -= 1;
+            }
+            patch.truncate(end);
+            patch.push_str("\n... (diff truncated)\n");
+        }
+        Ok(patch)
+    }
+
+    /// Commits the changes in the actor directory, and only those, on top
+    /// of HEAD. Changes elsewhere in the repository stay as they are.
+    /// Without a message one is generated from what changed.
+    pub fn commit(&self, message: Option<&str>) -> Result<CommitInfo> {
+        let mut index = self.repo.index()?;
+        index.add_all([self.pathspec()], IndexAddOption::DEFAULT, None)?;
+        index.update_all([self.pathspec()], None)?;
+        index.write()?;
+
+        let head = self.head_commit();
+        let head_tree = match &head {
+            Some(commit) => Some(commit.tree()?),
+            None => None,
+        };
+
+        // HEAD's tree with the actor directory taken from the index
+        let mut scoped = Index::new()?;
+        if let Some(tree) = &head_tree {
+            scoped.read_tree(tree)?;
+        }
+        if self.dir.is_empty() {
+            scoped.clear()?;
+        } else {
+            scoped.remove_dir(Path::new(&self.dir), 0)?;
+        }
+        for entry in index.iter() {
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            if !self.contains(&path) {
+                continue;
+            }
+            if (entry.flags >> 12) & 0x3 != 0 {
+                return Err(anyhow!(
+                    "{} has merge conflicts; resolve them before committing",
+                    path
+                ));
+            }
+            scoped.add(&entry)?;
+        }
+        let tree = self.repo.find_tree(scoped.write_tree_to(&self.repo)?)?;
+
+        let changes = self.tree_changes(head_tree.as_ref(), &tree)?;
+        if changes.is_empty() {
+            return Err(anyhow!("No changes to commit for actor '{}'", self.name));
+        }
+
+        let message = match message {
+            Some(message) => message.to_string(),
+            None => self.generate_message(head_tree.as_ref(), &tree, &changes),
+        };
+
+        let signature = self.signature()?;
+        let parents: Vec<&Commit> = head.iter().collect();
+        let id = self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &parents,
+        )?;
+
+        info!("Committed actor '{}' as {}", self.name, id);
+        Ok(CommitInfo {
+            id: short_id(id),
+            message,
+            author: signature.to_string(),
+            changes,
+        })
+    }
+
+    fn tree_changes(&self, old: Option<&Tree>, new: &Tree) -> Result<Vec<FileChange>> {
+        let mut options = DiffOptions::new();
+        options.pathspec(self.pathspec());
+        let diff = self
+            .repo
+            .diff_tree_to_tree(old, Some(new), Some(&mut options))?;
+
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| {
+                let kind = match delta.status() {
+                    Delta::Added => ChangeKind::Added,
+                    Delta::Deleted => ChangeKind::Deleted,
+                    Delta::Renamed => ChangeKind::Renamed,
+                    Delta::Typechange => ChangeKind::TypeChanged,
+                    Delta::Conflicted => ChangeKind::Conflicted,
+                    _ => ChangeKind::Modified,
+                };
+                let file = match kind {
+                    ChangeKind::Deleted => delta.old_file(),
+                    _ => delta.new_file(),
+                };
+                let path = file.path()?.to_string_lossy().to_string();
+                Some(FileChange {
+                    path: self.relative(&path).to_string(),
+                    kind,
+                    staged: true,
+                })
+            })
+            .collect())
+    }
+
+    /// The manifest version recorded in `tree`
+    fn manifest_version(&self, tree: &Tree) -> Option<String> {
+        let path = if self.dir.is_empty() {
+            "manifest.toml".to_string()
+        } else {
+            format!("{}/manifest.toml", self.dir)
+        };
+        let blob = self
+            .repo
+            .find_blob(tree.get_path(Path::new(&path)).ok()?.id())
+            .ok()?;
+        let manifest: toml::Table =
+            toml::from_str(std::str::from_utf8(blob.content()).ok()?).ok()?;
+        manifest.get("version")?.as_str().map(str::to_string)
+    }
+
+    /// Describes the change: a new actor, a version bump, a dependency
+    /// update or edits to a few files
+    fn generate_message(&self, old: Option<&Tree>, new: &Tree, changes: &[FileChange]) -> String {
+        let is_new = old.and_then(|tree| self.subtree_id(tree)).is_none();
+        let bump = match (
+            old.and_then(|tree| self.manifest_version(tree)),
+            self.manifest_version(new),
+        ) {
+            (Some(old), Some(new)) if old != new => Some((old, new)),
+            _ => None,
+        };
+        let only = |files: &[&str]| changes.iter().all(|c| files.contains(&c.path.as_str()));
+
+        let subject = if is_new {
+            format!("Add actor {}", self.name)
+        } else if let Some((old, new)) = bump {
+            format!("Release {} {} (from {})", self.name, new, old)
+        } else if only(&["Cargo.toml", "Cargo.lock"]) {
+            format!("Update dependencies of {}", self.name)
+        } else {
+            let files: Vec<&str> = changes.iter().take(3).map(|c| c.path.as_str()).collect();
+            let more = changes.len().saturating_sub(files.len());
+            let more = if more > 0 {
+                format!(" and {} more", more)
+            } else {
+                String::new()
+            };
+            format!("Update {}: {}{}", self.name, files.join(", "), more)
+        };
+
+        let mut message = format!("{}\n\n", subject);
+        for change in changes.iter().take(MAX_BODY_FILES) {
+            message.push_str(&format!("- {} {}\n", change.kind.as_str(), change.path));
+        }
+        if changes.len() > MAX_BODY_FILES {
+            message.push_str(&format!(
+                "- ... and {} more\n",
+                changes.len() - MAX_BODY_FILES
+            ));
+        }
+        message
+    }
+
+    /// Release tags of this actor, by the commit they point at
+    fn release_tags(&self) -> Result<BTreeMap<Oid, Vec<String>>> {
+        let mut tags: BTreeMap<Oid, Vec<String>> = BTreeMap::new();
+        for reference in self
+            .repo
+            .references_glob(&format!("refs/tags/{}@*", self.name))?
+        {
+            let reference = reference?;
+            let (Some(name), Ok(commit)) = (reference.shorthand(), reference.peel_to_commit())
+            else {
+                continue;
+            };
+            tags.entry(commit.id()).or_default().push(name.to_string());
+        }
+        Ok(tags)
+    }
+
+    /// The most recent commits that changed the actor directory, newest
+    /// first
+    pub fn log(&self, limit: usize) -> Result<Vec<HistoryEntry>> {
+        if self.head_commit().is_none() {
+            return Ok(Vec::new());
+        }
+
+        let tags = self.release_tags()?;
+        let mut walk = self.repo.revwalk()?;
+        walk.push_head()?;
+        walk.set_sorting(Sort::TIME)?;
+
+        let mut entries = Vec::new();
+        for id in walk {
+            if entries.len() >= limit {
+                break;
+            }
+            let commit = self.repo.find_commit(id?)?;
+            let subtree = self.subtree_id(&commit.tree()?);
+
+            // Like `git log -- <dir>`: skip commits that leave the directory
+            // as one of their parents had it
+            let touched = if commit.parent_count() == 0 {
+                subtree.is_some()
+            } else {
+                commit
+                    .parents()
+                    .map(|parent| parent.tree().map(|tree| self.subtree_id(&tree)))
+                    .collect::<std::result::Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .all(|parent_subtree| parent_subtree != subtree)
+            };
+            if !touched {
+                continue;
+            }
+
+            let author = commit.author();
+            entries.push(HistoryEntry {
+                id: short_id(commit.id()),
+                time: Utc.timestamp_opt(commit.time().seconds(), 0).single(),
+                author: author.name().unwrap_or("unknown").to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                tags: tags.get(&commit.id()).cloned().unwrap_or_default(),
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Tags HEAD as release `<actor>@<version>` with an annotated tag
+    pub fn tag_release(
+        &self,
+        version: &str,
+        message: Option<&str>,
+        force: bool,
+    ) -> Result<TagInfo> {
+        let name = format!("{}@{}", self.name, version);
+        if !git2::Reference::is_valid_name(&format!("refs/tags/{}", name)) {
+            return Err(anyhow!("'{}' is not a valid tag name", name));
+        }
+
+        let head = self
+            .head_commit()
+            .ok_or_else(|| anyhow!("The repository has no commits to tag yet"))?;
+        if self.subtree_id(&head.tree()?).is_none() {
+            return Err(anyhow!(
+                "Actor '{}' is not committed yet; commit it before tagging a release",
+                self.name
+            ));
+        }
+
+        let message = message
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Release {} {}", self.name, version));
+        let signature = self.signature()?;
+        self.repo
+            .tag(&name, head.as_object(), &signature, &message, force)
+            .map_err(|e| match e.code() {
+                ErrorCode::Exists => {
+                    anyhow!("Tag '{}' already exists; pass force to move it", name)
+                }
+                _ => anyhow!("Failed to create tag '{}': {}", name, e),
+            })?;
+
+        info!("Tagged {} at {}", name, head.id());
+        Ok(TagInfo {
+            name,
+            target: short_id(head.id()),
+            summary: head.summary().unwrap_or_default().to_string(),
+            dirty: !self.status()?.is_empty(),
+        })
+    }
+}
+
+/// Abbreviated commit id, as `git log --oneline` shows it
+pub fn short_id(id: Oid) -> String {
+    id.to_string().chars().take(7).collect()
+}
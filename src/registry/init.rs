@@ -0,0 +1,235 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+use walkdir::WalkDir;
+
+use super::config::{RegistryConfig, CONFIG_FILE};
+use super::document::TomlDocument;
+
+/// Directory inside the registry holding the vendored Theater WIT files
+pub const WIT_DIR: &str = "wit";
+
+/// Build outputs and caches kept out of version control
+pub const GITIGNORE_ENTRIES: &[&str] = &["target/", ".build_info/", ".bundles/", ".remote-cache/"];
+
+#[derive(Debug, Clone)]
+pub struct InitOptions {
+    /// Directory of Theater WIT files to vendor into the registry
+    pub wit_source: Option<PathBuf>,
+    /// Initialize a git repository and `.gitignore`
+    pub git: bool,
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        Self {
+            wit_source: None,
+            git: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepStatus {
+    Created,
+    Updated,
+    Unchanged,
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct InitStep {
+    pub name: &'static str,
+    pub status: StepStatus,
+}
+
+/// What `init_registry` did, step by step
+#[derive(Debug, Clone)]
+pub struct InitReport {
+    pub steps: Vec<InitStep>,
+}
+
+impl InitReport {
+    /// Whether the registry was already fully set up
+    pub fn unchanged(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| !matches!(step.status, StepStatus::Created | StepStatus::Updated))
+    }
+}
+
+/// Sets up a registry at `path`. Every step leaves existing files alone
+/// unless they are missing something, so running it again is harmless.
+pub fn init_registry(path: &Path, options: &InitOptions) -> Result<InitReport> {
+    let mut steps = Vec::new();
+    let mut step = |name, status| steps.push(InitStep { name, status });
+
+    step("registry directory", create_directory(path)?);
+    step("config", write_config(path)?);
+
+    step(
+        "WIT files",
+        vendor_wit(path, options.wit_source.as_deref())?,
+    );
+    if path.join(WIT_DIR).is_dir() {
+        step("config wit_source", point_config_at_wit(path)?);
+    }
+
+    if options.git {
+        step("git repository", init_git(path)?);
+        step(".gitignore", write_gitignore(path)?);
+    } else {
+        step(
+            "git repository",
+            StepStatus::Skipped("disabled".to_string()),
+        );
+    }
+
+    info!("Initialized registry at {:?}", path);
+    Ok(InitReport { steps })
+}
+
+fn create_directory(path: &Path) -> Result<StepStatus> {
+    if path.is_dir() {
+        return Ok(StepStatus::Unchanged);
+    }
+    if path.exists() {
+        return Err(anyhow!("Registry path is not a directory: {:?}", path));
+    }
+
+    fs::create_dir_all(path).with_context(|| format!("Failed to create {:?}", path))?;
+    Ok(StepStatus::Created)
+}
+
+fn write_config(path: &Path) -> Result<StepStatus> {
+    let config_path = path.join(CONFIG_FILE);
+    if config_path.exists() {
+        return Ok(StepStatus::Unchanged);
+    }
+
+    fs::write(&config_path, RegistryConfig::commented_defaults()?)
+        .with_context(|| format!("Failed to write {:?}", config_path))?;
+    Ok(StepStatus::Created)
+}
+
+/// Copies the WIT files under `source` into the registry, updating files
+/// whose content changed
+fn vendor_wit(path: &Path, source: Option<&Path>) -> Result<StepStatus> {
+    let target = path.join(WIT_DIR);
+
+    let Some(source) = source else {
+        return Ok(if target.is_dir() {
+            StepStatus::Unchanged
+        } else {
+            StepStatus::Skipped("no WIT source configured".to_string())
+        });
+    };
+
+    if !source.is_dir() {
+        return Err(anyhow!("WIT source is not a directory: {:?}", source));
+    }
+
+    // The registry's config points at its own vendored copy after init
+    if target.is_dir() && fs::canonicalize(source)? == fs::canonicalize(&target)? {
+        return Ok(StepStatus::Unchanged);
+    }
+
+    let existed = target.is_dir();
+    let mut changed = false;
+    for entry in WalkDir::new(source).sort_by_file_name() {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)?;
+        let dest = target.join(relative);
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&dest)?;
+        } else if entry.file_type().is_file() {
+            let content = fs::read(entry.path())?;
+            if fs::read(&dest).ok().as_deref() != Some(content.as_slice()) {
+                debug!("Vendoring {:?}", dest);
+                fs::write(&dest, content).with_context(|| format!("Failed to write {:?}", dest))?;
+                changed = true;
+            }
+        }
+    }
+
+    Ok(match (existed, changed) {
+        (false, _) => StepStatus::Created,
+        (true, true) => StepStatus::Updated,
+        (true, false) => StepStatus::Unchanged,
+    })
+}
+
+/// Makes new actors copy their WIT files from the vendored directory,
+/// unless the registry config already names a source
+fn point_config_at_wit(path: &Path) -> Result<StepStatus> {
+    let mut doc = TomlDocument::load_or_default(path.join(CONFIG_FILE))?;
+    if doc.doc().contains_key("wit_source") {
+        return Ok(StepStatus::Unchanged);
+    }
+
+    doc.set_value(&["wit_source"], Some(&WIT_DIR))?;
+    doc.save()?;
+    Ok(StepStatus::Updated)
+}
+
+fn init_git(path: &Path) -> Result<StepStatus> {
+    match git2::Repository::discover(path) {
+        Ok(repo) => {
+            let workdir = repo.workdir().map(fs::canonicalize).transpose()?;
+            if workdir.as_deref() == Some(fs::canonicalize(path)?.as_path()) {
+                Ok(StepStatus::Unchanged)
+            } else {
+                Ok(StepStatus::Skipped(format!(
+                    "already inside the repository at {}",
+                    repo.workdir().unwrap_or(repo.path()).display()
+                )))
+            }
+        }
+        Err(_) => {
+            git2::Repository::init(path)
+                .with_context(|| format!("Failed to initialize a git repository at {:?}", path))?;
+            Ok(StepStatus::Created)
+        }
+    }
+}
+
+/// Adds the missing build-output entries to `.gitignore`, keeping whatever
+/// else it holds
+fn write_gitignore(path: &Path) -> Result<StepStatus> {
+    let gitignore = path.join(".gitignore");
+    let existing = fs::read_to_string(&gitignore).unwrap_or_default();
+
+    let present: Vec<&str> = existing.lines().map(str::trim).collect();
+    let missing: Vec<&str> = GITIGNORE_ENTRIES
+        .iter()
+        .copied()
+        .filter(|entry| {
+            let bare = entry.trim_end_matches('/');
+            !present.contains(entry) && !present.contains(&bare)
+        })
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(StepStatus::Unchanged);
+    }
+
+    let mut content = existing.clone();
+    if existing.is_empty() {
+        content.push_str("# Build outputs and caches of the actor registry\n");
+    } else if !existing.ends_with('\n') {
+        content.push('\n');
+    }
+    for entry in &missing {
+        content.push_str(entry);
+        content.push('\n');
+    }
+
+    fs::write(&gitignore, content).with_context(|| format!("Failed to write {:?}", gitignore))?;
+    Ok(if existing.is_empty() {
+        StepStatus::Created
+    } else {
+        StepStatus::Updated
+    })
+}
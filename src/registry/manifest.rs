@@ -0,0 +1,192 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value as JsonValue;
+use std::path::Path;
+use toml_edit::{value, ArrayOfTables, Item};
+use tracing::debug;
+
+use theater::config::{HandlerConfig, ManifestConfig};
+
+use super::document::TomlDocument;
+
+/// Key under which manifest.toml lists its handlers
+pub const HANDLERS_KEY: &str = "handler";
+
+/// Manifest fields that can be edited through `ManifestDocument::set_field`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ManifestField {
+    Description,
+    LongDescription,
+    InitState,
+    SaveChain,
+    Version,
+}
+
+impl ManifestField {
+    pub const ALL: [ManifestField; 5] = [
+        ManifestField::Description,
+        ManifestField::LongDescription,
+        ManifestField::InitState,
+        ManifestField::SaveChain,
+        ManifestField::Version,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            ManifestField::Description => "description",
+            ManifestField::LongDescription => "long_description",
+            ManifestField::InitState => "init_state",
+            ManifestField::SaveChain => "save_chain",
+            ManifestField::Version => "version",
+        }
+    }
+
+    /// Whether the field may be removed from the manifest entirely
+    pub fn is_optional(&self) -> bool {
+        !matches!(self, ManifestField::Version)
+    }
+}
+
+/// A manifest.toml opened for editing. Edits are applied to the parsed
+/// document so comments, ordering and formatting survive the round-trip.
+pub struct ManifestDocument {
+    doc: TomlDocument,
+}
+
+impl ManifestDocument {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let doc = TomlDocument::load(&path)
+            .with_context(|| format!("Failed to load manifest from {:?}", path.as_ref()))?;
+
+        Ok(Self { doc })
+    }
+
+    /// Creates a new manifest document at `path`; nothing is written until `save`
+    pub fn create<P: AsRef<Path>>(path: P, manifest: &ManifestConfig) -> Result<Self> {
+        Ok(Self {
+            doc: TomlDocument::from_serializable(path, manifest)?,
+        })
+    }
+
+    /// Parses the document into Theater's manifest type, which doubles as
+    /// validation against Theater's schema
+    pub fn manifest(&self) -> Result<ManifestConfig> {
+        self.doc.deserialize().with_context(|| {
+            format!(
+                "Manifest {:?} is not a valid Theater manifest",
+                self.doc.path()
+            )
+        })
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.doc.doc_mut()["name"] = value(name);
+    }
+
+    /// Points the manifest at a newly built component
+    pub fn set_component(&mut self, component: &str) {
+        self.doc.doc_mut()["component"] = value(component);
+    }
+
+    /// Sets a manifest field from a JSON value. `null` removes optional fields.
+    pub fn set_field(&mut self, field: ManifestField, new_value: &JsonValue) -> Result<()> {
+        let key = field.key();
+        debug!("Setting manifest field '{}' to {}", key, new_value);
+
+        match (field, new_value) {
+            (_, JsonValue::Null) if field.is_optional() => {
+                self.doc.doc_mut().remove(key);
+            }
+            (ManifestField::SaveChain, JsonValue::Bool(b)) => {
+                self.doc.doc_mut()[key] = value(*b);
+            }
+            (ManifestField::Version, JsonValue::String(s)) if s.trim().is_empty() => {
+                return Err(anyhow!("Manifest version cannot be empty"));
+            }
+            (ManifestField::SaveChain, _) => {
+                return Err(anyhow!("Field '{}' must be a boolean", key));
+            }
+            (_, JsonValue::String(s)) => {
+                self.doc.doc_mut()[key] = value(s.as_str());
+            }
+            _ => {
+                return Err(anyhow!("Field '{}' must be a string", key));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends a handler entry, preserving the existing handler entries
+    pub fn add_handler(&mut self, handler: &HandlerConfig) -> Result<()> {
+        let entry = toml_edit::ser::to_document(handler)
+            .with_context(|| "Failed to serialize handler config")?
+            .as_table()
+            .clone();
+
+        let doc = self.doc.doc_mut();
+        match doc.get_mut(HANDLERS_KEY) {
+            Some(Item::ArrayOfTables(handlers)) => handlers.push(entry),
+            Some(Item::Value(toml_edit::Value::Array(handlers))) => {
+                handlers.push(entry.into_inline_table())
+            }
+            Some(_) => {
+                return Err(anyhow!(
+                    "Manifest key '{}' is not a list of handlers",
+                    HANDLERS_KEY
+                ))
+            }
+            None => {
+                let mut handlers = ArrayOfTables::new();
+                handlers.push(entry);
+                doc[HANDLERS_KEY] = Item::ArrayOfTables(handlers);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes every handler entry of the given type, returning how many
+    /// entries were removed
+    pub fn remove_handlers(&mut self, handler_type: &str) -> usize {
+        let is_type = |t: Option<&str>| t == Some(handler_type);
+
+        match self.doc.doc_mut().get_mut(HANDLERS_KEY) {
+            Some(Item::ArrayOfTables(handlers)) => {
+                let before = handlers.len();
+                handlers.retain(|t| !is_type(t.get("type").and_then(|v| v.as_str())));
+                before - handlers.len()
+            }
+            Some(Item::Value(toml_edit::Value::Array(handlers))) => {
+                let before = handlers.len();
+                handlers.retain(|v| {
+                    !is_type(
+                        v.as_inline_table()
+                            .and_then(|t| t.get("type"))
+                            .and_then(|v| v.as_str()),
+                    )
+                });
+                before - handlers.len()
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        // Never write a manifest Theater would refuse to load
+        self.manifest()?;
+        self.doc.save()
+    }
+}
+
+impl std::fmt::Display for ManifestDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
+
+/// Parses a handler entry given as JSON (e.g. `{"type": "runtime", "config": {}}`)
+/// into Theater's typed handler config, validating its parameters
+pub fn parse_handler(entry: &JsonValue) -> Result<HandlerConfig> {
+    serde_json::from_value(entry.clone())
+        .with_context(|| format!("Invalid handler configuration: {}", entry))
+}
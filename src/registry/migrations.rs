@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::DocumentMut;
+use tracing::debug;
+
+use super::document::TomlDocument;
+
+/// Schema version of the config files this build writes
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Version of config files written before `schema_version` existed
+const UNVERSIONED: u32 = 1;
+
+const VERSION_KEY: &str = "schema_version";
+
+/// Upgrades a config from one schema version to the next, editing the
+/// document in place so comments and formatting survive
+type Migrate = fn(&mut DocumentMut) -> Result<()>;
+
+struct Migration {
+    /// Version the migration upgrades from; it produces `from + 1`
+    from: u32,
+    description: &'static str,
+    migrate: Migrate,
+}
+
+/// Every migration in order. Add one here, and bump
+/// `CURRENT_SCHEMA_VERSION`, whenever a change to `RegistryConfig` needs
+/// more than a serde default.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    description: "record the schema version",
+    migrate: |_| Ok(()),
+}];
+
+/// What `migrate_file` did to a config file
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    pub backup: PathBuf,
+    pub applied: Vec<&'static str>,
+}
+
+/// The schema version a config document declares
+pub fn schema_version(doc: &DocumentMut) -> Result<u32> {
+    match doc.get(VERSION_KEY) {
+        None => Ok(UNVERSIONED),
+        Some(item) => item
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow!("{} must be a positive integer", VERSION_KEY)),
+    }
+}
+
+/// Upgrades the config file at `path` to the current schema, first copying
+/// the original next to it. Returns `None` if it was already current.
+pub fn migrate_file(path: &Path) -> Result<Option<MigrationReport>> {
+    let mut doc = TomlDocument::load(path)?;
+    let from = schema_version(doc.doc()).with_context(|| format!("In {:?}", path))?;
+
+    if from > CURRENT_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "{:?} has schema version {}, but this build only understands up to {}; upgrade the actor registry",
+            path,
+            from,
+            CURRENT_SCHEMA_VERSION
+        ));
+    }
+    if from == CURRENT_SCHEMA_VERSION {
+        debug!("{:?} is at schema version {}", path, from);
+        return Ok(None);
+    }
+
+    let backup = backup_path(path, from);
+    fs::copy(path, &backup).with_context(|| format!("Failed to back up {:?}", path))?;
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from) {
+        debug!(
+            "Migrating {:?} from schema {}: {}",
+            path, migration.from, migration.description
+        );
+        (migration.migrate)(doc.doc_mut()).with_context(|| {
+            format!(
+                "Failed to migrate {:?} from schema version {}",
+                path, migration.from
+            )
+        })?;
+        doc.set_value(&[VERSION_KEY], Some(&(migration.from + 1)))?;
+        applied.push(migration.description);
+    }
+    doc.save()?;
+
+    Ok(Some(MigrationReport {
+        from,
+        to: CURRENT_SCHEMA_VERSION,
+        backup,
+        applied,
+    }))
+}
+
+/// `<file>.v<version>-<timestamp>.bak`, so repeated migrations never
+/// overwrite an older backup
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}-{}.bak", version, timestamp));
+    path.with_file_name(name)
+}
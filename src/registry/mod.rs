@@ -1,90 +1,562 @@
 pub mod actor;
+pub mod build;
+pub mod bundle;
+pub mod compat;
 pub mod config;
+pub mod dependencies;
+pub mod document;
+pub mod harness;
+pub mod history;
+pub mod init;
+pub mod manifest;
+pub mod migrations;
+pub mod namespace;
+pub mod release;
+pub mod remote;
+pub mod server;
+pub mod smoke;
+pub mod source;
 
 use anyhow::{anyhow, Context, Result};
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
 use walkdir::WalkDir;
 
 use self::actor::Actor;
-use self::config::RegistryConfig;
+use self::bundle::{ExportInfo, ExportOptions, ImportInfo, ImportOptions};
+use self::compat::CompatibilityReport;
+use self::config::{ConfigLayers, RegistryConfig, ResolvedConfig, CONFIG_FILE};
+use self::document::TomlDocument;
+use self::manifest::ManifestDocument;
+use self::remote::{ActorSummary, RemoteCache, RemoteClient, RemoteListing};
+use self::source::{CloneOptions, SourceRecord, UpdateInfo, UpdateOptions};
+use crate::theater_client::{chain, TheaterClient};
 
 #[derive(Clone)]
 pub struct Registry {
+    /// The writable root; new actors, imports and caches go here
     path: PathBuf,
+    /// Every root in lookup order, starting with `path`
+    roots: Vec<PathBuf>,
     config: Arc<Mutex<RegistryConfig>>,
+    /// The sources the config is resolved from, kept to re-resolve it
+    layers: ConfigLayers,
+}
+
+/// An actor hidden behind an actor of the same name in a root with higher
+/// precedence
+#[derive(Debug, Clone)]
+pub struct ShadowedActor {
+    pub actor: Actor,
+    pub shadowed_by: PathBuf,
 }
 
 impl Registry {
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref().to_path_buf();
+        Self::with_roots(vec![path.as_ref().to_path_buf()])
+    }
+
+    /// Creates a registry over several roots in precedence order. The first
+    /// root is writable and holds the registry config; the others are only
+    /// searched. Roots listed under `extra_roots` in the config are searched
+    /// after them.
+    pub fn with_roots(roots: Vec<PathBuf>) -> Result<Self> {
+        Self::with_config(roots, ConfigLayers::default())
+    }
+
+    /// Like `with_roots`, resolving the config from the given layers around
+    /// the registry's own config file
+    pub fn with_config(roots: Vec<PathBuf>, layers: ConfigLayers) -> Result<Self> {
+        let mut roots = roots.into_iter();
+        let path = roots
+            .next()
+            .ok_or_else(|| anyhow!("No registry path given"))?;
 
         if !path.exists() {
-            return Err(anyhow!("Registry path does not exist: {:?}", path));
+            return Err(anyhow!(
+                "Registry path does not exist: {:?} (run `init` to create it)",
+                path
+            ));
         }
 
         if !path.is_dir() {
             return Err(anyhow!("Registry path is not a directory: {:?}", path));
         }
 
-        // Load or create default config
-        let config_path = path.join(".registry.config.toml");
-        let config = if config_path.exists() {
-            RegistryConfig::load(&config_path)?
-        } else {
+        // Create a config with the defaults commented out, so it does not
+        // shadow the user config
+        let config_path = path.join(CONFIG_FILE);
+        if !config_path.exists() {
             info!("Creating default registry config at {:?}", config_path);
-            let config = RegistryConfig::default();
-            config.save(&config_path)?;
-            config
-        };
+            std::fs::write(&config_path, RegistryConfig::commented_defaults()?)
+                .with_context(|| format!("Failed to write {:?}", config_path))?;
+        }
+        if let Some(report) = migrations::migrate_file(&config_path)? {
+            info!(
+                "Migrated {:?} from schema version {} to {} ({}); the original is at {:?}",
+                config_path,
+                report.from,
+                report.to,
+                report.applied.join(", "),
+                report.backup
+            );
+        }
+        let config = layers.resolve(&config_path)?.config;
+
+        // Whether an actor is writable is decided by its path prefix, so the
+        // other roots are resolved first: `../shared` is not inside the
+        // writable root even though its path starts with it
+        let writable = fs::canonicalize(&path)
+            .with_context(|| format!("Failed to resolve registry path {:?}", path))?;
+        let mut all_roots = vec![path.clone()];
+        let configured = config.extra_roots.iter().map(|root| path.join(root));
+        for root in roots.chain(configured) {
+            if !root.is_dir() {
+                warn!("Skipping registry root {:?}: not a directory", root);
+                continue;
+            }
+            let root = fs::canonicalize(&root)
+                .with_context(|| format!("Failed to resolve registry root {:?}", root))?;
+            if root == writable || all_roots.contains(&root) {
+                continue;
+            }
+            debug!("Adding read-only registry root {:?}", root);
+            all_roots.push(root);
+        }
 
         Ok(Self {
             path,
+            roots: all_roots,
             config: Arc::new(Mutex::new(config)),
+            layers,
         })
     }
 
+    /// The registry's own config file
+    pub fn config_file(&self) -> PathBuf {
+        self.path.join(CONFIG_FILE)
+    }
+
+    /// The effective config
+    pub fn config(&self) -> RegistryConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Re-reads every config layer, reporting where each value comes from
+    pub fn resolve_config(&self) -> Result<ResolvedConfig> {
+        let resolved = self.layers.resolve(&self.config_file())?;
+        *self.config.lock().unwrap() = resolved.config.clone();
+        Ok(resolved)
+    }
+
+    /// Sets `key` in the registry's config file, or removes it so lower
+    /// layers apply again. The file is only written if the resulting config
+    /// is valid.
+    pub fn set_config(&self, key: &str, value: Option<&toml::Value>) -> Result<ResolvedConfig> {
+        let key_path = config::validate_key(key)?;
+        let file = self.config_file();
+
+        let mut doc = TomlDocument::load_or_default(&file)?;
+        doc.set_value(&key_path, value)?;
+        let resolved = self.layers.resolve_with(&file, doc.deserialize()?)?;
+        doc.save()?;
+
+        info!("Set registry config '{}' in {:?}", key, file);
+        *self.config.lock().unwrap() = resolved.config.clone();
+        Ok(resolved)
+    }
+
+    /// Directory of WIT files copied into new actors, if configured
+    pub fn wit_source(&self) -> Option<PathBuf> {
+        self.config().wit_source.map(|dir| self.path.join(dir))
+    }
+
+    /// Directory of saved event chains, relative to the registry unless
+    /// absolute
+    pub fn chain_store(&self) -> Option<PathBuf> {
+        self.config()
+            .chain_store
+            .map(|dir| self.path.join(dir))
+            .or_else(chain::default_chain_store)
+    }
+
+    /// Client for the configured Theater server
+    pub fn theater_client(&self) -> TheaterClient {
+        TheaterClient::new(self.config().theater_addr())
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    /// All roots in lookup order; the first one is writable
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// The root an actor was found in
+    pub fn origin_root(&self, actor: &Actor) -> Option<&Path> {
+        self.roots
+            .iter()
+            .find(|root| actor.path.starts_with(root))
+            .map(|root| root.as_path())
+    }
+
+    /// Whether the registry may modify an actor. Actors in read-only roots,
+    /// copies cached from remotes and release snapshots are never written to.
+    pub fn is_writable(&self, actor: &Actor) -> bool {
+        !release::is_release(actor)
+            && actor.path.starts_with(&self.path)
+            && !actor.path.starts_with(self.path.join(remote::CACHE_DIR))
+    }
+
+    fn ensure_writable(&self, actor: &Actor) -> Result<()> {
+        if self.is_writable(actor) {
+            return Ok(());
+        }
+
+        if release::is_release(actor) {
+            return Err(anyhow!(
+                "Actor '{}' is a release snapshot and cannot be modified",
+                actor.name
+            ));
+        }
+
+        Err(anyhow!(
+            "Actor '{}' at {:?} is read-only; only actors in {:?} can be modified",
+            actor.name,
+            actor.path,
+            self.path
+        ))
+    }
+
+    /// Finds an actor by name. `name@version` resolves to the release
+    /// snapshot of that version. Only the local roots are searched, in order.
     pub fn find_actor(&self, name: &str) -> Result<Actor> {
-        let actor_path = self.path.join(name);
+        if let (base, Some(version)) = release::parse_reference(name)? {
+            let actor = self.find_actor(base)?;
+            return release::load_release(&actor, &version);
+        }
 
-        if !actor_path.exists() {
-            return Err(anyhow!("Actor '{}' not found in registry", name));
+        namespace::validate_name(name)?;
+
+        for root in &self.roots {
+            let actor_path = root.join(name);
+            if actor_path.join("manifest.toml").exists() {
+                return Self::load_actor(root, &actor_path);
+            }
         }
 
-        Actor::from_path(actor_path)
+        Err(anyhow!("Actor '{}' not found in registry", name))
     }
 
-    pub fn list_actors(&self) -> Result<Vec<Actor>> {
+    /// Like `find_actor`, but falls back to the configured remotes when the
+    /// actor is not found locally. Each remote may cost a network round
+    /// trip, so only tools that read an actor use this.
+    pub fn resolve_actor(&self, name: &str) -> Result<Actor> {
+        match self.find_actor(name) {
+            Ok(actor) => Ok(actor),
+            Err(e) if namespace::validate_name(name).is_err() => Err(e),
+            Err(e) => self.find_remote_actor(name).ok_or(e),
+        }
+    }
+
+    /// Like `find_actor`, but fails if the actor cannot be modified
+    pub fn find_writable_actor(&self, name: &str) -> Result<Actor> {
+        let actor = self.find_actor(name)?;
+        self.ensure_writable(&actor)?;
+        Ok(actor)
+    }
+
+    /// Clients for the configured remotes, in lookup order
+    pub fn remotes(&self) -> Vec<RemoteClient> {
+        let config = self.config.lock().unwrap();
+        config
+            .remotes
+            .iter()
+            .cloned()
+            .map(RemoteClient::new)
+            .collect()
+    }
+
+    fn remote(&self, name: Option<&str>) -> Result<RemoteClient> {
+        let mut remotes = self.remotes().into_iter();
+        match name {
+            Some(name) => remotes
+                .find(|r| r.name() == name)
+                .ok_or_else(|| anyhow!("No remote named '{}' is configured", name)),
+            None => remotes
+                .next()
+                .ok_or_else(|| anyhow!("No remotes are configured")),
+        }
+    }
+
+    /// Looks an actor up in each remote in turn, caching what it fetches
+    fn find_remote_actor(&self, name: &str) -> Option<Actor> {
+        for client in self.remotes() {
+            let cache = RemoteCache::new(&self.path, client.name());
+            match remote::resolve(&client, &cache, name) {
+                Ok(Some(actor)) => return Some(actor),
+                Ok(None) => {}
+                Err(e) => warn!(
+                    "Failed to fetch '{}' from remote '{}': {}",
+                    name,
+                    client.name(),
+                    e
+                ),
+            }
+        }
+
+        None
+    }
+
+    /// Lists the actors of one remote, or of every remote when `name` is `None`
+    pub fn list_remote_actors(&self, name: Option<&str>) -> Result<Vec<RemoteListing>> {
+        let clients = match name {
+            Some(_) => vec![self.remote(name)?],
+            None => self.remotes(),
+        };
+
+        clients
+            .iter()
+            .map(|client| remote::list_remote(client, &RemoteCache::new(&self.path, client.name())))
+            .collect()
+    }
+
+    /// Bundles a local actor and uploads it to a remote
+    pub fn publish_actor(
+        &self,
+        name: &str,
+        remote: Option<&str>,
+        overwrite: bool,
+    ) -> Result<ActorSummary> {
+        let client = self.remote(remote)?;
+        let actor = self.find_actor(name)?;
+
+        let options = ExportOptions {
+            include_component: compat::component_path(&actor)
+                .map(|p| p.is_file())
+                .unwrap_or(false),
+            checksums: true,
+        };
+        let bundle = self.export_actor(name, None, &options)?;
+
+        info!("Publishing actor '{}' to remote '{}'", name, client.name());
+        client.publish(&actor.name, &bundle.path, overwrite)
+    }
+
+    /// Loads the actor at `path`, naming it after its namespaced path in `root`
+    fn load_actor(root: &Path, path: &Path) -> Result<Actor> {
+        let mut actor = Actor::from_path(path)?;
+        if let Some(name) = namespace::qualified_name(root, path) {
+            actor.name = name;
+        }
+        Ok(actor)
+    }
+
+    /// Lists the actors under one root. Directories without a manifest.toml
+    /// are namespaces and are searched for more actors; actors never nest.
+    fn scan_root(root: &Path) -> Result<Vec<Actor>> {
         let mut actors = Vec::new();
 
-        for entry in WalkDir::new(&self.path).min_depth(1).max_depth(1) {
+        let mut walker = WalkDir::new(root)
+            .min_depth(1)
+            .max_depth(namespace::MAX_DEPTH)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.file_type().is_dir()
+                    && entry
+                        .file_name()
+                        .to_str()
+                        .map(namespace::is_valid_segment)
+                        .unwrap_or(false)
+            });
+
+        while let Some(entry) = walker.next() {
             let entry = entry?;
-            if entry.file_type().is_dir() {
-                let path = entry.path();
-
-                // Skip directories that don't contain a manifest.toml
-                let manifest_path = path.join("manifest.toml");
-                if !manifest_path.exists() {
-                    debug!("Skipping directory without manifest.toml: {:?}", path);
-                    continue;
-                }
+            let path = entry.path();
+
+            // Skip directories that don't contain a manifest.toml
+            let manifest_path = path.join("manifest.toml");
+            if !manifest_path.exists() {
+                debug!(
+                    "Searching directory without manifest.toml as a namespace: {:?}",
+                    path
+                );
+                continue;
+            }
+
+            walker.skip_current_dir();
+            match Self::load_actor(root, path) {
+                Ok(actor) => actors.push(actor),
+                Err(e) => warn!("Failed to load actor from {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(actors)
+    }
+
+    /// Scans every root, splitting the actors that resolve by name from the
+    /// ones hidden behind a same-named actor in an earlier root
+    fn scan_roots(&self) -> Result<(Vec<Actor>, Vec<ShadowedActor>)> {
+        let mut actors: Vec<(Actor, &PathBuf)> = Vec::new();
+        let mut shadowed = Vec::new();
 
-                match Actor::from_path(path) {
-                    Ok(actor) => actors.push(actor),
-                    Err(e) => warn!("Failed to load actor from {}: {}", path.display(), e),
+        for root in &self.roots {
+            for actor in Self::scan_root(root)? {
+                match actors.iter().find(|(a, _)| a.name == actor.name) {
+                    Some((_, winner)) => shadowed.push(ShadowedActor {
+                        actor,
+                        shadowed_by: winner.to_path_buf(),
+                    }),
+                    None => actors.push((actor, root)),
                 }
             }
         }
 
+        Ok((actors.into_iter().map(|(a, _)| a).collect(), shadowed))
+    }
+
+    pub fn list_actors(&self) -> Result<Vec<Actor>> {
+        let (actors, shadowed) = self.scan_roots()?;
+
+        for hidden in &shadowed {
+            warn!(
+                "Actor '{}' at {:?} is shadowed by the one in {:?}",
+                hidden.actor.name, hidden.actor.path, hidden.shadowed_by
+            );
+        }
+
+        Ok(actors)
+    }
+
+    /// Lists the actors in a namespace and its sub-namespaces
+    pub fn list_namespace(&self, namespace: &str) -> Result<Vec<Actor>> {
+        Ok(self
+            .list_actors()?
+            .into_iter()
+            .filter(|actor| namespace::in_namespace(&actor.name, namespace))
+            .collect())
+    }
+
+    /// Every namespace that contains at least one actor
+    pub fn namespaces(&self) -> Result<Vec<String>> {
+        let mut namespaces = std::collections::BTreeSet::new();
+        for actor in self.list_actors()? {
+            let mut namespace = namespace::split(&actor.name).0;
+            while let Some(current) = namespace {
+                namespaces.insert(current.to_string());
+                namespace = namespace::split(current).0;
+            }
+        }
+        Ok(namespaces.into_iter().collect())
+    }
+
+    /// The registry config with the overrides of the namespaces enclosing
+    /// `name` applied
+    pub fn config_for(&self, name: &str) -> Result<RegistryConfig> {
+        let config = self.config.lock().unwrap().clone();
+        namespace::effective_config(&self.roots, &config, namespace::split(name).0)
+    }
+
+    /// Actors that cannot be reached by name because an earlier root has an
+    /// actor with the same name
+    pub fn shadowed_actors(&self) -> Result<Vec<ShadowedActor>> {
+        Ok(self.scan_roots()?.1)
+    }
+
+    pub fn export_actor(
+        &self,
+        name: &str,
+        dest: Option<&Path>,
+        options: &ExportOptions,
+    ) -> Result<ExportInfo> {
+        let actor = self.find_actor(name)?;
+
+        let dest = match dest {
+            Some(dest) => dest.to_path_buf(),
+            None => {
+                let version = actor
+                    .manifest
+                    .as_ref()
+                    .map(|m| m.version.clone())
+                    .unwrap_or_else(|| "0.0.0".to_string());
+                self.path
+                    .join(".bundles")
+                    .join(format!("{}-{}.tar.zst", actor.name, version))
+            }
+        };
+
+        bundle::export_actor(&actor, &dest, options)
+    }
+
+    /// Imports a bundle into the writable root, returning the actor and
+    /// whether its checksums were verified
+    pub fn import_actor(
+        &self,
+        bundle: &Path,
+        options: &ImportOptions,
+    ) -> Result<(Actor, ImportInfo)> {
+        let info = bundle::import_bundle(bundle, &self.path, options)?;
+        Ok((Self::load_actor(&self.path, &info.path)?, info))
+    }
+
+    /// Clones an actor from a git repository into the writable root
+    pub fn clone_actor(&self, options: &CloneOptions) -> Result<(Actor, SourceRecord)> {
+        let info = source::clone_actor(&self.path, options)?;
+        Ok((Self::load_actor(&self.path, &info.path)?, info.source))
+    }
+
+    /// Pulls upstream changes into an actor created by `clone_actor`
+    pub fn update_actor_from_source(
+        &self,
+        name: &str,
+        options: &UpdateOptions,
+    ) -> Result<UpdateInfo> {
+        let actor = self.find_writable_actor(name)?;
+        source::update_actor(&self.path, &actor, options)
+    }
+
+    /// Resolves the actors a bulk operation applies to: every actor in the
+    /// registry when `all` is set, otherwise the named ones
+    pub fn select_actors(&self, names: &[String], all: bool) -> Result<Vec<Actor>> {
+        if all {
+            return self.list_actors();
+        }
+
+        if names.is_empty() {
+            return Err(anyhow!("No actors selected"));
+        }
+
+        names.iter().map(|name| self.find_actor(name)).collect()
+    }
+
+    /// Like `select_actors` for operations that modify the actors: `all`
+    /// skips read-only actors, naming one is an error
+    pub fn select_writable_actors(&self, names: &[String], all: bool) -> Result<Vec<Actor>> {
+        if all {
+            return Ok(self
+                .list_actors()?
+                .into_iter()
+                .filter(|actor| self.is_writable(actor))
+                .collect());
+        }
+
+        let actors = self.select_actors(names, false)?;
+        for actor in &actors {
+            self.ensure_writable(actor)?;
+        }
         Ok(actors)
     }
 
+    /// Creates an actor in the writable root. Namespaced names such as
+    /// `team-a/logger` create the namespace directories as needed, and the
+    /// template defaults to the namespace's `default_template`.
     pub fn create_actor(&self, name: &str, template: Option<&str>) -> Result<Actor> {
+        namespace::validate_name(name)?;
         let actor_path = self.path.join(name);
 
         if actor_path.exists() {
@@ -95,8 +567,41 @@ impl Registry {
             ));
         }
 
-        // Create the actor using the template system
-        Actor::create(name, actor_path, template)
+        if let Some(existing) = self.roots[1..]
+            .iter()
+            .map(|root| root.join(name))
+            .find(|path| path.exists())
+        {
+            warn!(
+                "New actor '{}' will shadow the existing actor at {:?}",
+                name, existing
+            );
+        }
+
+        let config = self.config_for(name)?;
+        let template = template.unwrap_or(&config.default_template);
+
+        // Create the actor using the template system. The crate and manifest
+        // are named after the last segment, the registry uses the full name.
+        let wit_source = self.wit_source();
+        let mut actor = Actor::create(
+            namespace::base_name(name),
+            actor_path,
+            Some(template),
+            wit_source.as_deref(),
+        )?;
+        actor.name = name.to_string();
+        Ok(actor)
+    }
+
+    pub fn manifest_document(&self, name: &str) -> Result<ManifestDocument> {
+        let actor = self.find_actor(name)?;
+        ManifestDocument::load(actor.path.join("manifest.toml"))
+    }
+
+    pub fn check_compatibility(&self, name: &str) -> Result<CompatibilityReport> {
+        let actor = self.find_actor(name)?;
+        compat::check_actor(&actor)
     }
 
     // Note: build_actor method has been removed since we now use the `theater build` command directly in the tool implementation
@@ -122,4 +627,3 @@ impl Registry {
         ]
     }
 }
-
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::debug;
+
+use super::config::RegistryConfig;
+
+/// Per-namespace overrides of the registry config, read from this file in
+/// the namespace directory
+pub const NAMESPACE_CONFIG_FILE: &str = ".namespace.config.toml";
+
+/// Separator between namespace segments and the actor name, e.g. `team-a/logger`
+pub const SEPARATOR: char = '/';
+
+/// How deep listings look for namespaced actors
+pub const MAX_DEPTH: usize = 8;
+
+fn segment_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^[A-Za-z0-9_][A-Za-z0-9_.-]*$").unwrap())
+}
+
+/// Whether a single namespace segment or actor name is a plain, visible path
+/// component
+pub fn is_valid_segment(segment: &str) -> bool {
+    segment_pattern().is_match(segment)
+}
+
+/// Validates a possibly namespaced actor name such as `team-a/logger`
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(anyhow!("Actor name cannot be empty"));
+    }
+
+    for segment in name.split(SEPARATOR) {
+        if !is_valid_segment(segment) {
+            return Err(anyhow!(
+                "Invalid name '{}': '{}' must start with a letter, digit or '_' and contain only letters, digits, '_', '-' and '.'",
+                name,
+                segment
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits `team-a/sub/logger` into `(Some("team-a/sub"), "logger")`
+pub fn split(name: &str) -> (Option<&str>, &str) {
+    match name.rsplit_once(SEPARATOR) {
+        Some((namespace, base)) => (Some(namespace), base),
+        None => (None, name),
+    }
+}
+
+/// The actor name without its namespace
+pub fn base_name(name: &str) -> &str {
+    split(name).1
+}
+
+/// Whether `name` lives in `namespace` or one of its sub-namespaces
+pub fn in_namespace(name: &str, namespace: &str) -> bool {
+    let namespace = namespace.trim_matches(SEPARATOR);
+    namespace.is_empty()
+        || name
+            .strip_prefix(namespace)
+            .map(|rest| rest.starts_with(SEPARATOR))
+            .unwrap_or(false)
+}
+
+/// The registry name of the actor at `path` inside `root`, with namespace
+/// segments joined by `/`
+pub fn qualified_name(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+
+    let segments = relative
+        .components()
+        .map(|c| match c {
+            Component::Normal(s) => s.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if segments.is_empty() {
+        return None;
+    }
+
+    Some(segments.join(&SEPARATOR.to_string()))
+}
+
+/// Overrides a namespace applies on top of the registry config. Unset fields
+/// inherit from the enclosing namespace or the registry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamespaceConfig {
+    pub default_template: Option<String>,
+    pub default_interfaces: Option<Vec<String>>,
+    pub build_cache_enabled: Option<bool>,
+}
+
+impl NamespaceConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read namespace config {:?}", path.as_ref()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse namespace config {:?}", path.as_ref()))
+    }
+
+    pub fn apply(&self, config: &mut RegistryConfig) {
+        if let Some(template) = &self.default_template {
+            config.default_template = template.clone();
+        }
+        if let Some(interfaces) = &self.default_interfaces {
+            config.default_interfaces = interfaces.clone();
+        }
+        if let Some(enabled) = self.build_cache_enabled {
+            config.build_cache_enabled = enabled;
+        }
+    }
+}
+
+/// Applies the overrides of `namespace` and every namespace enclosing it,
+/// outermost first, to the registry config
+pub fn effective_config(
+    roots: &[PathBuf],
+    base: &RegistryConfig,
+    namespace: Option<&str>,
+) -> Result<RegistryConfig> {
+    let mut config = base.clone();
+
+    let Some(namespace) = namespace else {
+        return Ok(config);
+    };
+
+    let mut prefix = String::new();
+    for segment in namespace.split(SEPARATOR) {
+        if !prefix.is_empty() {
+            prefix.push(SEPARATOR);
+        }
+        prefix.push_str(segment);
+
+        // The first root defining the namespace wins, like actor lookups
+        let file = roots
+            .iter()
+            .map(|root| root.join(&prefix).join(NAMESPACE_CONFIG_FILE))
+            .find(|file| file.exists());
+
+        if let Some(file) = file {
+            debug!("Applying namespace config {:?}", file);
+            NamespaceConfig::load(&file)?.apply(&mut config);
+        }
+    }
+
+    Ok(config)
+}
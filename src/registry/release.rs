@@ -0,0 +1,273 @@
+use anyhow::{anyhow, Context, Result};
+use semver::Version;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use toml_edit::value;
+use tracing::{debug, info, warn};
+
+use super::actor::Actor;
+use super::build::{theater_build_command, BuildOptions};
+use super::compat;
+use super::document::TomlDocument;
+use super::manifest::{ManifestDocument, ManifestField};
+use super::namespace;
+
+/// Directory inside an actor that holds its release snapshots
+pub const RELEASES_DIR: &str = "releases";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionBump {
+    Major,
+    Minor,
+    Patch,
+    Exact(Version),
+}
+
+impl std::str::FromStr for VersionBump {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "major" => Ok(VersionBump::Major),
+            "minor" => Ok(VersionBump::Minor),
+            "patch" => Ok(VersionBump::Patch),
+            _ => Version::parse(s)
+                .map(VersionBump::Exact)
+                .map_err(|e| anyhow!("Invalid version bump '{}': {}", s, e)),
+        }
+    }
+}
+
+impl VersionBump {
+    /// The version after the bump; an exact version must be newer than the
+    /// current one so releases only move forward
+    pub fn apply(&self, current: &Version) -> Result<Version> {
+        match self {
+            VersionBump::Major => Ok(Version::new(current.major + 1, 0, 0)),
+            VersionBump::Minor => Ok(Version::new(current.major, current.minor + 1, 0)),
+            VersionBump::Patch => Ok(Version::new(
+                current.major,
+                current.minor,
+                current.patch + 1,
+            )),
+            VersionBump::Exact(version) if version > current => Ok(version.clone()),
+            VersionBump::Exact(version) => Err(anyhow!(
+                "Version {} is not newer than the current version {}",
+                version,
+                current
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseInfo {
+    pub name: String,
+    pub previous_version: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub component: PathBuf,
+    pub build_output: String,
+}
+
+/// Splits a `name@version` reference into its parts. The version must be
+/// strict semver since it ends up as a directory name under `releases/`.
+pub fn parse_reference(reference: &str) -> Result<(&str, Option<Version>)> {
+    match reference.split_once('@') {
+        Some((name, version)) => {
+            let version = Version::parse(version)
+                .map_err(|e| anyhow!("Invalid version '{}' in '{}': {}", version, reference, e))?;
+            Ok((name, Some(version)))
+        }
+        None => Ok((reference, None)),
+    }
+}
+
+/// Whether `actor` is a release snapshot loaded by `load_release`
+pub fn is_release(actor: &Actor) -> bool {
+    actor.name.contains('@')
+}
+
+pub fn release_dir(actor: &Actor, version: &Version) -> PathBuf {
+    actor.path.join(RELEASES_DIR).join(version.to_string())
+}
+
+/// The version an actor is currently at, taken from Cargo.toml and falling
+/// back to the manifest
+pub fn current_version(actor: &Actor) -> Result<Version> {
+    let version = actor
+        .cargo_config
+        .as_ref()
+        .map(|c| c.package.version.clone())
+        .or_else(|| actor.manifest.as_ref().map(|m| m.version.clone()))
+        .ok_or_else(|| anyhow!("Actor '{}' has no version", actor.name))?;
+
+    Version::parse(&version).with_context(|| {
+        format!(
+            "Actor '{}' has an invalid version '{}'",
+            actor.name, version
+        )
+    })
+}
+
+/// Lists the released versions of an actor, oldest first
+pub fn list_versions(actor: &Actor) -> Result<Vec<Version>> {
+    let releases = actor.path.join(RELEASES_DIR);
+    if !releases.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(&releases)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name();
+        match name.to_str().map(Version::parse) {
+            Some(Ok(version)) => versions.push(version),
+            _ => debug!("Ignoring non-version directory {:?} in releases", name),
+        }
+    }
+
+    versions.sort();
+    Ok(versions)
+}
+
+/// Writes `version` to both Cargo.toml and manifest.toml
+fn set_version(actor: &Actor, version: &Version) -> Result<()> {
+    let version = version.to_string();
+
+    let cargo_path = actor.path.join("Cargo.toml");
+    if cargo_path.exists() {
+        let mut cargo = TomlDocument::load(&cargo_path)?;
+        cargo.doc_mut()["package"]["version"] = value(version.as_str());
+        cargo.save()?;
+    }
+
+    let mut manifest = ManifestDocument::load(actor.path.join("manifest.toml"))?;
+    manifest.set_field(ManifestField::Version, &serde_json::json!(version))?;
+    manifest.save()
+}
+
+/// Copies the built component into `releases/<version>/` together with a
+/// frozen copy of the manifest pointing at it. The component path is kept
+/// relative to the release directory so the snapshot survives the registry
+/// being moved or exported.
+fn snapshot(actor: &Actor, version: &Version) -> Result<(PathBuf, PathBuf)> {
+    let component = compat::component_path(actor)?;
+    if !component.exists() {
+        return Err(anyhow!(
+            "Built component not found at {}",
+            component.display()
+        ));
+    }
+
+    let dir = release_dir(actor, version);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create release directory {:?}", dir))?;
+
+    let component_file = format!("{}.wasm", namespace::base_name(&actor.name));
+    let release_component = dir.join(&component_file);
+    fs::copy(&component, &release_component)
+        .with_context(|| format!("Failed to copy component to {:?}", release_component))?;
+
+    let manifest_path = dir.join("manifest.toml");
+    fs::copy(actor.path.join("manifest.toml"), &manifest_path)?;
+    let mut manifest = ManifestDocument::load(&manifest_path)?;
+    manifest.set_component(&component_file);
+    manifest.save()?;
+
+    Ok((dir, release_component))
+}
+
+/// Bumps the actor's version, builds it and stores the component and a
+/// frozen manifest under `releases/<version>/`. If the build fails the
+/// version change is rolled back.
+pub fn release(actor: &Actor, bump: &VersionBump, options: &BuildOptions) -> Result<ReleaseInfo> {
+    let previous = current_version(actor)?;
+    let version = bump.apply(&previous)?;
+
+    let dir = release_dir(actor, &version);
+    if dir.exists() {
+        return Err(anyhow!(
+            "Actor '{}' already has a release {}",
+            actor.name,
+            version
+        ));
+    }
+
+    // Keep the original files around so a failed build leaves no trace
+    let originals = ["Cargo.toml", "manifest.toml"]
+        .iter()
+        .map(|file| actor.path.join(file))
+        .filter(|path| path.exists())
+        .map(|path| fs::read_to_string(&path).map(|content| (path, content)))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let restore = || {
+        for (path, content) in &originals {
+            if let Err(e) = fs::write(path, content) {
+                warn!("Failed to restore {:?}: {}", path, e);
+            }
+        }
+    };
+
+    info!(
+        "Releasing actor '{}' {} -> {}",
+        actor.name, previous, version
+    );
+
+    let result = set_version(actor, &version).and_then(|_| {
+        let mut cmd = theater_build_command(&actor.path, options);
+        info!("Executing: {:?}", cmd);
+        let output = cmd
+            .output()
+            .with_context(|| "Failed to execute theater build command")?;
+
+        let build_output = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        if !output.status.success() {
+            return Err(anyhow!("Build failed:\n{}", build_output));
+        }
+
+        // Reload so we pick up the component path the build wrote
+        let built = Actor::from_path(&actor.path)?;
+        let (path, component) = snapshot(&built, &version)?;
+        Ok((path, component, build_output))
+    });
+
+    match result {
+        Ok((path, component, build_output)) => Ok(ReleaseInfo {
+            name: actor.name.clone(),
+            previous_version: previous.to_string(),
+            version: version.to_string(),
+            path,
+            component,
+            build_output,
+        }),
+        Err(e) => {
+            restore();
+            if dir.exists() {
+                let _ = fs::remove_dir_all(&dir);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Loads a release snapshot as an actor named `name@version`
+pub fn load_release(actor: &Actor, version: &Version) -> Result<Actor> {
+    let dir = release_dir(actor, version);
+    if !dir.exists() {
+        return Err(anyhow!("Actor '{}' has no release {}", actor.name, version));
+    }
+
+    let mut release = Actor::from_path(&dir)?;
+    release.name = format!("{}@{}", actor.name, version);
+    Ok(release)
+}
@@ -0,0 +1,353 @@
+use anyhow::{anyhow, Context, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::actor::Actor;
+use super::bundle::{self, Collision, ImportOptions};
+use super::config::RemoteConfig;
+use super::namespace;
+use super::release;
+
+/// Path prefix of every route in the registry HTTP protocol
+pub const API_PREFIX: &str = "/api/v1";
+
+/// Address `serve` listens on when none is given
+pub const DEFAULT_SERVER_ADDR: &str = "127.0.0.1:7878";
+
+/// Directory inside the registry holding actors fetched from remotes
+pub const CACHE_DIR: &str = ".remote-cache";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Escapes the namespace separator so a namespaced name fits in a single
+/// path segment of a URL
+pub fn encode_name(name: &str) -> String {
+    name.replace(namespace::SEPARATOR, "%2F")
+}
+
+pub fn decode_name(segment: &str) -> String {
+    segment.replace("%2F", "/").replace("%2f", "/")
+}
+
+/// What the protocol reports about an actor, both in listings and in the
+/// metadata of a single actor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActorSummary {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub build_status: String,
+    /// Released versions, oldest first
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+impl ActorSummary {
+    pub fn from_actor(actor: &Actor) -> Self {
+        let versions = release::list_versions(actor)
+            .map(|versions| versions.iter().map(|v| v.to_string()).collect())
+            .unwrap_or_else(|e| {
+                warn!("Failed to list versions of '{}': {}", actor.name, e);
+                Vec::new()
+            });
+
+        Self {
+            name: actor.name.clone(),
+            version: actor.manifest.as_ref().map(|m| m.version.clone()),
+            description: actor.manifest.as_ref().and_then(|m| m.description.clone()),
+            build_status: actor.build_info.build_status.to_string(),
+            versions,
+        }
+    }
+}
+
+/// Body of every non-2xx response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// Actors listed by one remote, possibly served from the offline cache
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteListing {
+    pub remote: String,
+    pub url: String,
+    pub actors: Vec<ActorSummary>,
+    pub from_cache: bool,
+}
+
+/// Blocking client for a remote registry
+pub struct RemoteClient {
+    config: RemoteConfig,
+    agent: ureq::Agent,
+}
+
+impl RemoteClient {
+    pub fn new(config: RemoteConfig) -> Self {
+        let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+        Self { config, agent }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn url(&self) -> &str {
+        &self.config.url
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!(
+            "{}{}/actors{}",
+            self.config.url.trim_end_matches('/'),
+            API_PREFIX,
+            path
+        )
+    }
+
+    /// Turns a failed request into an error carrying the server's message
+    fn request_error(&self, err: ureq::Error) -> anyhow::Error {
+        match err {
+            ureq::Error::Status(code, response) => {
+                let message = response
+                    .into_json::<ErrorBody>()
+                    .map(|body| body.error)
+                    .unwrap_or_else(|_| "no details".to_string());
+                anyhow!("Remote '{}' returned {}: {}", self.name(), code, message)
+            }
+            ureq::Error::Transport(transport) => {
+                anyhow!("Remote '{}' is unreachable: {}", self.name(), transport)
+            }
+        }
+    }
+
+    pub fn list_actors(&self) -> Result<Vec<ActorSummary>> {
+        let response = self
+            .agent
+            .get(&self.endpoint(""))
+            .call()
+            .map_err(|e| self.request_error(e))?;
+
+        response
+            .into_json()
+            .with_context(|| format!("Invalid actor listing from remote '{}'", self.name()))
+    }
+
+    /// Fetches an actor's metadata, `None` if the remote does not have it
+    pub fn metadata(&self, name: &str) -> Result<Option<ActorSummary>> {
+        match self
+            .agent
+            .get(&self.endpoint(&format!("/{}", encode_name(name))))
+            .call()
+        {
+            Ok(response) => response
+                .into_json()
+                .map(Some)
+                .with_context(|| format!("Invalid metadata for '{}' from '{}'", name, self.name())),
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(self.request_error(e)),
+        }
+    }
+
+    /// Downloads an actor's bundle to `dest`
+    pub fn download_bundle(&self, name: &str, dest: &Path) -> Result<()> {
+        let response = self
+            .agent
+            .get(&self.endpoint(&format!("/{}/bundle", encode_name(name))))
+            .call()
+            .map_err(|e| self.request_error(e))?;
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write next to the destination so a dropped connection never
+        // leaves a truncated bundle in the cache
+        let partial = dest.with_extension("partial");
+        let mut file = fs::File::create(&partial)
+            .with_context(|| format!("Failed to create {:?}", partial))?;
+        io::copy(&mut response.into_reader(), &mut file)
+            .with_context(|| format!("Failed to download bundle of '{}'", name))?;
+        fs::rename(&partial, dest)?;
+
+        debug!("Downloaded bundle of '{}' to {:?}", name, dest);
+        Ok(())
+    }
+
+    /// Uploads a bundle as actor `name`
+    pub fn publish(&self, name: &str, bundle: &Path, overwrite: bool) -> Result<ActorSummary> {
+        let data = fs::read(bundle).with_context(|| format!("Failed to read {:?}", bundle))?;
+
+        let response = self
+            .agent
+            .put(&self.endpoint(&format!("/{}", encode_name(name))))
+            .query("overwrite", if overwrite { "true" } else { "false" })
+            .set("Content-Type", "application/zstd")
+            .send_bytes(&data)
+            .map_err(|e| self.request_error(e))?;
+
+        response
+            .into_json()
+            .with_context(|| format!("Invalid publish response from remote '{}'", self.name()))
+    }
+}
+
+/// On-disk cache of one remote: its last listing, downloaded bundles and
+/// the actors unpacked from them
+pub struct RemoteCache {
+    root: PathBuf,
+}
+
+impl RemoteCache {
+    pub fn new(registry_path: &Path, remote: &str) -> Self {
+        Self {
+            root: registry_path.join(CACHE_DIR).join(remote),
+        }
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join("index.json")
+    }
+
+    fn actors_dir(&self) -> PathBuf {
+        self.root.join("actors")
+    }
+
+    /// Where a downloaded bundle is kept. The version comes from the remote,
+    /// so it has to be strict semver before it becomes part of a file name.
+    fn bundle_path(&self, name: &str, version: Option<&str>) -> Result<PathBuf> {
+        let version = match version {
+            Some(version) => Version::parse(version)
+                .map_err(|e| {
+                    anyhow!(
+                        "Remote reported an invalid version '{}' for '{}': {}",
+                        version,
+                        name,
+                        e
+                    )
+                })?
+                .to_string(),
+            None => "unversioned".to_string(),
+        };
+
+        Ok(self
+            .root
+            .join("bundles")
+            .join(format!("{}-{}.tar.zst", name, version)))
+    }
+
+    pub fn store_index(&self, actors: &[ActorSummary]) -> Result<()> {
+        fs::create_dir_all(&self.root)?;
+        fs::write(self.index_path(), serde_json::to_string_pretty(actors)?)
+            .with_context(|| format!("Failed to write {:?}", self.index_path()))
+    }
+
+    pub fn load_index(&self) -> Option<Vec<ActorSummary>> {
+        let content = fs::read_to_string(self.index_path()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// The cached copy of an actor, if it was fetched before
+    pub fn cached_actor(&self, name: &str) -> Option<Actor> {
+        let path = self.actors_dir().join(name);
+        if !path.join("manifest.toml").exists() {
+            return None;
+        }
+
+        match Actor::from_path(&path) {
+            Ok(mut actor) => {
+                actor.name = name.to_string();
+                Some(actor)
+            }
+            Err(e) => {
+                warn!("Ignoring broken cached actor at {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// Unpacks a downloaded bundle, replacing any older cached copy
+    fn install(&self, bundle: &Path, name: &str) -> Result<Actor> {
+        let actors = self.actors_dir();
+        fs::create_dir_all(&actors)?;
+
+        let options = ImportOptions {
+            name: Some(name.to_string()),
+            on_collision: Collision::Overwrite,
+            allow_unverified: false,
+        };
+        let info = bundle::import_bundle(bundle, &actors, &options)?;
+        let mut actor = Actor::from_path(info.path)?;
+        actor.name = name.to_string();
+        Ok(actor)
+    }
+}
+
+/// Lists a remote's actors, falling back to the cached listing when the
+/// remote cannot be reached
+pub fn list_remote(client: &RemoteClient, cache: &RemoteCache) -> Result<RemoteListing> {
+    let (actors, from_cache) = match client.list_actors() {
+        Ok(actors) => {
+            cache.store_index(&actors)?;
+            (actors, false)
+        }
+        Err(e) => match cache.load_index() {
+            Some(actors) => {
+                warn!("{}, using cached listing", e);
+                (actors, true)
+            }
+            None => return Err(e),
+        },
+    };
+
+    Ok(RemoteListing {
+        remote: client.name().to_string(),
+        url: client.url().to_string(),
+        actors,
+        from_cache,
+    })
+}
+
+/// Resolves an actor from a remote. The cached copy is reused while it
+/// matches the remote's version, and is used as-is when the remote is
+/// offline.
+pub fn resolve(client: &RemoteClient, cache: &RemoteCache, name: &str) -> Result<Option<Actor>> {
+    let summary = match client.metadata(name) {
+        Ok(Some(summary)) => summary,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            let cached = cache.cached_actor(name);
+            if cached.is_some() {
+                warn!("{}, using cached copy of '{}'", e, name);
+            } else {
+                debug!("{}", e);
+            }
+            return Ok(cached);
+        }
+    };
+
+    if let Some(actor) = cache.cached_actor(name) {
+        let cached_version = actor.manifest.as_ref().map(|m| m.version.clone());
+        if cached_version == summary.version {
+            debug!("Using cached copy of '{}' from '{}'", name, client.name());
+            return Ok(Some(actor));
+        }
+    }
+
+    let bundle = cache.bundle_path(name, summary.version.as_deref())?;
+    client.download_bundle(name, &bundle)?;
+    let actor = cache.install(&bundle, name)?;
+
+    info!(
+        "Fetched actor '{}' {} from remote '{}'",
+        name,
+        summary.version.as_deref().unwrap_or("(unversioned)"),
+        client.name()
+    );
+    Ok(Some(actor))
+}
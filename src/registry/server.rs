@@ -0,0 +1,308 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tiny_http::{Header, Method, Request, Response};
+use tracing::{debug, error, info};
+
+use super::bundle::{Collision, ExportOptions, ImportOptions};
+use super::compat;
+use super::namespace;
+use super::remote::{decode_name, ActorSummary, ErrorBody, API_PREFIX};
+use super::Registry;
+
+/// Largest bundle accepted by a publish request
+const MAX_UPLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+static SCRATCH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+type Reply = Response<Cursor<Vec<u8>>>;
+
+/// Serves a local registry over the HTTP registry protocol:
+///
+/// - `GET  /api/v1/actors` lists actors
+/// - `GET  /api/v1/actors/<name>` returns an actor's metadata
+/// - `GET  /api/v1/actors/<name>/bundle` downloads an actor bundle
+/// - `PUT  /api/v1/actors/<name>[?overwrite=true]` publishes a bundle
+pub struct RegistryServer {
+    server: Arc<tiny_http::Server>,
+    registry: Registry,
+}
+
+/// A server running on a background thread
+pub struct ServerHandle {
+    server: Arc<tiny_http::Server>,
+    addr: SocketAddr,
+    thread: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Stops accepting requests and waits for the server thread to exit
+    pub fn shutdown(self) {
+        self.server.unblock();
+        if self.thread.join().is_err() {
+            error!("Registry server thread panicked");
+        }
+    }
+}
+
+impl RegistryServer {
+    pub fn bind(registry: Registry, addr: &str) -> Result<Self> {
+        let server = tiny_http::Server::http(addr)
+            .map_err(|e| anyhow!("Failed to listen on {}: {}", addr, e))?;
+
+        Ok(Self {
+            server: Arc::new(server),
+            registry,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| anyhow!("Registry server is not listening on an IP address"))
+    }
+
+    /// Handles requests until the server is unblocked
+    pub fn run(&self) -> Result<()> {
+        info!("Serving registry {:?}", self.registry.path());
+
+        for request in self.server.incoming_requests() {
+            handle(&self.registry, request);
+        }
+
+        info!("Registry server stopped");
+        Ok(())
+    }
+
+    /// Runs the server on a background thread
+    pub fn spawn(self) -> Result<ServerHandle> {
+        let addr = self.local_addr()?;
+        let server = self.server.clone();
+        let thread = std::thread::Builder::new()
+            .name("registry-server".to_string())
+            .spawn(move || {
+                if let Err(e) = self.run() {
+                    error!("Registry server failed: {}", e);
+                }
+            })?;
+
+        Ok(ServerHandle {
+            server,
+            addr,
+            thread,
+        })
+    }
+}
+
+fn handle(registry: &Registry, mut request: Request) {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    debug!("{} {}", method, url);
+
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    let route = path
+        .strip_prefix(API_PREFIX)
+        .and_then(|p| p.strip_prefix("/actors"))
+        .map(|p| p.trim_matches('/'));
+
+    // Namespaced names arrive with their separator escaped, see `encode_name`
+    let reply = match (&method, route.map(|r| r.split_once('/').unwrap_or((r, "")))) {
+        (Method::Get, Some(("", ""))) => list(registry),
+        (Method::Get, Some((name, ""))) => metadata(registry, &decode_name(name)),
+        (Method::Get, Some((name, "bundle"))) => download(registry, &decode_name(name)),
+        (Method::Put, Some((name, ""))) if !name.is_empty() => publish(
+            registry,
+            &decode_name(name),
+            query_flag(query, "overwrite"),
+            &mut request,
+        ),
+        (Method::Get, Some(_)) => error_reply(404, format!("No route for {}", path)),
+        (_, Some(_)) => error_reply(405, format!("{} is not supported on {}", method, path)),
+        (_, None) => error_reply(404, format!("No route for {}", path)),
+    };
+
+    let status = reply.status_code().0;
+    if let Err(e) = request.respond(reply) {
+        error!("Failed to respond to {} {}: {}", method, url, e);
+    } else {
+        debug!("{} {} -> {}", method, url, status);
+    }
+}
+
+fn query_flag(query: &str, key: &str) -> bool {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .any(|(k, v)| k == key && (v == "true" || v == "1"))
+}
+
+fn json_reply<T: Serialize>(status: u16, body: &T) -> Reply {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    Response::from_data(body)
+        .with_status_code(status)
+        .with_header(content_type("application/json"))
+}
+
+fn error_reply(status: u16, error: String) -> Reply {
+    json_reply(status, &ErrorBody { error })
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes(&b"Content-Type"[..], value.as_bytes())
+        .expect("static content type header is valid")
+}
+
+/// A unique path for temporary bundles inside the registry
+fn scratch_path(registry: &Registry, kind: &str) -> PathBuf {
+    registry.path().join(".bundles").join(format!(
+        ".{}-{}-{}.tar.zst",
+        kind,
+        std::process::id(),
+        SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Only actor names (and `name@version` for reads) can be addressed, never
+/// arbitrary paths. The version is checked too: `decode_name` turns `%2F`
+/// into `/`, and the version becomes part of the release directory path.
+fn is_valid_name(name: &str) -> bool {
+    match super::release::parse_reference(name) {
+        Ok((name, _)) => namespace::validate_name(name).is_ok(),
+        Err(_) => false,
+    }
+}
+
+fn list(registry: &Registry) -> Reply {
+    match registry.list_actors() {
+        Ok(actors) => {
+            let summaries: Vec<ActorSummary> =
+                actors.iter().map(ActorSummary::from_actor).collect();
+            json_reply(200, &summaries)
+        }
+        Err(e) => {
+            error!("Failed to list actors: {}", e);
+            error_reply(500, format!("Failed to list actors: {}", e))
+        }
+    }
+}
+
+fn metadata(registry: &Registry, name: &str) -> Reply {
+    if !is_valid_name(name) {
+        return error_reply(400, format!("Invalid actor name '{}'", name));
+    }
+
+    match registry.find_actor(name) {
+        Ok(actor) => json_reply(200, &ActorSummary::from_actor(&actor)),
+        Err(e) => error_reply(404, e.to_string()),
+    }
+}
+
+fn download(registry: &Registry, name: &str) -> Reply {
+    if !is_valid_name(name) {
+        return error_reply(400, format!("Invalid actor name '{}'", name));
+    }
+
+    let actor = match registry.find_actor(name) {
+        Ok(actor) => actor,
+        Err(e) => return error_reply(404, e.to_string()),
+    };
+
+    // Ship the built component along when there is one
+    let options = ExportOptions {
+        include_component: compat::component_path(&actor)
+            .map(|p| p.is_file())
+            .unwrap_or(false),
+        checksums: true,
+    };
+
+    let scratch = scratch_path(registry, "download");
+    let result = registry
+        .export_actor(name, Some(&scratch), &options)
+        .and_then(|_| fs::read(&scratch).with_context(|| format!("Failed to read {:?}", scratch)));
+    let _ = fs::remove_file(&scratch);
+
+    match result {
+        Ok(data) => Response::from_data(data)
+            .with_status_code(200)
+            .with_header(content_type("application/zstd")),
+        Err(e) => {
+            error!("Failed to bundle actor '{}': {}", name, e);
+            error_reply(500, format!("Failed to bundle actor '{}': {}", name, e))
+        }
+    }
+}
+
+fn publish(registry: &Registry, name: &str, overwrite: bool, request: &mut Request) -> Reply {
+    if !is_valid_name(name) || name.contains('@') {
+        return error_reply(400, format!("Invalid actor name '{}'", name));
+    }
+
+    if registry.path().join(name).exists() && !overwrite {
+        return error_reply(409, format!("Actor '{}' already exists", name));
+    }
+
+    if request.body_length().unwrap_or(0) as u64 > MAX_UPLOAD_SIZE {
+        return error_reply(
+            413,
+            format!("Bundles are limited to {} bytes", MAX_UPLOAD_SIZE),
+        );
+    }
+
+    let mut data = Vec::new();
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_UPLOAD_SIZE + 1)
+        .read_to_end(&mut data)
+    {
+        return error_reply(400, format!("Failed to read request body: {}", e));
+    }
+    if data.len() as u64 > MAX_UPLOAD_SIZE {
+        return error_reply(
+            413,
+            format!("Bundles are limited to {} bytes", MAX_UPLOAD_SIZE),
+        );
+    }
+
+    let scratch = scratch_path(registry, "upload");
+    let options = ImportOptions {
+        name: Some(name.to_string()),
+        on_collision: if overwrite {
+            Collision::Overwrite
+        } else {
+            Collision::Error
+        },
+        allow_unverified: false,
+    };
+    let result = (|| -> Result<_> {
+        if let Some(parent) = scratch.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&scratch, &data)?;
+        registry
+            .import_actor(&scratch, &options)
+            .map(|(actor, _)| actor)
+    })();
+    let _ = fs::remove_file(&scratch);
+
+    match result {
+        Ok(actor) => {
+            info!("Actor '{}' published to {:?}", name, actor.path);
+            json_reply(201, &ActorSummary::from_actor(&actor))
+        }
+        Err(e) => {
+            error!("Failed to publish actor '{}': {}", name, e);
+            error_reply(400, format!("Failed to publish actor '{}': {:#}", name, e))
+        }
+    }
+}
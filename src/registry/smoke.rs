@@ -0,0 +1,444 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use super::actor::Actor;
+use super::build::{theater_build_command, BuildOptions};
+use super::compat;
+use crate::theater_client::{describe_payload, TheaterClient};
+
+/// Script of a smoke test, relative to the actor
+pub const SMOKE_SCRIPT: &str = "tests/smoke.toml";
+
+/// How long a log assertion waits for the log to show up, since sent
+/// messages are handled asynchronously
+const LOG_TIMEOUT: Duration = Duration::from_secs(2);
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A smoke test script:
+///
+/// ```toml
+/// init_state = { count = 0 }
+///
+/// [[step]]
+/// name = "ping"
+/// request = { type = "ping" }
+/// expect = { type = "pong" }
+///
+/// [[step]]
+/// send = "hello"
+/// expect_log = "hello"
+/// ```
+///
+/// Strings are sent as UTF-8 text, other values as JSON.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmokeScript {
+    pub init_state: Option<Value>,
+    #[serde(default, rename = "step")]
+    pub steps: Vec<SmokeStep>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmokeStep {
+    pub name: Option<String>,
+    /// One-way message to send
+    pub send: Option<Value>,
+    /// Request to send; its response is checked against `expect` and
+    /// `expect_contains`
+    pub request: Option<Value>,
+    /// The whole response: a string matches the response text, anything
+    /// else the parsed JSON
+    pub expect: Option<Value>,
+    /// Text the response must contain
+    pub expect_contains: Option<String>,
+    /// Text a log line must contain once the step ran
+    pub expect_log: Option<String>,
+}
+
+impl SmokeStep {
+    pub fn label(&self, index: usize) -> String {
+        match &self.name {
+            Some(name) => format!("step {} ({})", index + 1, name),
+            None => format!("step {}", index + 1),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.send.is_some() && self.request.is_some() {
+            return Err(anyhow!("has both send and request"));
+        }
+        if self.request.is_none() && (self.expect.is_some() || self.expect_contains.is_some()) {
+            return Err(anyhow!("expects a response but sends no request"));
+        }
+        if self.send.is_none() && self.request.is_none() && self.expect_log.is_none() {
+            return Err(anyhow!("has nothing to send or check"));
+        }
+        Ok(())
+    }
+}
+
+impl SmokeScript {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let script: Self =
+            toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))?;
+
+        for (index, step) in script.steps.iter().enumerate() {
+            step.validate()
+                .with_context(|| format!("In {:?}, {}", path, step.label(index)))?;
+        }
+        Ok(script)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SmokeOptions {
+    /// Build before starting; off to test the component as it is
+    pub build: bool,
+    pub build_options: BuildOptions,
+    /// Overrides the script's `init_state`
+    pub init_state: Option<Value>,
+    /// Script to run instead of the actor's `tests/smoke.toml`
+    pub script: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub name: String,
+    pub outcome: StepOutcome,
+    /// What the step did or received
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SmokeReport {
+    /// What ran, e.g. "Smoke test"
+    pub kind: &'static str,
+    pub actor: String,
+    pub script: PathBuf,
+    pub actor_id: Option<String>,
+    pub steps: Vec<StepResult>,
+}
+
+impl SmokeReport {
+    pub fn passed(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|s| !matches!(s.outcome, StepOutcome::Failed(_)))
+    }
+
+    pub fn to_text(&self) -> String {
+        let failed = self
+            .steps
+            .iter()
+            .filter(|s| matches!(s.outcome, StepOutcome::Failed(_)))
+            .count();
+        let mut text = if failed == 0 {
+            format!("{} of '{}' passed.\n\n", self.kind, self.actor)
+        } else {
+            format!(
+                "{} of '{}' failed: {} of {} steps failed.\n\n",
+                self.kind,
+                self.actor,
+                failed,
+                self.steps.len()
+            )
+        };
+
+        text.push_str(&format!("Script: {}\n", self.script.display()));
+        if let Some(id) = &self.actor_id {
+            text.push_str(&format!("Actor ID: {}\n", id));
+        }
+        text.push('\n');
+
+        for step in &self.steps {
+            let outcome = match &step.outcome {
+                StepOutcome::Passed => "PASS".to_string(),
+                // Traps and build errors span lines; indent them like details
+                StepOutcome::Failed(reason) => {
+                    format!("FAIL: {}", reason.trim_end().replace('\n', "\n    "))
+                }
+                StepOutcome::Skipped(reason) => format!("SKIP: {}", reason),
+            };
+            text.push_str(&format!("- {}: {}\n", step.name, outcome));
+            if let Some(detail) = &step.detail {
+                for line in detail.lines() {
+                    text.push_str(&format!("    {}\n", line));
+                }
+            }
+        }
+        text
+    }
+}
+
+/// Builds `actor`, starts it in the Theater server behind `client`, runs its
+/// smoke script against it and stops it again. Failing steps are reported,
+/// not returned as errors; only an unreadable script is an error.
+pub fn run(actor: &Actor, client: &TheaterClient, options: &SmokeOptions) -> Result<SmokeReport> {
+    let script_path = options
+        .script
+        .clone()
+        .unwrap_or_else(|| actor.path.join(SMOKE_SCRIPT));
+    if !script_path.exists() {
+        return Err(anyhow!(
+            "No smoke test script at {}; add one to describe the steps",
+            script_path.display()
+        ));
+    }
+    let script = SmokeScript::load(&script_path)?;
+
+    let mut report = SmokeReport {
+        kind: "Smoke test",
+        actor: actor.name.clone(),
+        script: script_path,
+        actor_id: None,
+        steps: Vec::new(),
+    };
+    let skip_rest = |report: &mut SmokeReport, reason: &str| {
+        for (index, step) in script.steps.iter().enumerate() {
+            report.steps.push(skipped(step.label(index), reason));
+        }
+        report.steps.push(skipped("stop".to_string(), reason));
+    };
+
+    if options.build {
+        let result = build(actor, &options.build_options);
+        let failed = matches!(result.outcome, StepOutcome::Failed(_));
+        report.steps.push(result);
+        if failed {
+            report
+                .steps
+                .push(skipped("start".to_string(), "build failed"));
+            skip_rest(&mut report, "build failed");
+            return Ok(report);
+        }
+    } else {
+        report
+            .steps
+            .push(skipped("build".to_string(), "build disabled"));
+    }
+
+    let init_state = options.init_state.as_ref().or(script.init_state.as_ref());
+    let started = start(actor, client, init_state);
+    let id = match started {
+        Ok(id) => {
+            report.steps.push(StepResult {
+                name: "start".to_string(),
+                outcome: StepOutcome::Passed,
+                detail: Some(format!("Actor ID: {}", id)),
+            });
+            id
+        }
+        Err(e) => {
+            report.steps.push(step_result("start", Err(e)));
+            skip_rest(&mut report, "actor did not start");
+            return Ok(report);
+        }
+    };
+    report.actor_id = Some(id.clone());
+
+    // The chain holds the logs of earlier steps too; a step only looks at
+    // the ones written after the previous step finished
+    let mut seen_logs = 0;
+    for (index, step) in script.steps.iter().enumerate() {
+        debug!("Smoke test of '{}': {}", actor.name, step.label(index));
+        let result = run_step(client, &id, step, seen_logs);
+        report.steps.push(step_result(&step.label(index), result));
+
+        match log_lines(client, &id) {
+            Ok(logs) => seen_logs = logs.len(),
+            Err(e) => debug!("Failed to read the logs of actor {}: {}", id, e),
+        }
+    }
+
+    let stopped = client.stop_actor(&id).map(|_| None);
+    report.steps.push(step_result("stop", stopped));
+
+    info!(
+        "Smoke test of '{}' {}",
+        actor.name,
+        if report.passed() { "passed" } else { "failed" }
+    );
+    Ok(report)
+}
+
+/// Runs the build as a step, with its output as the detail when it fails
+fn build(actor: &Actor, options: &BuildOptions) -> StepResult {
+    let mut cmd = theater_build_command(&actor.path, options);
+    info!("Executing: {:?}", cmd);
+
+    let (outcome, detail) = match cmd.output() {
+        Ok(output) if output.status.success() => (StepOutcome::Passed, None),
+        Ok(output) => {
+            let log = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let log = log.trim();
+            (
+                StepOutcome::Failed(format!("build failed ({})", output.status)),
+                (!log.is_empty()).then(|| log.to_string()),
+            )
+        }
+        Err(e) => (
+            StepOutcome::Failed(format!("failed to execute theater build command: {}", e)),
+            None,
+        ),
+    };
+
+    StepResult {
+        name: "build".to_string(),
+        outcome,
+        detail,
+    }
+}
+
+fn start(actor: &Actor, client: &TheaterClient, init_state: Option<&Value>) -> Result<String> {
+    // Reload so we pick up the component path a build just wrote
+    let actor = Actor::from_path(&actor.path)?;
+    let component = compat::component_path(&actor)?;
+    if !component.exists() {
+        return Err(anyhow!("Component {} does not exist", component.display()));
+    }
+
+    let manifest = actor.path.join("manifest.toml").canonicalize()?;
+    let initial_state = init_state.map(serde_json::to_vec).transpose()?;
+    client.start_actor(&manifest.to_string_lossy(), initial_state)
+}
+
+fn run_step(
+    client: &TheaterClient,
+    id: &str,
+    step: &SmokeStep,
+    seen_logs: usize,
+) -> Result<Option<String>> {
+    let mut details = Vec::new();
+
+    if let Some(message) = &step.send {
+        client.send_message(id, payload(message)?)?;
+        details.push(format!("Sent {}", message));
+    }
+
+    if let Some(request) = &step.request {
+        let response = client.request(id, payload(request)?)?;
+        details.push(format!("Response: {}", describe_payload(&response)));
+        check_response(step, &response)?;
+    }
+
+    if let Some(needle) = &step.expect_log {
+        let line = wait_for_log(client, id, needle, seen_logs)?;
+        details.push(format!("Log: {}", line));
+    }
+
+    Ok(Some(details.join("\n")))
+}
+
+/// Checks a response against the step's `expect` and `expect_contains`
+pub fn check_response(step: &SmokeStep, response: &[u8]) -> Result<()> {
+    let text = String::from_utf8_lossy(response);
+
+    if let Some(expected) = &step.expect {
+        let matches = match expected {
+            Value::String(expected) => text == expected.as_str(),
+            expected => serde_json::from_slice::<Value>(response)
+                .map(|actual| &actual == expected)
+                .unwrap_or(false),
+        };
+        if !matches {
+            return Err(anyhow!(
+                "expected response {}, got {}",
+                expected,
+                describe_payload(response)
+            ));
+        }
+    }
+
+    if let Some(needle) = &step.expect_contains {
+        if !text.contains(needle.as_str()) {
+            return Err(anyhow!("response does not contain '{}'", needle));
+        }
+    }
+    Ok(())
+}
+
+/// Strings go out as text, everything else as JSON
+pub fn payload(value: &Value) -> Result<Vec<u8>> {
+    match value {
+        Value::String(text) => Ok(text.as_bytes().to_vec()),
+        value => Ok(serde_json::to_vec(value)?),
+    }
+}
+
+/// The log lines in the actor's chain, oldest first
+fn log_lines(client: &TheaterClient, id: &str) -> Result<Vec<String>> {
+    Ok(client
+        .actor_events(id)?
+        .iter()
+        .filter(|e| e.event_type.to_lowercase().contains("log"))
+        .map(|e| {
+            e.description
+                .clone()
+                .unwrap_or_else(|| describe_payload(&e.data))
+        })
+        .collect())
+}
+
+/// Polls the actor's chain until one of the log lines after the first
+/// `seen` contains `needle`, returning that line
+fn wait_for_log(client: &TheaterClient, id: &str, needle: &str, seen: usize) -> Result<String> {
+    let deadline = Instant::now() + LOG_TIMEOUT;
+    loop {
+        let logs = log_lines(client, id)?;
+        if let Some(line) = logs
+            .into_iter()
+            .skip(seen)
+            .find(|line| line.contains(needle))
+        {
+            return Ok(line);
+        }
+
+        if Instant::now() >= deadline {
+            warn!("No log containing '{}' from actor {}", needle, id);
+            return Err(anyhow!("no log line contains '{}'", needle));
+        }
+        std::thread::sleep(LOG_POLL_INTERVAL);
+    }
+}
+
+pub fn step_result(name: &str, result: Result<Option<String>>) -> StepResult {
+    match result {
+        Ok(detail) => StepResult {
+            name: name.to_string(),
+            outcome: StepOutcome::Passed,
+            detail: detail.filter(|d| !d.is_empty()),
+        },
+        Err(e) => StepResult {
+            name: name.to_string(),
+            outcome: StepOutcome::Failed(format!("{:#}", e)),
+            detail: None,
+        },
+    }
+}
+
+pub fn skipped(name: String, reason: &str) -> StepResult {
+    StepResult {
+        name,
+        outcome: StepOutcome::Skipped(reason.to_string()),
+        detail: None,
+    }
+}
@@ -0,0 +1,584 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use git2::build::RepoBuilder;
+use git2::{
+    AutotagOption, Commit, Cred, CredentialType, FetchOptions, ObjectType, Oid, RemoteCallbacks,
+    Repository, Tree, TreeWalkMode, TreeWalkResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+use tracing::{debug, info, warn};
+
+use super::actor::{Actor, ActorCargoConfig};
+use super::document::TomlDocument;
+use super::history::short_id;
+use super::manifest::ManifestDocument;
+use super::namespace;
+
+/// Where an actor cloned from git came from, kept in the actor directory
+pub const SOURCE_FILE: &str = ".actor-source.toml";
+
+/// Manifest fields the registry rewrites locally, ignored when deciding
+/// whether manifest.toml has local changes
+const LOCAL_MANIFEST_FIELDS: &[&str] = &["name", "component"];
+
+/// Credential attempts before giving up on an authenticated source
+const MAX_CREDENTIAL_ATTEMPTS: usize = 3;
+
+/// The origin of a cloned actor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRecord {
+    /// Repository URL, or absolute path of a local repository
+    pub url: String,
+    /// Branch, tag or commit the actor follows; the repository's default
+    /// branch if unset
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Directory of the actor inside the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdirectory: Option<String>,
+    /// Commit the actor's files were last taken from
+    pub commit: String,
+    /// When the files were last taken from the source, RFC 3339
+    pub updated: String,
+}
+
+impl SourceRecord {
+    pub fn load(actor: &Actor) -> Result<Self> {
+        let path = actor.path.join(SOURCE_FILE);
+        if !path.is_file() {
+            return Err(anyhow!(
+                "Actor '{}' has no {}; it was not cloned from a git source",
+                actor.name,
+                SOURCE_FILE
+            ));
+        }
+        TomlDocument::load(&path)?.deserialize()
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        let mut doc = TomlDocument::load_or_default(dir.join(SOURCE_FILE))?;
+        doc.merge_from(self)?;
+        doc.save()
+    }
+
+    /// The source as shown to users, e.g. `https://host/repo.git (main) actors/echo`
+    pub fn describe(&self) -> String {
+        let mut text = self.url.clone();
+        if let Some(reference) = &self.reference {
+            text.push_str(&format!(" ({})", reference));
+        }
+        if let Some(subdirectory) = &self.subdirectory {
+            text.push_str(&format!(" {}", subdirectory));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    /// Git URL or path of a local repository
+    pub source: String,
+    pub reference: Option<String>,
+    pub subdirectory: Option<String>,
+    /// Name in the registry; defaults to the name in the actor's manifest
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CloneInfo {
+    pub path: PathBuf,
+    pub source: SourceRecord,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOptions {
+    /// Follow a different branch, tag or commit from now on
+    pub reference: Option<String>,
+    /// Overwrite local changes to files that changed upstream
+    pub force: bool,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateInfo {
+    pub source: SourceRecord,
+    pub previous_commit: String,
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+    /// Local changes that were replaced by upstream's version
+    pub overwritten: Vec<String>,
+}
+
+impl UpdateInfo {
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A scratch directory next to the registry's actors, removed when dropped.
+/// Each call gets its own, so concurrent clones and updates never share one.
+fn scratch_dir(root: &Path, prefix: &str) -> Result<TempDir> {
+    tempfile::Builder::new()
+        .prefix(prefix)
+        .tempdir_in(root)
+        .with_context(|| format!("Failed to create a scratch directory in {:?}", root))
+}
+
+/// Local paths are recorded absolute so updates work from any directory
+fn source_url(source: &str) -> Result<String> {
+    let path = Path::new(source);
+    if path.exists() {
+        Ok(fs::canonicalize(path)?.to_string_lossy().into_owned())
+    } else {
+        Ok(source.to_string())
+    }
+}
+
+fn normalize_subdirectory(subdirectory: Option<&str>) -> Result<Option<String>> {
+    let Some(subdirectory) = subdirectory.map(|s| s.trim_matches('/')) else {
+        return Ok(None);
+    };
+    if subdirectory.is_empty() {
+        return Ok(None);
+    }
+    if !Path::new(subdirectory)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(anyhow!(
+            "Invalid subdirectory '{}': it must be a relative path inside the repository",
+            subdirectory
+        ));
+    }
+    Ok(Some(subdirectory.to_string()))
+}
+
+/// Answers credential requests from the SSH agent and git's credential
+/// helpers
+fn remote_callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if attempts > MAX_CREDENTIAL_ATTEMPTS {
+            return Err(git2::Error::from_str(&format!(
+                "Authentication to {} failed",
+                url
+            )));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            let config = git2::Config::open_default()?;
+            return Cred::credential_helper(&config, url, username);
+        }
+        Cred::default()
+    });
+    callbacks
+}
+
+/// Clones `url` bare into `dir`, with all its branches and tags
+fn fetch(url: &str, dir: &Path) -> Result<Repository> {
+    debug!("Fetching {} into {:?}", url, dir);
+    let mut options = FetchOptions::new();
+    options
+        .remote_callbacks(remote_callbacks())
+        .download_tags(AutotagOption::All);
+
+    RepoBuilder::new()
+        .bare(true)
+        .fetch_options(options)
+        .clone(url, dir)
+        .with_context(|| format!("Failed to fetch {}", url))
+}
+
+/// Resolves a branch, tag or commit, or the default branch if `reference`
+/// is unset
+fn resolve<'r>(repo: &'r Repository, reference: Option<&str>, url: &str) -> Result<Commit<'r>> {
+    let Some(reference) = reference else {
+        return repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|_| anyhow!("{} has no default branch; pass a ref", url));
+    };
+
+    for candidate in [
+        format!("refs/remotes/origin/{}", reference),
+        format!("refs/tags/{}", reference),
+        format!("refs/heads/{}", reference),
+    ] {
+        if let Ok(found) = repo.find_reference(&candidate) {
+            return Ok(found.peel_to_commit()?);
+        }
+    }
+
+    repo.revparse_single(reference)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| anyhow!("'{}' is not a branch, tag or commit of {}", reference, url))
+}
+
+/// The tree of the actor's directory at `commit`
+fn actor_tree<'r>(
+    repo: &'r Repository,
+    commit: &Commit<'r>,
+    subdirectory: Option<&str>,
+) -> Result<Tree<'r>> {
+    let tree = commit.tree()?;
+    let Some(subdirectory) = subdirectory else {
+        return Ok(tree);
+    };
+    tree.get_path(Path::new(subdirectory))
+        .and_then(|entry| entry.to_object(repo))
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|_| {
+            anyhow!(
+                "There is no directory '{}' at commit {}",
+                subdirectory,
+                short_id(commit.id())
+            )
+        })
+}
+
+/// Regular files of `tree` by `/`-separated path, with their blob ids.
+/// Symlinks and submodules are skipped, as is a source record committed
+/// upstream.
+fn tree_files(tree: &Tree) -> Result<BTreeMap<String, Oid>> {
+    let mut files = BTreeMap::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        let Some(name) = entry.name() else {
+            warn!("Skipping {}{} with a non UTF-8 name", root, entry.id());
+            return TreeWalkResult::Skip;
+        };
+        if matches!(name, "." | ".." | ".git") || name.contains('\\') {
+            warn!("Skipping unsafe path {}{}", root, name);
+            return TreeWalkResult::Skip;
+        }
+
+        let path = format!("{}{}", root, name);
+        match entry.kind() {
+            Some(ObjectType::Blob) if entry.filemode() == 0o120000 => {
+                warn!("Skipping symlink {}", path);
+            }
+            Some(ObjectType::Blob) if path != SOURCE_FILE => {
+                files.insert(path, entry.id());
+            }
+            Some(ObjectType::Commit) => warn!("Skipping submodule {}", path),
+            _ => {}
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok(files)
+}
+
+/// Writes the files of `tree` under `dir`
+fn checkout(repo: &Repository, tree: &Tree, dir: &Path) -> Result<BTreeMap<String, Oid>> {
+    let files = tree_files(tree)?;
+    for (path, id) in &files {
+        let dest = dir.join(path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, repo.find_blob(*id)?.content())?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = match tree.get_path(Path::new(path))?.filemode() {
+                0o100755 => 0o755,
+                _ => 0o644,
+            };
+            fs::set_permissions(&dest, fs::Permissions::from_mode(mode))?;
+        }
+    }
+    Ok(files)
+}
+
+/// Checks that `dir` holds a Theater actor: a valid manifest.toml and a
+/// crate that builds a cdylib
+fn validate_actor(dir: &Path) -> Result<String> {
+    let manifest_path = dir.join("manifest.toml");
+    if !manifest_path.is_file() {
+        return Err(anyhow!("there is no manifest.toml"));
+    }
+    let manifest = ManifestDocument::load(&manifest_path)?.manifest()?;
+
+    let cargo_path = dir.join("Cargo.toml");
+    if !cargo_path.is_file() {
+        return Err(anyhow!("there is no Cargo.toml"));
+    }
+    let cargo: ActorCargoConfig = toml::from_str(&fs::read_to_string(&cargo_path)?)
+        .with_context(|| format!("Failed to parse {:?}", cargo_path))?;
+    let is_cdylib = cargo
+        .lib
+        .as_ref()
+        .is_some_and(|lib| lib.crate_type.iter().any(|t| t == "cdylib"));
+    if !is_cdylib {
+        return Err(anyhow!(
+            "Cargo.toml does not build a cdylib (crate-type = [\"cdylib\"] under [lib])"
+        ));
+    }
+
+    Ok(manifest.name)
+}
+
+/// Clones an actor from a git repository into `root`. Only the actor's files
+/// are kept, not the repository; where they came from is recorded in
+/// `.actor-source.toml` for `update_actor`.
+pub fn clone_actor(root: &Path, options: &CloneOptions) -> Result<CloneInfo> {
+    if let Some(name) = &options.name {
+        namespace::validate_name(name)?;
+        if root.join(name).exists() {
+            return Err(anyhow!(
+                "Actor '{}' already exists at {:?}",
+                name,
+                root.join(name)
+            ));
+        }
+    }
+
+    let url = source_url(&options.source)?;
+    let subdirectory = normalize_subdirectory(options.subdirectory.as_deref())?;
+
+    // Stage next to the target so the final move is a rename on one filesystem
+    let scratch = scratch_dir(root, ".clone-")?;
+    let repo = fetch(&url, &scratch.path().join("repo"))?;
+    let commit = resolve(&repo, options.reference.as_deref(), &url)?;
+    let tree = actor_tree(&repo, &commit, subdirectory.as_deref())?;
+
+    let staged = scratch.path().join("actor");
+    fs::create_dir_all(&staged)?;
+    checkout(&repo, &tree, &staged)?;
+    let manifest_name =
+        validate_actor(&staged).map_err(|e| anyhow!("{} is not a Theater actor: {}", url, e))?;
+
+    let name = options.name.clone().unwrap_or(manifest_name.clone());
+    namespace::validate_name(&name)?;
+    let target = root.join(&name);
+    if target.exists() {
+        return Err(anyhow!(
+            "Actor '{}' already exists at {:?}; pass a different name",
+            name,
+            target
+        ));
+    }
+
+    if namespace::base_name(&name) != manifest_name {
+        let mut manifest = ManifestDocument::load(staged.join("manifest.toml"))?;
+        manifest.set_name(namespace::base_name(&name));
+        manifest.save()?;
+    }
+
+    let source = SourceRecord {
+        url,
+        reference: options.reference.clone(),
+        subdirectory,
+        commit: commit.id().to_string(),
+        updated: Utc::now().to_rfc3339(),
+    };
+    source.save(&staged)?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&staged, &target)
+        .with_context(|| format!("Failed to move cloned actor to {:?}", target))?;
+
+    info!(
+        "Cloned actor '{}' from {} at {}",
+        name,
+        source.describe(),
+        short_id(commit.id())
+    );
+    Ok(CloneInfo {
+        path: target,
+        source,
+    })
+}
+
+/// A manifest without the fields the registry rewrites locally
+fn comparable_manifest(content: &[u8]) -> Option<toml::Table> {
+    let mut manifest: toml::Table = toml::from_str(std::str::from_utf8(content).ok()?).ok()?;
+    for field in LOCAL_MANIFEST_FIELDS {
+        manifest.remove(*field);
+    }
+    Some(manifest)
+}
+
+/// Whether the file at `path` has the content of blob `id`. For
+/// manifest.toml the locally rewritten fields don't count.
+fn matches_blob(repo: &Repository, path: &Path, relative: &str, id: Oid) -> Result<bool> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+    if Oid::hash_file(ObjectType::Blob, path)? == id {
+        return Ok(true);
+    }
+    if relative == "manifest.toml" {
+        let upstream = comparable_manifest(repo.find_blob(id)?.content());
+        let local = comparable_manifest(&fs::read(path)?);
+        return Ok(upstream.is_some() && upstream == local);
+    }
+    Ok(false)
+}
+
+/// Brings a cloned actor up to date with its source. Files that changed
+/// upstream are replaced and files removed upstream are deleted; local
+/// changes to other files are kept. A file changed both locally and
+/// upstream is an error unless `force` is set.
+pub fn update_actor(root: &Path, actor: &Actor, options: &UpdateOptions) -> Result<UpdateInfo> {
+    let mut source = SourceRecord::load(actor)?;
+    let previous_commit = source.commit.clone();
+    let previous_reference = source.reference.clone();
+    if options.reference.is_some() {
+        source.reference = options.reference.clone();
+    }
+
+    let scratch = scratch_dir(root, ".source-")?;
+    let repo = fetch(&source.url, &scratch.path().join("repo"))?;
+    let commit = resolve(&repo, source.reference.as_deref(), &source.url)?;
+    let tree = actor_tree(&repo, &commit, source.subdirectory.as_deref())?;
+
+    let staged = scratch.path().join("actor");
+    fs::create_dir_all(&staged)?;
+    let new_files = checkout(&repo, &tree, &staged)?;
+    validate_actor(&staged).map_err(|e| {
+        anyhow!(
+            "{} at {} is not a Theater actor: {}",
+            source.url,
+            short_id(commit.id()),
+            e
+        )
+    })?;
+
+    // Without the previous commit there is no telling upstream changes from
+    // local ones, so every file that differs counts as both
+    let old_files = match Oid::from_str(&previous_commit)
+        .ok()
+        .and_then(|id| repo.find_commit(id).ok())
+    {
+        Some(old) => Some(tree_files(&actor_tree(
+            &repo,
+            &old,
+            source.subdirectory.as_deref(),
+        )?)?),
+        None => {
+            warn!(
+                "Commit {} is no longer in {}; treating every differing file as a local change",
+                previous_commit, source.url
+            );
+            None
+        }
+    };
+
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    let mut removed = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for (path, id) in &new_files {
+        let local = actor.path.join(path);
+        match old_files.as_ref().and_then(|old| old.get(path)) {
+            Some(old_id) if old_id == id => continue,
+            Some(old_id) => {
+                if !matches_blob(&repo, &local, path, *old_id)? {
+                    conflicts.push(path.clone());
+                }
+                modified.push(path.clone());
+            }
+            None if local.exists() => {
+                if matches_blob(&repo, &local, path, *id)? {
+                    continue;
+                }
+                conflicts.push(path.clone());
+                modified.push(path.clone());
+            }
+            None => added.push(path.clone()),
+        }
+    }
+    if let Some(old_files) = &old_files {
+        for (path, old_id) in old_files {
+            if new_files.contains_key(path) {
+                continue;
+            }
+            let local = actor.path.join(path);
+            if !local.exists() {
+                continue;
+            }
+            if !matches_blob(&repo, &local, path, *old_id)? {
+                conflicts.push(path.clone());
+            }
+            removed.push(path.clone());
+        }
+    }
+
+    if !conflicts.is_empty() && !options.force {
+        return Err(anyhow!(
+            "Local changes to {} in actor '{}' would be overwritten by {} at {}; commit or move them, or pass force",
+            conflicts.join(", "),
+            actor.name,
+            source.url,
+            short_id(commit.id())
+        ));
+    }
+
+    let reference_changed = source.reference != previous_reference;
+    source.commit = commit.id().to_string();
+    let info = UpdateInfo {
+        source: source.clone(),
+        previous_commit,
+        added,
+        modified,
+        removed,
+        overwritten: conflicts,
+    };
+    if options.dry_run {
+        return Ok(info);
+    }
+    if info.is_unchanged() && info.source.commit == info.previous_commit && !reference_changed {
+        return Ok(info);
+    }
+
+    // The registry's name and build output stay as they are locally
+    let local_manifest = ManifestDocument::load(actor.path.join("manifest.toml"))
+        .and_then(|doc| doc.manifest())
+        .ok();
+
+    for path in info.added.iter().chain(&info.modified) {
+        let dest = actor.path.join(path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(staged.join(path), &dest)?;
+    }
+    for path in &info.removed {
+        fs::remove_file(actor.path.join(path))?;
+        let mut dir = actor.path.join(path);
+        while dir.pop() && dir != actor.path && fs::remove_dir(&dir).is_ok() {}
+    }
+
+    if let Some(local) = local_manifest {
+        if info.modified.iter().any(|p| p == "manifest.toml") {
+            let mut manifest = ManifestDocument::load(actor.path.join("manifest.toml"))?;
+            manifest.set_name(&local.name);
+            manifest.set_component(&local.component);
+            manifest.save()?;
+        }
+    }
+
+    source.updated = Utc::now().to_rfc3339();
+    source.save(&actor.path)?;
+
+    info!(
+        "Updated actor '{}' from {} to {}",
+        actor.name,
+        source.describe(),
+        short_id(commit.id())
+    );
+    Ok(UpdateInfo { source, ..info })
+}
@@ -1,4 +1,5 @@
 // In a real implementation, these templates would be in separate files
+#[allow(clippy::module_inception)]
 pub(crate) mod templates {
     pub(crate) const BASIC_LIB_RS: &str = r#"mod bindings;
 
@@ -729,11 +730,154 @@ bindings::export!(Component with_types_in bindings);
 "#;
     pub(crate) const BASIC_WIT: &str = r#"package ntwk:theater;
 
-world {{actor_name}} {
+world {{world_name}} {
     import runtime;
 
     export message-server-client;
     export actor;
 }
 "#;
+    pub(crate) const HTTP_WIT: &str = r#"package ntwk:theater;
+
+world {{world_name}} {
+    import runtime;
+    import http-client;
+    import http-framework;
+
+    export message-server-client;
+    export actor;
+    export http-handlers;
+}
+"#;
+    pub(crate) const SUPERVISOR_WIT: &str = r#"package ntwk:theater;
+
+world {{world_name}} {
+    import runtime;
+    import supervisor;
+
+    export message-server-client;
+    export actor;
+    export supervisor;
+}
+"#;
+}
+
+/// WIT keywords that can't name a world without a `%` escape
+const WIT_KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "bool",
+    "borrow",
+    "char",
+    "constructor",
+    "enum",
+    "export",
+    "f32",
+    "f64",
+    "flags",
+    "float32",
+    "float64",
+    "from",
+    "func",
+    "future",
+    "import",
+    "include",
+    "interface",
+    "list",
+    "option",
+    "own",
+    "package",
+    "record",
+    "resource",
+    "result",
+    "s16",
+    "s32",
+    "s64",
+    "s8",
+    "static",
+    "stream",
+    "string",
+    "tuple",
+    "type",
+    "u16",
+    "u32",
+    "u64",
+    "u8",
+    "use",
+    "variant",
+    "with",
+    "world",
+];
+
+/// ASCII spelling of an accented Latin letter, e.g. `é` as `e`; `c` must
+/// already be lowercase
+fn fold_accent(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// The actor name as a WIT identifier for its world: lowercase ASCII words
+/// joined by `-`, so `chat_room` and `Chat.Room` both become `chat-room`.
+/// Accented Latin letters lose their accents (`café` becomes `cafe`), other
+/// characters are dropped and a digit can't start the name.
+pub fn wit_world_name(name: &str) -> String {
+    let mut folded = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        match fold_accent(c) {
+            Some(ascii) => folded.push_str(ascii),
+            // Combining accents, as in a decomposed `é`
+            None if ('\u{300}'..='\u{36f}').contains(&c) => {}
+            None => folded.push(c),
+        }
+    }
+
+    let mut ident = String::new();
+    for word in folded
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let word = word.to_ascii_lowercase();
+        // Words must start with a letter, so digits join the previous word
+        if word.starts_with(|c: char| c.is_ascii_digit()) {
+            if ident.is_empty() {
+                ident.push_str("actor");
+            }
+        } else if !ident.is_empty() {
+            ident.push('-');
+        }
+        ident.push_str(&word);
+    }
+
+    if ident.is_empty() {
+        "actor".to_string()
+    } else if WIT_KEYWORDS.contains(&ident.as_str()) {
+        format!("%{}", ident)
+    } else {
+        ident
+    }
 }
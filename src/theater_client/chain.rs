@@ -0,0 +1,244 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::describe_payload;
+
+/// One entry in an actor's event chain, as Theater records it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainEvent {
+    #[serde(default)]
+    pub hash: Vec<u8>,
+    #[serde(default)]
+    pub parent_hash: Option<Vec<u8>>,
+    pub event_type: String,
+    #[serde(default)]
+    pub data: Vec<u8>,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ChainEvent {
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        Utc.timestamp_millis_opt(self.timestamp as i64).single()
+    }
+
+    /// Whether the event records a failure, judged by its type and
+    /// description
+    pub fn is_error(&self) -> bool {
+        let mentions_error = |s: &str| {
+            let s = s.to_lowercase();
+            s.contains("error") || s.contains("fail") || s.contains("panic")
+        };
+        mentions_error(&self.event_type) || self.description.as_deref().is_some_and(mentions_error)
+    }
+
+    /// Whether the event records a change of the actor's state
+    pub fn is_state_change(&self) -> bool {
+        self.event_type.to_lowercase().contains("state")
+    }
+}
+
+/// Which events `get-actor-events` shows
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Substrings of the event type, any of which selects an event
+    pub event_types: Vec<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Keep only the last `limit` matching events
+    pub limit: Option<usize>,
+}
+
+impl EventFilter {
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        let event_types = match args.get("event_type") {
+            None | Some(serde_json::Value::Null) => Vec::new(),
+            Some(serde_json::Value::String(s)) => vec![s.clone()],
+            Some(serde_json::Value::Array(types)) => types
+                .iter()
+                .map(|t| {
+                    t.as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| anyhow!("event_type must be a string or list of strings"))
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => return Err(anyhow!("event_type must be a string or list of strings")),
+        };
+
+        let time = |key: &str| -> Result<Option<DateTime<Utc>>> {
+            match args.get(key).and_then(|v| v.as_str()) {
+                Some(s) => parse_time(s)
+                    .map(Some)
+                    .with_context(|| format!("Invalid {}", key)),
+                None => Ok(None),
+            }
+        };
+
+        Ok(Self {
+            event_types,
+            since: time("since")?,
+            until: time("until")?,
+            limit: args
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map(|n| n as usize),
+        })
+    }
+
+    pub fn matches(&self, event: &ChainEvent) -> bool {
+        let type_matches = self.event_types.is_empty()
+            || self
+                .event_types
+                .iter()
+                .any(|t| event.event_type.contains(t.as_str()));
+        let time = event.time();
+        let after = match (self.since, time) {
+            (Some(since), Some(time)) => time >= since,
+            _ => true,
+        };
+        let before = match (self.until, time) {
+            (Some(until), Some(time)) => time <= until,
+            _ => true,
+        };
+
+        type_matches && after && before
+    }
+
+    pub fn apply<'a>(&self, events: &'a [ChainEvent]) -> Vec<&'a ChainEvent> {
+        let matching: Vec<&ChainEvent> = events.iter().filter(|e| self.matches(e)).collect();
+        match self.limit {
+            Some(limit) if matching.len() > limit => matching[matching.len() - limit..].to_vec(),
+            _ => matching,
+        }
+    }
+}
+
+/// Accepts RFC 3339 times, e.g. `2024-05-01T12:00:00Z`, or milliseconds
+/// since the Unix epoch
+pub fn parse_time(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(millis) = s.parse::<i64>() {
+        return Utc
+            .timestamp_millis_opt(millis)
+            .single()
+            .ok_or_else(|| anyhow!("'{}' is out of range", s));
+    }
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| anyhow!("'{}' is neither RFC 3339 nor milliseconds: {}", s, e))
+}
+
+/// Counts and notable events of a chain, for a quick look at what happened
+#[derive(Debug, Clone, Default)]
+pub struct EventSummary {
+    pub total: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub first: Option<DateTime<Utc>>,
+    pub last: Option<DateTime<Utc>>,
+    pub state_changes: Vec<ChainEvent>,
+    pub errors: Vec<ChainEvent>,
+}
+
+impl EventSummary {
+    pub fn of<'a>(events: impl IntoIterator<Item = &'a ChainEvent>) -> Self {
+        let mut summary = Self::default();
+        for event in events {
+            summary.total += 1;
+            *summary.by_type.entry(event.event_type.clone()).or_default() += 1;
+
+            let time = event.time();
+            if summary.first.is_none() {
+                summary.first = time;
+            }
+            if time.is_some() {
+                summary.last = time;
+            }
+
+            if event.is_state_change() {
+                summary.state_changes.push(event.clone());
+            }
+            if event.is_error() {
+                summary.errors.push(event.clone());
+            }
+        }
+        summary
+    }
+}
+
+/// One line describing an event, with its data shortened
+pub fn describe_event(event: &ChainEvent) -> String {
+    const MAX_DATA: usize = 200;
+
+    let time = event
+        .time()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+        .unwrap_or_else(|| event.timestamp.to_string());
+    let mut line = format!("[{}] {}", time, event.event_type);
+
+    if let Some(description) = &event.description {
+        line.push_str(&format!(": {}", description));
+    }
+    if !event.data.is_empty() {
+        let data = match serde_json::from_slice::<serde_json::Value>(&event.data) {
+            Ok(value) => value.to_string(),
+            Err(_) => describe_payload(&event.data).replace('\n', " "),
+        };
+        let data = if data.chars().count() > MAX_DATA {
+            format!("{}...", data.chars().take(MAX_DATA).collect::<String>())
+        } else {
+            data
+        };
+        line.push_str(&format!(" ({})", data));
+    }
+    line
+}
+
+/// Where Theater saves chains: `$THEATER_HOME/chains`, else
+/// `~/.theater/chains`
+pub fn default_chain_store() -> Option<PathBuf> {
+    let non_empty = |var: &str| {
+        env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+
+    non_empty("THEATER_HOME")
+        .or_else(|| non_empty("HOME").map(|home| home.join(".theater")))
+        .map(|dir| dir.join("chains"))
+}
+
+/// Reads the chain Theater saved for actor `id`, a JSON list of events or
+/// an object with an `events` list
+pub fn load_chain(store: &Path, id: &str) -> Result<Vec<ChainEvent>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SavedChain {
+        Events(Vec<ChainEvent>),
+        Chain { events: Vec<ChainEvent> },
+    }
+
+    if id.contains(['/', '\\']) || id.starts_with('.') {
+        return Err(anyhow!("Invalid actor id '{}'", id));
+    }
+
+    let path = [store.join(format!("{}.json", id)), store.join(id)]
+        .into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| anyhow!("No saved chain for actor {} in {}", id, store.display()))?;
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read chain {:?}", path))?;
+    let chain: SavedChain = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse chain {:?}", path))?;
+
+    Ok(match chain {
+        SavedChain::Events(events) => events,
+        SavedChain::Chain { events } => events,
+    })
+}
@@ -0,0 +1,294 @@
+pub mod chain;
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use tracing::debug;
+
+pub use self::chain::ChainEvent;
+
+/// Address of the Theater management socket when none is configured
+pub const DEFAULT_THEATER_ADDR: &str = "127.0.0.1:9000";
+
+/// Frames larger than this are rejected rather than allocated
+pub const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The part of Theater's management commands the registry uses, in the
+/// same JSON shape. `tests/theater_protocol.rs` round-trips these through
+/// Theater's own types so the two can't drift apart unnoticed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ManagementCommand {
+    StartActor {
+        /// Path of the actor's manifest.toml
+        manifest: String,
+        /// Overrides the manifest's init state
+        initial_state: Option<Vec<u8>>,
+        parent: bool,
+        subscribe: bool,
+    },
+    StopActor {
+        id: String,
+    },
+    ListActors,
+    /// One-way message to an actor's `handle-send`
+    SendActorMessage {
+        id: String,
+        data: Vec<u8>,
+    },
+    /// Request to an actor's `handle-request`, answered with its response
+    RequestActorMessage {
+        id: String,
+        data: Vec<u8>,
+    },
+    GetActorEvents {
+        id: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ManagementResponse {
+    ActorStarted { id: String },
+    ActorStopped { id: String },
+    ActorList { actors: Vec<ActorListEntry> },
+    SentMessage { id: String },
+    RequestedMessage { id: String, message: Vec<u8> },
+    ActorEvents { id: String, events: Vec<ChainEvent> },
+    Error { error: serde_json::Value },
+}
+
+/// Theater lists actors by id, or by id and name depending on its version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ActorListEntry {
+    Named(String, String),
+    Id(String),
+}
+
+/// An actor running in the Theater server
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunningActor {
+    pub id: String,
+    pub name: Option<String>,
+}
+
+impl From<ActorListEntry> for RunningActor {
+    fn from(entry: ActorListEntry) -> Self {
+        match entry {
+            ActorListEntry::Named(id, name) => Self {
+                id,
+                name: Some(name),
+            },
+            ActorListEntry::Id(id) => Self { id, name: None },
+        }
+    }
+}
+
+/// Writes one length-prefixed JSON frame
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, value: &T) -> Result<()> {
+    let body = serde_json::to_vec(value)?;
+    let len = u32::try_from(body.len()).map_err(|_| anyhow!("Frame too large"))?;
+
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads one length-prefixed frame, `None` if the peer closed the
+/// connection between frames
+pub fn read_frame<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(anyhow!("Frame of {} bytes exceeds the limit", len));
+    }
+
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// Blocking client for a Theater server's management socket. Theater takes
+/// JSON commands over TCP, each framed by its length as a 4-byte big-endian
+/// integer, and answers each with one response frame.
+#[derive(Debug, Clone)]
+pub struct TheaterClient {
+    addr: String,
+}
+
+impl TheaterClient {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self { addr: addr.into() }
+    }
+
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        let addrs = self
+            .addr
+            .to_socket_addrs()
+            .with_context(|| format!("Invalid Theater address '{}'", self.addr))?;
+
+        let mut last_error = None;
+        for addr in addrs {
+            match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+                    stream.set_write_timeout(Some(RESPONSE_TIMEOUT))?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(anyhow!(
+            "Theater server at {} is unreachable{}; is `theater server` running?",
+            self.addr,
+            last_error.map(|e| format!(" ({})", e)).unwrap_or_default()
+        ))
+    }
+
+    /// Sends a command and waits for its response
+    pub fn call(&self, command: &ManagementCommand) -> Result<ManagementResponse> {
+        debug!("Theater {} <- {:?}", self.addr, command);
+
+        let mut stream = self.connect()?;
+        write_frame(&mut stream, command)?;
+
+        let frame = read_frame(&mut stream)?
+            .ok_or_else(|| anyhow!("Theater server at {} closed the connection", self.addr))?;
+        let response: ManagementResponse = serde_json::from_slice(&frame)
+            .with_context(|| format!("Invalid response from Theater server at {}", self.addr))?;
+
+        debug!("Theater {} -> {:?}", self.addr, response);
+        match response {
+            ManagementResponse::Error { error } => Err(anyhow!("Theater error: {}", error)),
+            response => Ok(response),
+        }
+    }
+
+    /// Starts the actor described by `manifest`, returning its runtime id
+    pub fn start_actor(&self, manifest: &str, initial_state: Option<Vec<u8>>) -> Result<String> {
+        let command = ManagementCommand::StartActor {
+            manifest: manifest.to_string(),
+            initial_state,
+            parent: false,
+            subscribe: false,
+        };
+
+        match self.call(&command)? {
+            ManagementResponse::ActorStarted { id } => Ok(id),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn stop_actor(&self, id: &str) -> Result<()> {
+        let command = ManagementCommand::StopActor { id: id.to_string() };
+
+        match self.call(&command)? {
+            ManagementResponse::ActorStopped { .. } => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Delivers `data` to the actor without waiting for it to be handled
+    pub fn send_message(&self, id: &str, data: Vec<u8>) -> Result<()> {
+        let command = ManagementCommand::SendActorMessage {
+            id: id.to_string(),
+            data,
+        };
+
+        match self.call(&command)? {
+            ManagementResponse::SentMessage { .. } => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Sends `data` as a request and returns the actor's response
+    pub fn request(&self, id: &str, data: Vec<u8>) -> Result<Vec<u8>> {
+        let command = ManagementCommand::RequestActorMessage {
+            id: id.to_string(),
+            data,
+        };
+
+        match self.call(&command)? {
+            ManagementResponse::RequestedMessage { message, .. } => Ok(message),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// The event chain of a running actor
+    pub fn actor_events(&self, id: &str) -> Result<Vec<ChainEvent>> {
+        let command = ManagementCommand::GetActorEvents { id: id.to_string() };
+
+        match self.call(&command)? {
+            ManagementResponse::ActorEvents { events, .. } => Ok(events),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn list_actors(&self) -> Result<Vec<RunningActor>> {
+        match self.call(&ManagementCommand::ListActors)? {
+            ManagementResponse::ActorList { actors } => {
+                Ok(actors.into_iter().map(RunningActor::from).collect())
+            }
+            other => Err(unexpected(other)),
+        }
+    }
+}
+
+/// Reads a message payload from tool arguments: `payload` is sent as JSON,
+/// `text` as UTF-8 and `base64` as the bytes it decodes to
+pub fn payload_from_args(args: &serde_json::Value) -> Result<Vec<u8>> {
+    let payload = args.get("payload").filter(|v| !v.is_null());
+    let text = args.get("text").and_then(|v| v.as_str());
+    let base64 = args.get("base64").and_then(|v| v.as_str());
+
+    match (payload, text, base64) {
+        (Some(payload), None, None) => Ok(serde_json::to_vec(payload)?),
+        (None, Some(text), None) => Ok(text.as_bytes().to_vec()),
+        (None, None, Some(encoded)) => BASE64
+            .decode(encoded.trim())
+            .map_err(|e| anyhow!("Invalid base64 payload: {}", e)),
+        (None, None, None) => Err(anyhow!(
+            "Missing payload: give one of payload, text or base64"
+        )),
+        _ => Err(anyhow!("Give only one of payload, text or base64")),
+    }
+}
+
+/// Renders bytes from an actor readably: pretty JSON if they parse as JSON,
+/// else text if they are UTF-8, else base64
+pub fn describe_payload(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "(empty)".to_string();
+    }
+    if let Ok(value) = serde_json::from_slice::<serde_json::Value>(bytes) {
+        if let Ok(pretty) = serde_json::to_string_pretty(&value) {
+            return pretty;
+        }
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => format!("base64:{}", BASE64.encode(bytes)),
+    }
+}
+
+fn unexpected(response: ManagementResponse) -> anyhow::Error {
+    anyhow!("Unexpected response from Theater: {:?}", response)
+}
@@ -0,0 +1,121 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::dependencies::{self, DependencySpec};
+use crate::registry::Registry;
+use crate::utils;
+
+pub fn register_add_dependency_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "add-dependency",
+        Some("Adds or updates a Cargo dependency in one or more actors"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (optional if actors or all is given)"
+                },
+                "actors": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Names of several actors to apply the change to (optional)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "Apply the change to every actor in the registry (optional)"
+                },
+                "dependency": {
+                    "type": "object",
+                    "description": "The dependency to add (required)",
+                    "properties": {
+                        "name": { "type": "string", "description": "Crate name (required)" },
+                        "version": { "type": "string", "description": "Version requirement" },
+                        "features": { "type": "array", "items": { "type": "string" } },
+                        "default_features": { "type": "boolean" },
+                        "optional": { "type": "boolean" },
+                        "path": { "type": "string", "description": "Path to a local crate" },
+                        "git": { "type": "string", "description": "Git repository URL" },
+                        "branch": { "type": "string" },
+                        "tag": { "type": "string" },
+                        "rev": { "type": "string" }
+                    },
+                    "required": ["name"]
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Add the dependency even if it is known not to compile for wasm32-unknown-unknown (optional)"
+                }
+            },
+            "required": ["dependency"]
+        }),
+        move |args| {
+            let spec = args
+                .get("dependency")
+                .ok_or_else(|| anyhow!("Missing required parameter: dependency"))
+                .and_then(DependencySpec::from_json)?;
+            let names = utils::target_actor_names(&args);
+            let all = args.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+            let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            debug!("Adding dependency '{}' to actors {:?}", spec.name, names);
+
+            let actors = match spec.validate().and_then(|_| {
+                if !force {
+                    spec.check_wasm_compatible()?;
+                }
+                registry.select_writable_actors(&names, all)
+            }) {
+                Ok(actors) => actors,
+                Err(e) => {
+                    error!("Failed to add dependency '{}': {}", spec.name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to add dependency '{}': {}", spec.name, e),
+                    }];
+
+                    return Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    });
+                }
+            };
+
+            let mut text = format!("## Adding {}\n\n", spec.to_text());
+            let mut failed = false;
+            for actor in &actors {
+                match dependencies::add_dependency(actor, &spec, force) {
+                    Ok(Some(previous)) => {
+                        info!("Updated dependency '{}' in actor '{}'", spec.name, actor.name);
+                        text.push_str(&format!(
+                            "- {}: updated (was {})\n",
+                            actor.name,
+                            previous.to_text()
+                        ));
+                    }
+                    Ok(None) => {
+                        info!("Added dependency '{}' to actor '{}'", spec.name, actor.name);
+                        text.push_str(&format!("- {}: added\n", actor.name));
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to add dependency '{}' to actor '{}': {}",
+                            spec.name, actor.name, e
+                        );
+                        failed = true;
+                        text.push_str(&format!("- {}: failed: {}\n", actor.name, e));
+                    }
+                }
+            }
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(failed),
+            })
+        },
+    )
+}
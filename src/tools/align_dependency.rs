@@ -0,0 +1,140 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::dependencies;
+use crate::registry::Registry;
+use crate::utils;
+
+pub fn register_align_dependency_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "align-dependency",
+        Some("Aligns a dependency to one version across actors, with a dry-run diff"),
+        json!({
+            "type": "object",
+            "properties": {
+                "dependency": {
+                    "type": "string",
+                    "description": "Name of the crate to align (required)"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Version requirement to align to, e.g. \"0.41.0\" (required)"
+                },
+                "actors": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only align these actors (optional, defaults to every actor using the crate)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Show the diff without writing any files (optional)"
+                }
+            },
+            "required": ["dependency", "version"]
+        }),
+        move |args| {
+            let dependency = args
+                .get("dependency")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: dependency"))?;
+            let version = args
+                .get("version")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: version"))?;
+            let dry_run = args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+            let names = utils::target_actor_names(&args);
+
+            debug!(
+                "Aligning dependency '{}' to {} (dry run: {})",
+                dependency, version, dry_run
+            );
+
+            // A dry run only reads, so it can cover read-only roots as well
+            let selected = dependencies::parse_requirement(dependency, version).and_then(|_| {
+                if dry_run {
+                    registry.select_actors(&names, names.is_empty())
+                } else {
+                    registry.select_writable_actors(&names, names.is_empty())
+                }
+            });
+            let actors = match selected {
+                Ok(actors) => actors,
+                Err(e) => {
+                    error!("Failed to align dependency '{}': {}", dependency, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to align dependency '{}': {}", dependency, e),
+                    }];
+
+                    return Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    });
+                }
+            };
+
+            let mut diffs = String::new();
+            let mut changed = Vec::new();
+            let mut failed = Vec::new();
+            for actor in &actors {
+                match dependencies::align_dependency(actor, dependency, version, dry_run) {
+                    Ok(Some(diff)) => {
+                        changed.push(actor.name.clone());
+                        diffs.push_str(&diff);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        error!(
+                            "Failed to align dependency '{}' of actor '{}': {}",
+                            dependency, actor.name, e
+                        );
+                        failed.push(format!("{}: {}", actor.name, e));
+                    }
+                }
+            }
+
+            if !dry_run && !changed.is_empty() {
+                info!(
+                    "Aligned dependency '{}' to {} in {:?}",
+                    dependency, version, changed
+                );
+            }
+
+            let mut text = if dry_run {
+                format!(
+                    "Dry run: {} actor(s) would be aligned to {} {}.\n",
+                    changed.len(),
+                    dependency,
+                    version
+                )
+            } else {
+                format!(
+                    "Aligned {} actor(s) to {} {}.\n",
+                    changed.len(),
+                    dependency,
+                    version
+                )
+            };
+
+            if !failed.is_empty() {
+                text.push_str("\n## Failures\n\n");
+                for failure in &failed {
+                    text.push_str(&format!("- {}\n", failure));
+                }
+            }
+
+            if !diffs.is_empty() {
+                text.push_str(&format!("\n```diff\n{}```\n", diffs));
+            }
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(!failed.is_empty()),
+            })
+        },
+    )
+}
@@ -1,11 +1,11 @@
 use anyhow::{anyhow, Result};
 use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
 use modelcontextprotocol_server::ServerBuilder;
-use serde_json::json;
+use serde_json::{json, Value};
 
-use std::process::Command;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
+use crate::registry::build::{theater_build_command, BuildOptions};
 use crate::registry::Registry;
 
 pub fn register_build_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
@@ -38,98 +38,83 @@ pub fn register_build_actor_tool(builder: ServerBuilder, registry: Registry) ->
             },
             "required": ["name"]
         }),
-        move |args| {
-            let name = args
-                .get("name")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
-
-            debug!("Building actor '{}'", name);
-
-            // First, find the actor to get its path
-            match registry.find_actor(name) {
-                Ok(actor) => {
-                    // Prepare the theater build command with optional arguments
-                    let mut cmd = Command::new("theater");
-                    cmd.arg("build");
+        move |args| handle(&registry, &args),
+    )
+}
 
-                    // Add optional flags
-                    if args
-                        .get("release")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false)
-                    {
-                        cmd.arg("--release");
-                    }
-                    if args.get("clean").and_then(|v| v.as_bool()).unwrap_or(false) {
-                        cmd.arg("--clean");
-                    }
-                    if args.get("force").and_then(|v| v.as_bool()).unwrap_or(false) {
-                        cmd.arg("--force");
-                    }
-                    if args
-                        .get("verbose")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false)
-                    {
-                        cmd.arg("--verbose");
-                    }
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
 
-                    // Add the actor path as the final argument
-                    cmd.arg(&actor.path);
+    debug!("Building actor '{}'", name);
 
-                    // Execute the command
-                    info!("Executing: {:?}", cmd);
-                    match cmd.output() {
-                        Ok(output) => {
-                            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    // First, find the actor to get its path
+    match registry.find_writable_actor(name) {
+        Ok(actor) => {
+            let options = BuildOptions::from_args(args)
+                .with_theater_binary(registry.config().theater_binary());
+            let mut cmd = theater_build_command(&actor.path, &options);
 
-                            if output.status.success() {
-                                info!("Successfully built actor '{}'", name);
-                                let content = vec![ToolContent::Text {
-                                    text: format!(
-                                        "Actor '{}' successfully built.\n\nOutput:\n{}\n{}",
-                                        name, stdout, stderr
-                                    ),
-                                }];
+            // Execute the command
+            info!("Executing: {:?}", cmd);
+            match cmd.output() {
+                Ok(output) => {
+                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-                                Ok(ToolCallResult {
-                                    content,
-                                    is_error: Some(false),
-                                })
-                            } else {
-                                error!("Failed to build actor '{}': {}", name, stderr);
-                                let content = vec![ToolContent::Text {
-                                    text: format!(
-                                        "Failed to build actor '{}':\n\nOutput:\n{}\n\nError:\n{}",
-                                        name, stdout, stderr
-                                    ),
-                                }];
+                    if output.status.success() {
+                        info!("Successfully built actor '{}'", name);
+                        let mut text = format!(
+                            "Actor '{}' successfully built.\n\nOutput:\n{}\n{}",
+                            name, stdout, stderr
+                        );
 
-                                Ok(ToolCallResult {
-                                    content,
-                                    is_error: Some(true),
-                                })
+                        // Post-build step: make sure the manifest provides every
+                        // handler the freshly built component imports
+                        match registry.check_compatibility(name) {
+                            Ok(report) => {
+                                if !report.is_compatible() {
+                                    warn!("Actor '{}' is missing handlers for its imports", name);
+                                }
+                                text.push_str("\n## Handler Compatibility\n\n");
+                                text.push_str(&report.to_text());
+                            }
+                            Err(e) => {
+                                warn!("Failed to check compatibility for actor '{}': {}", name, e);
+                                text.push_str(&format!(
+                                    "\nCould not check handler compatibility: {}\n",
+                                    e
+                                ));
                             }
                         }
-                        Err(e) => {
-                            error!("Failed to execute theater build command: {}", e);
-                            let content = vec![ToolContent::Text {
-                                text: format!("Failed to execute theater build command: {}", e),
-                            }];
 
-                            Ok(ToolCallResult {
-                                content,
-                                is_error: Some(true),
-                            })
-                        }
+                        let content = vec![ToolContent::Text { text }];
+
+                        Ok(ToolCallResult {
+                            content,
+                            is_error: Some(false),
+                        })
+                    } else {
+                        error!("Failed to build actor '{}': {}", name, stderr);
+                        let content = vec![ToolContent::Text {
+                            text: format!(
+                                "Failed to build actor '{}':\n\nOutput:\n{}\n\nError:\n{}",
+                                name, stdout, stderr
+                            ),
+                        }];
+
+                        Ok(ToolCallResult {
+                            content,
+                            is_error: Some(true),
+                        })
                     }
                 }
                 Err(e) => {
-                    error!("Failed to find actor '{}' for building: {}", name, e);
+                    error!("Failed to execute theater build command: {}", e);
                     let content = vec![ToolContent::Text {
-                        text: format!("Failed to find actor '{}': {}", name, e),
+                        text: format!("Failed to execute theater build command: {}", e),
                     }];
 
                     Ok(ToolCallResult {
@@ -138,6 +123,17 @@ pub fn register_build_actor_tool(builder: ServerBuilder, registry: Registry) ->
                     })
                 }
             }
-        },
-    )
+        }
+        Err(e) => {
+            error!("Failed to find actor '{}' for building: {}", name, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to find actor '{}': {}", name, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
 }
@@ -0,0 +1,68 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::Registry;
+
+pub fn register_check_compatibility_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "check-actor-compatibility",
+        Some("Checks that an actor's manifest handlers match the interfaces its built component imports"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "format": {
+                    "type": "string",
+                    "description": "Output format (optional: text, json)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("text");
+
+            debug!("Checking handler compatibility for actor '{}'", name);
+
+            match registry.check_compatibility(name) {
+                Ok(report) => {
+                    let text = if format == "json" {
+                        serde_json::to_string_pretty(&report)?
+                    } else {
+                        format!("# Compatibility: {}\n\n{}", name, report.to_text())
+                    };
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(!report.is_compatible()),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to check compatibility for actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to check compatibility for actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
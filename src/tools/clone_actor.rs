@@ -0,0 +1,88 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::source::CloneOptions;
+use crate::registry::Registry;
+
+pub fn register_clone_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "clone-actor",
+        Some("Creates an actor from a git repository, copying the actor's files into the registry and recording where they came from for update-actor-from-source"),
+        json!({
+            "type": "object",
+            "properties": {
+                "source": {
+                    "type": "string",
+                    "description": "Git URL or path of a local repository (required)"
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch, tag or commit to clone (optional, defaults to the repository's default branch)"
+                },
+                "subdirectory": {
+                    "type": "string",
+                    "description": "Directory of the actor inside the repository (optional, defaults to the repository root)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor in the registry, which may be namespaced (optional, defaults to the name in its manifest)"
+                }
+            },
+            "required": ["source"]
+        }),
+        move |args| {
+            let source = args
+                .get("source")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: source"))?;
+            let string_arg = |key: &str| {
+                args.get(key)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+            };
+
+            debug!("Cloning actor from {}", source);
+
+            let options = CloneOptions {
+                source: source.to_string(),
+                reference: string_arg("ref"),
+                subdirectory: string_arg("subdirectory"),
+                name: string_arg("name"),
+            };
+
+            match registry.clone_actor(&options) {
+                Ok((actor, record)) => {
+                    info!("Cloned actor '{}' from {}", actor.name, source);
+                    let text = format!(
+                        "Actor '{}' cloned to {}\n\nSource: {}\nCommit: {}\n\nRun update-actor-from-source to pull later changes.",
+                        actor.name,
+                        actor.path.display(),
+                        record.describe(),
+                        record.commit
+                    );
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to clone actor from {}: {}", source, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to clone actor from {}: {}", source, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,84 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::history::ActorRepo;
+use crate::registry::Registry;
+
+pub fn register_commit_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "commit-actor",
+        Some("Commits the changes in an actor's directory, and nothing outside it, to the git repository the registry lives in"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Commit message (optional, generated from the changes by default)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let message = args.get("message").and_then(|v| v.as_str());
+
+            debug!("Committing actor '{}'", name);
+
+            let result = registry
+                .find_writable_actor(name)
+                .and_then(|actor| ActorRepo::open(&actor)?.commit(message));
+
+            match result {
+                Ok(commit) => {
+                    info!("Committed actor '{}' as {}", name, commit.id);
+                    let mut text = format!(
+                        "Committed {} file(s) of actor '{}' as {}.\n\nAuthor: {}\n\n{}",
+                        commit.changes.len(),
+                        name,
+                        commit.id,
+                        commit.author,
+                        commit.message.trim_end()
+                    );
+                    if message.is_some() {
+                        text.push_str("\n\nFiles:\n");
+                        for change in &commit.changes {
+                            text.push_str(&format!(
+                                "- {} ({})\n",
+                                change.path,
+                                change.kind.as_str()
+                            ));
+                        }
+                    }
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to commit actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to commit actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
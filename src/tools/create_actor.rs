@@ -1,8 +1,8 @@
 use anyhow::{anyhow, Result};
 use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
 use modelcontextprotocol_server::ServerBuilder;
-use serde_json::json;
-use tracing::{debug, error, info};
+use serde_json::{json, Value};
+use tracing::{debug, error};
 
 use crate::registry::Registry;
 
@@ -15,54 +15,56 @@ pub fn register_create_actor_tool(builder: ServerBuilder, registry: Registry) ->
             "properties": {
                 "name": {
                     "type": "string",
-                    "description": "Name of the actor (required)"
+                    "description": "Name of the actor, optionally namespaced like team-a/logger (required)"
                 },
                 "template": {
                     "type": "string",
-                    "description": "Template to use (defaults to basic, only basic is supported)"
+                    "description": "Template to use (defaults to the namespace or registry default_template, only basic is supported)"
                 }
             },
             "required": ["name"]
         }),
-        move |args| {
-            let name = args
-                .get("name")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+        move |args| handle(&registry, &args),
+    )
+}
 
-            // Optional parameters
-            let template = args.get("template").and_then(|v| v.as_str());
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
 
-            debug!("Creating actor '{}' with template '{:?}'", name, template);
+    // Optional parameters
+    let template = args.get("template").and_then(|v| v.as_str());
 
-            // Clone interfaces to avoid losing ownership
-            match registry.create_actor(name, template) {
-                Ok(actor) => {
-                    let content = vec![ToolContent::Text {
-                        text: format!(
-                            "Actor '{}' successfully created at {}",
-                            name,
-                            actor.path.display()
-                        ),
-                    }];
+    debug!("Creating actor '{}' with template '{:?}'", name, template);
 
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: Some(false),
-                    })
-                }
-                Err(e) => {
-                    error!("Failed to create actor: {}", e);
-                    let content = vec![ToolContent::Text {
-                        text: format!("Failed to create actor: {}", e),
-                    }];
+    // Clone interfaces to avoid losing ownership
+    match registry.create_actor(name, template) {
+        Ok(actor) => {
+            let content = vec![ToolContent::Text {
+                text: format!(
+                    "Actor '{}' successfully created at {}",
+                    name,
+                    actor.path.display()
+                ),
+            }];
 
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: Some(true),
-                    })
-                }
-            }
-        },
-    )
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to create actor: {}", e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to create actor: {}", e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
 }
@@ -0,0 +1,79 @@
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::dependencies::DependencyReport;
+use crate::registry::Registry;
+
+pub fn register_dependency_report_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "dependency-report",
+        Some("Aggregates dependencies across all actors and highlights version drift"),
+        json!({
+            "type": "object",
+            "properties": {
+                "drift_only": {
+                    "type": "boolean",
+                    "description": "Only show dependencies whose versions differ between actors (optional)"
+                },
+                "format": {
+                    "type": "string",
+                    "description": "Output format (optional: text, json)"
+                }
+            }
+        }),
+        move |args| {
+            let drift_only = args
+                .get("drift_only")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("text");
+
+            debug!("Building dependency report (drift only: {})", drift_only);
+
+            match registry.list_actors() {
+                Ok(actors) => {
+                    let report = DependencyReport::build(&actors);
+                    let drifted = report.drifted();
+
+                    let text = if format == "json" {
+                        let dependencies: serde_json::Map<String, serde_json::Value> = report
+                            .dependencies
+                            .keys()
+                            .filter(|name| !drift_only || drifted.contains(*name))
+                            .map(|name| (name.clone(), json!(report.sources(name))))
+                            .collect();
+                        serde_json::to_string_pretty(&json!({
+                            "dependencies": dependencies,
+                            "drifted": drifted,
+                        }))?
+                    } else {
+                        report.to_text(drift_only)
+                    };
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to build dependency report: {}", e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to build dependency report: {}", e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
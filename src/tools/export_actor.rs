@@ -0,0 +1,88 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use std::path::Path;
+use tracing::{debug, error};
+
+use crate::registry::bundle::ExportOptions;
+use crate::registry::Registry;
+
+pub fn register_export_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "export-actor",
+        Some("Packages an actor into a deterministic .tar.zst bundle"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "output": {
+                    "type": "string",
+                    "description": "Path of the bundle to write (optional, defaults to <registry>/.bundles/<name>-<version>.tar.zst)"
+                },
+                "include_component": {
+                    "type": "boolean",
+                    "description": "Include the built component (optional)"
+                },
+                "checksums": {
+                    "type": "boolean",
+                    "description": "Include a SHA-256 checksum manifest (optional, defaults to true)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let output = args.get("output").and_then(|v| v.as_str()).map(Path::new);
+            let options = ExportOptions {
+                include_component: args
+                    .get("include_component")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                checksums: args
+                    .get("checksums")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true),
+            };
+
+            debug!("Exporting actor '{}' with {:?}", name, options);
+
+            match registry.export_actor(name, output, &options) {
+                Ok(info) => {
+                    let text = format!(
+                        "Actor '{}' exported to {}\n\nFiles: {}\nSize: {} bytes\nSHA-256: {}\n",
+                        name,
+                        info.path.display(),
+                        info.files,
+                        info.size,
+                        info.sha256
+                    );
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to export actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to export actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,99 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::history::ActorRepo;
+use crate::registry::Registry;
+
+pub fn register_get_actor_changes_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "get-actor-changes",
+        Some("Shows the uncommitted changes in an actor's directory, from the git repository the registry lives in"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "diff": {
+                    "type": "boolean",
+                    "description": "Include the patch against HEAD, untracked files included (optional, defaults to false)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let include_diff = args.get("diff").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            debug!("Getting uncommitted changes of actor '{}'", name);
+
+            let result = registry.find_actor(name).and_then(|actor| {
+                let repo = ActorRepo::open(&actor)?;
+                let changes = repo.status()?;
+                let diff = if include_diff && !changes.is_empty() {
+                    Some(repo.diff()?)
+                } else {
+                    None
+                };
+                Ok((changes, diff))
+            });
+
+            match result {
+                Ok((changes, diff)) => {
+                    let text = if changes.is_empty() {
+                        format!("Actor '{}' has no uncommitted changes.", name)
+                    } else {
+                        let mut text = format!("Uncommitted changes in actor '{}':\n", name);
+                        for (staged, heading) in [(true, "Staged"), (false, "Not staged")] {
+                            let group: Vec<_> =
+                                changes.iter().filter(|c| c.staged == staged).collect();
+                            if group.is_empty() {
+                                continue;
+                            }
+                            text.push_str(&format!("\n{}:\n", heading));
+                            for change in group {
+                                text.push_str(&format!(
+                                    "- {} ({})\n",
+                                    change.path,
+                                    change.kind.as_str()
+                                ));
+                            }
+                        }
+                        if let Some(diff) = diff {
+                            text.push_str(&format!("\nDiff:\n{}", diff));
+                        }
+                        text
+                    };
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to get changes of actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to get changes of actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
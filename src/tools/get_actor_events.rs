@@ -0,0 +1,162 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use tracing::{debug, error};
+
+use crate::registry::Registry;
+use crate::theater_client::chain::{self, describe_event, EventFilter, EventSummary};
+use crate::theater_client::ChainEvent;
+
+pub fn register_get_actor_events_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "get-actor-events",
+        Some("Reads an actor's event chain from the Theater server, or from the chain store once it has stopped, and summarizes state changes and errors"),
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Runtime id of the actor (required)"
+                },
+                "source": {
+                    "type": "string",
+                    "enum": ["auto", "server", "disk"],
+                    "description": "Where to read the chain: the server, the on-disk chain store, or the server falling back to disk (default: auto)"
+                },
+                "event_type": {
+                    "description": "Only events whose type contains this string, or any of these strings (optional)"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only events at or after this time, RFC 3339 or milliseconds since the epoch (optional)"
+                },
+                "until": {
+                    "type": "string",
+                    "description": "Only events at or before this time (optional)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Only the last N matching events (optional)"
+                }
+            },
+            "required": ["id"]
+        }),
+        move |args| handle(&registry, &args),
+    )
+}
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let id = args
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: id"))?;
+    let source = args
+        .get("source")
+        .and_then(|v| v.as_str())
+        .unwrap_or("auto");
+
+    debug!("Getting events of actor {} (source: {})", id, source);
+
+    let result = EventFilter::from_args(args)
+        .and_then(|filter| Ok((filter, read_events(registry, id, source)?)));
+
+    match result {
+        Ok((filter, (events, origin))) => {
+            let matching = filter.apply(&events);
+            let summary = EventSummary::of(matching.iter().copied());
+
+            let mut text = format!(
+                "Actor {}: {} of {} events match (from {}).\n",
+                id,
+                matching.len(),
+                events.len(),
+                origin
+            );
+
+            if let (Some(first), Some(last)) = (summary.first, summary.last) {
+                text.push_str(&format!("Between {} and {}.\n", first, last));
+            }
+
+            if !summary.by_type.is_empty() {
+                text.push_str("\nEvent types:\n");
+                for (event_type, count) in &summary.by_type {
+                    text.push_str(&format!("- {}: {}\n", event_type, count));
+                }
+            }
+
+            push_section(&mut text, "State changes", &summary.state_changes);
+            push_section(&mut text, "Errors", &summary.errors);
+
+            if !matching.is_empty() {
+                text.push_str("\nEvents:\n");
+                for event in &matching {
+                    text.push_str(&format!("- {}\n", describe_event(event)));
+                }
+            }
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to get events of actor {}: {}", id, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to get events of actor {}: {:#}", id, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
+
+/// The actor's chain and a description of where it came from
+fn read_events(registry: &Registry, id: &str, source: &str) -> Result<(Vec<ChainEvent>, String)> {
+    let from_server = || {
+        let client = registry.theater_client();
+        client
+            .actor_events(id)
+            .map(|events| (events, format!("Theater at {}", client.addr())))
+    };
+    let from_disk = || {
+        let store = registry
+            .chain_store()
+            .ok_or_else(|| anyhow!("No chain_store configured and no home directory"))?;
+        chain::load_chain(&store, id).map(|events| (events, store.display().to_string()))
+    };
+
+    match source {
+        "server" => from_server(),
+        "disk" => from_disk(),
+        "auto" => from_server().or_else(|server_error| {
+            debug!("Reading chain from disk: {}", server_error);
+            from_disk().map_err(|disk_error| {
+                anyhow!(
+                    "not on the server ({:#}) nor on disk ({:#})",
+                    server_error,
+                    disk_error
+                )
+            })
+        }),
+        other => Err(anyhow!(
+            "Unknown source '{}'; expected auto, server or disk",
+            other
+        )),
+    }
+}
+
+fn push_section(text: &mut String, title: &str, events: &[ChainEvent]) {
+    if events.is_empty() {
+        return;
+    }
+    text.push_str(&format!("\n{} ({}):\n", title, events.len()));
+    for event in events {
+        text.push_str(&format!("- {}\n", describe_event(event)));
+    }
+}
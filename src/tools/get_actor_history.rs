@@ -0,0 +1,92 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::history::ActorRepo;
+use crate::registry::Registry;
+
+pub fn register_get_actor_history_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "get-actor-history",
+        Some("Lists the recent git commits that changed an actor's directory, with their release tags"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of commits to show (optional, defaults to 10)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+
+            debug!("Getting history of actor '{}'", name);
+
+            let result = registry
+                .find_actor(name)
+                .and_then(|actor| ActorRepo::open(&actor)?.log(limit));
+
+            match result {
+                Ok(entries) => {
+                    let text = if entries.is_empty() {
+                        format!("Actor '{}' has no commits yet.", name)
+                    } else {
+                        let mut text = format!(
+                            "Last {} commit(s) changing actor '{}':\n\n",
+                            entries.len(),
+                            name
+                        );
+                        for entry in entries {
+                            let date = entry
+                                .time
+                                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_default();
+                            text.push_str(&format!(
+                                "{} {} {}: {}",
+                                entry.id, date, entry.author, entry.summary
+                            ));
+                            if !entry.tags.is_empty() {
+                                text.push_str(&format!(" [{}]", entry.tags.join(", ")));
+                            }
+                            text.push('\n');
+                        }
+                        text
+                    };
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to get history of actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to get history of actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
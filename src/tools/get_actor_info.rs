@@ -1,9 +1,9 @@
 use anyhow::{anyhow, Result};
 use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
 use modelcontextprotocol_server::ServerBuilder;
-use serde_json::json;
+use serde_json::{json, Value};
 use std::fs;
-use tracing::{debug, error, info};
+use tracing::{debug, error};
 
 use crate::registry::Registry;
 
@@ -21,70 +21,72 @@ pub fn register_get_actor_info_tool(builder: ServerBuilder, registry: Registry)
             },
             "required": ["name"]
         }),
-        move |args| {
-            let name = args
-                .get("name")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
-
-            debug!("Getting info for actor '{}'", name);
-
-            match registry.find_actor(name) {
-                Ok(actor) => {
-                    // Format as text
-                    let mut text = format!("# Actor: {}\n\n", actor.name);
+        move |args| handle(&registry, &args),
+    )
+}
 
-                    if let Some(ref manifest) = actor.manifest {
-                        // We no longer have version or description in Theater's manifest
-                        text.push_str("Theater Component\n");
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
 
-                        text.push_str("\n## Interfaces\n\n");
+    debug!("Getting info for actor '{}'", name);
 
-                        if !manifest.component.is_empty() {
-                            text.push_str(&format!("\nComponent path: {}\n", manifest.component));
-                        }
-                    }
+    match registry.resolve_actor(name) {
+        Ok(actor) => {
+            // Format as text
+            let mut text = format!("# Actor: {}\n\n", actor.name);
 
-                    text.push_str(&format!(
-                        "\n## Build Status\n\n{:?}\n",
-                        actor.build_info.build_status
-                    ));
+            if let Some(ref manifest) = actor.manifest {
+                // We no longer have version or description in Theater's manifest
+                text.push_str("Theater Component\n");
 
-                    if let Some(ref cargo) = actor.cargo_config {
-                        text.push_str("\n## Dependencies\n\n");
-                        for (name, version) in &cargo.dependencies {
-                            text.push_str(&format!("- {}: {}\n", name, version));
-                        }
-                    }
+                text.push_str("\n## Interfaces\n\n");
 
-                    // Read README if exists
-                    let readme_path = actor.path.join("README.md");
-                    if readme_path.exists() {
-                        if let Ok(readme) = fs::read_to_string(readme_path) {
-                            text.push_str("\n## README\n\n");
-                            text.push_str(&readme);
-                        }
-                    }
+                if !manifest.component.is_empty() {
+                    text.push_str(&format!("\nComponent path: {}\n", manifest.component));
+                }
+            }
 
-                    let content = vec![ToolContent::Text { text }];
+            text.push_str(&format!(
+                "\n## Build Status\n\n{:?}\n",
+                actor.build_info.build_status
+            ));
 
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: Some(false),
-                    })
+            if let Some(ref cargo) = actor.cargo_config {
+                text.push_str("\n## Dependencies\n\n");
+                for (name, version) in &cargo.dependencies {
+                    text.push_str(&format!("- {}: {}\n", name, version));
                 }
-                Err(e) => {
-                    error!("Failed to get actor info: {}", e);
-                    let content = vec![ToolContent::Text {
-                        text: format!("Failed to get actor info: {}", e),
-                    }];
+            }
 
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: Some(true),
-                    })
+            // Read README if exists
+            let readme_path = actor.path.join("README.md");
+            if readme_path.exists() {
+                if let Ok(readme) = fs::read_to_string(readme_path) {
+                    text.push_str("\n## README\n\n");
+                    text.push_str(&readme);
                 }
             }
-        },
-    )
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to get actor info: {}", e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to get actor info: {}", e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
 }
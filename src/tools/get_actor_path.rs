@@ -1,9 +1,8 @@
 use anyhow::{anyhow, Result};
 use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
 use modelcontextprotocol_server::ServerBuilder;
-use serde_json::json;
-use std::path::PathBuf;
-use tracing::{debug, error, info};
+use serde_json::{json, Value};
+use tracing::{debug, error};
 
 use crate::registry::Registry;
 
@@ -21,40 +20,42 @@ pub fn register_get_actor_path_tool(builder: ServerBuilder, registry: Registry)
             },
             "required": ["name"]
         }),
-        move |args| {
-            let name = args
-                .get("name")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
-
-            debug!("Getting path for actor '{}'", name);
-
-            match registry.find_actor(name) {
-                Ok(actor) => {
-                    let base_path = actor.path.clone();
-
-                    // Format the path string
-                    let path_str = base_path.to_string_lossy().to_string();
-
-                    let content = vec![ToolContent::Text { text: path_str }];
-
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: None,
-                    })
-                }
-                Err(e) => {
-                    error!("Failed to get actor path: {}", e);
-                    let content = vec![ToolContent::Text {
-                        text: format!("Failed to get actor path: {}", e),
-                    }];
-
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: Some(true),
-                    })
-                }
-            }
-        },
+        move |args| handle(&registry, &args),
     )
 }
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+
+    debug!("Getting path for actor '{}'", name);
+
+    match registry.resolve_actor(name) {
+        Ok(actor) => {
+            let base_path = actor.path.clone();
+
+            // Format the path string
+            let path_str = base_path.to_string_lossy().to_string();
+
+            let content = vec![ToolContent::Text { text: path_str }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: None,
+            })
+        }
+        Err(e) => {
+            error!("Failed to get actor path: {}", e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to get actor path: {}", e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
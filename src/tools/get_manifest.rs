@@ -0,0 +1,70 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::Registry;
+
+pub fn register_get_manifest_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "get-manifest",
+        Some("Returns an actor's manifest.toml"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "format": {
+                    "type": "string",
+                    "description": "Output format (optional: toml, json; defaults to toml)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let format = args
+                .get("format")
+                .and_then(|v| v.as_str())
+                .unwrap_or("toml");
+
+            debug!("Getting manifest for actor '{}'", name);
+
+            let result = registry.manifest_document(name).and_then(|doc| {
+                if format == "json" {
+                    Ok(serde_json::to_string_pretty(&doc.manifest()?)?)
+                } else {
+                    Ok(doc.to_string())
+                }
+            });
+
+            match result {
+                Ok(text) => {
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to get manifest for actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to get manifest for actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde::Serialize;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::config::{self, ConfigSource, ResolvedConfig};
+use crate::registry::Registry;
+
+pub fn register_get_registry_config_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "get-registry-config",
+        Some("Shows the effective registry configuration and where each value comes from"),
+        json!({
+            "type": "object",
+            "properties": {
+                "key": {
+                    "type": "string",
+                    "description": "Only show this key, e.g. theater_binary or logging.filter (optional)"
+                }
+            }
+        }),
+        move |args| {
+            let key = args.get("key").and_then(|v| v.as_str());
+
+            debug!("Getting registry config {:?}", key);
+
+            let result = registry
+                .resolve_config()
+                .and_then(|resolved| describe(&resolved, key));
+
+            match result {
+                Ok(text) => {
+                    let text = format!(
+                        "# Registry configuration\n\nConfig file: {}\n\n{}",
+                        registry.config_file().display(),
+                        text
+                    );
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to get registry config: {}", e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to get registry config: {:#}", e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
+
+/// Renders `value` as an inline TOML value
+fn inline<T: Serialize>(value: &T) -> Result<String> {
+    let value = value
+        .serialize(toml_edit::ser::ValueSerializer::new())
+        .with_context(|| "Failed to serialize config value")?;
+    Ok(value.to_string().trim().to_string())
+}
+
+/// Lists the effective value of `key`, or of every key, with the layer it
+/// comes from
+pub fn describe(resolved: &ResolvedConfig, key: Option<&str>) -> Result<String> {
+    let table =
+        toml::Table::try_from(&resolved.config).with_context(|| "Failed to serialize config")?;
+
+    let keys = match key {
+        Some(key) => vec![config::validate_key(key)?],
+        None => config::KEYS.iter().map(|key| vec![*key]).collect(),
+    };
+
+    let mut text = String::new();
+    for path in keys {
+        let value = path
+            .iter()
+            .try_fold(&toml::Value::Table(table.clone()), |value, segment| {
+                value.get(segment)
+            })
+            .cloned();
+        let source = resolved
+            .sources
+            .get(path[0])
+            .cloned()
+            .unwrap_or(ConfigSource::Default);
+
+        let rendered = match &value {
+            Some(value) => inline(value)?,
+            None => "(unset)".to_string(),
+        };
+        text.push_str(&format!(
+            "{} = {}  [{}]\n",
+            path.join("."),
+            rendered,
+            source
+        ));
+    }
+
+    Ok(text)
+}
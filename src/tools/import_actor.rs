@@ -0,0 +1,105 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use std::path::Path;
+use tracing::{debug, error, info};
+
+use crate::registry::bundle::{Collision, ImportOptions};
+use crate::registry::Registry;
+
+pub fn register_import_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "import-actor",
+        Some("Imports an actor bundle created by export-actor into the registry"),
+        json!({
+            "type": "object",
+            "properties": {
+                "bundle": {
+                    "type": "string",
+                    "description": "Path to the .tar.zst bundle (required)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Name to import the actor under (optional, defaults to the bundled name)"
+                },
+                "on_collision": {
+                    "type": "string",
+                    "description": "What to do if the actor already exists (optional: error, rename, overwrite; defaults to error)"
+                },
+                "allow_unverified": {
+                    "type": "boolean",
+                    "description": "Import a bundle that has no checksums to verify (optional, defaults to false)"
+                }
+            },
+            "required": ["bundle"]
+        }),
+        move |args| {
+            let bundle = args
+                .get("bundle")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: bundle"))?;
+
+            debug!("Importing actor bundle {}", bundle);
+
+            let result = args
+                .get("on_collision")
+                .and_then(|v| v.as_str())
+                .unwrap_or("error")
+                .parse::<Collision>()
+                .and_then(|on_collision| {
+                    let options = ImportOptions {
+                        name: args
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        on_collision,
+                        allow_unverified: args
+                            .get("allow_unverified")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false),
+                    };
+                    registry.import_actor(Path::new(bundle), &options)
+                });
+
+            match result {
+                Ok((actor, import)) => {
+                    info!("Imported actor '{}' from {}", actor.name, bundle);
+                    let mut text = format!(
+                        "Actor '{}' imported to {}\n",
+                        actor.name,
+                        actor.path.display()
+                    );
+                    if import.verified {
+                        text.push_str("Checksums: verified\n");
+                    } else {
+                        text.push_str("Checksums: none, the bundle was not verified\n");
+                    }
+                    if let Some(manifest) = &actor.manifest {
+                        if !manifest.component.is_empty() {
+                            text.push_str(&format!("Component: {}\n", manifest.component));
+                        }
+                    }
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to import actor bundle {}: {}", bundle, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to import actor bundle {}: {}", bundle, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tracing::{debug, error, warn};
+
+use crate::registry::init::{self, InitOptions, StepStatus};
+use crate::registry::Registry;
+
+pub fn register_init_registry_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "init-registry",
+        Some("Sets up a registry directory with a config, vendored WIT files and a git repository; safe to run on an existing registry"),
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory of the registry (optional, defaults to this server's registry)"
+                },
+                "wit_source": {
+                    "type": "string",
+                    "description": "Directory of Theater WIT files to vendor (optional, defaults to the configured wit_source)"
+                },
+                "git": {
+                    "type": "boolean",
+                    "description": "Initialize a git repository and .gitignore (default: true)"
+                }
+            }
+        }),
+        move |args| {
+            let path = args
+                .get("path")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| registry.path().to_path_buf());
+            let options = InitOptions {
+                wit_source: args
+                    .get("wit_source")
+                    .and_then(|v| v.as_str())
+                    .map(PathBuf::from)
+                    .or_else(|| registry.wit_source()),
+                git: args.get("git").and_then(|v| v.as_bool()).unwrap_or(true),
+            };
+
+            let result = handle(&path, &options);
+
+            // Pick up a config the init changed
+            if path == registry.path() {
+                if let Err(e) = registry.resolve_config() {
+                    warn!("Failed to reload the registry config: {}", e);
+                }
+            }
+
+            result
+        },
+    )
+}
+
+pub fn handle(path: &Path, options: &InitOptions) -> Result<ToolCallResult> {
+    debug!("Initializing registry at {:?}", path);
+
+    match init::init_registry(path, options) {
+        Ok(report) => {
+            let mut text = if report.unchanged() {
+                format!("Registry at {} is already set up.\n\n", path.display())
+            } else {
+                format!("Initialized registry at {}.\n\n", path.display())
+            };
+
+            for step in &report.steps {
+                let status = match &step.status {
+                    StepStatus::Created => "created".to_string(),
+                    StepStatus::Updated => "updated".to_string(),
+                    StepStatus::Unchanged => "unchanged".to_string(),
+                    StepStatus::Skipped(reason) => format!("skipped ({})", reason),
+                };
+                text.push_str(&format!("- {}: {}\n", step.name, status));
+            }
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to initialize registry at {:?}: {}", path, e);
+            let content = vec![ToolContent::Text {
+                text: format!(
+                    "Failed to initialize registry at {}: {:#}",
+                    path.display(),
+                    e
+                ),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
@@ -1,7 +1,8 @@
+use anyhow::Result;
 use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
 use modelcontextprotocol_server::ServerBuilder;
-use serde_json::json;
-use tracing::{debug, error};
+use serde_json::{json, Value};
+use tracing::{debug, error, warn};
 
 use crate::registry::Registry;
 
@@ -12,48 +13,101 @@ pub fn register_list_actors_tool(builder: ServerBuilder, registry: Registry) ->
         json!({
             "type": "object",
             "properties": {
+                "namespace": {
+                    "type": "string",
+                    "description": "Only list actors in this namespace, e.g. team-a (optional)"
+                }
             }
         }),
-        move |_| {
-            debug!("Listing actors in registry",);
+        move |args| handle(&registry, &args),
+    )
+}
 
-            match registry.list_actors() {
-                Ok(actors) => {
-                    // Apply filter if provided
-                    let mut text = format!("Found {} actors:\n\n", actors.len());
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let namespace = args.get("namespace").and_then(|v| v.as_str());
 
-                    for actor in &actors {
-                        text.push_str(&format!("- {} ", actor.name));
+    debug!("Listing actors in registry (namespace: {:?})", namespace);
 
-                        // Include actor interface information
-                        if let Some(ref manifest) = actor.manifest {
-                            text.push_str(&format!("- {}", manifest.name));
-                        }
+    let actors = match namespace {
+        Some(namespace) => registry.list_namespace(namespace),
+        None => registry.list_actors(),
+    };
 
-                        text.push_str(&format!(" [{}]", actor.build_info.build_status));
+    match actors {
+        Ok(actors) => {
+            // Apply filter if provided
+            let mut text = match namespace {
+                Some(namespace) => format!(
+                    "Found {} actors in namespace '{}':\n\n",
+                    actors.len(),
+                    namespace
+                ),
+                None => format!("Found {} actors:\n\n", actors.len()),
+            };
 
-                        text.push_str("\n");
+            for actor in &actors {
+                text.push_str(&format!("- {} ", actor.name));
+
+                // Include actor interface information
+                if let Some(ref manifest) = actor.manifest {
+                    text.push_str(&format!("- {}", manifest.name));
+                }
+
+                text.push_str(&format!(" [{}]", actor.build_info.build_status));
+
+                // Only worth showing when there is more than one root
+                if registry.roots().len() > 1 {
+                    if let Some(root) = registry.origin_root(actor) {
+                        text.push_str(&format!(" ({})", root.display()));
                     }
+                }
 
-                    let content = vec![ToolContent::Text { text }];
+                text.push('\n');
+            }
 
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: Some(false),
-                    })
+            if namespace.is_none() {
+                match registry.namespaces() {
+                    Ok(namespaces) if !namespaces.is_empty() => {
+                        text.push_str(&format!("\nNamespaces: {}\n", namespaces.join(", ")));
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to list namespaces: {}", e),
                 }
-                Err(e) => {
-                    error!("Failed to list actors: {}", e);
-                    let content = vec![ToolContent::Text {
-                        text: format!("Failed to list actors: {}", e),
-                    }];
-
-                    Ok(ToolCallResult {
-                        content,
-                        is_error: Some(true),
-                    })
+            }
+
+            match registry.shadowed_actors() {
+                Ok(shadowed) if !shadowed.is_empty() => {
+                    text.push_str("\nShadowed actors (not reachable by name):\n\n");
+                    for hidden in &shadowed {
+                        text.push_str(&format!(
+                            "- {} at {} (shadowed by {})\n",
+                            hidden.actor.name,
+                            hidden.actor.path.display(),
+                            hidden.shadowed_by.display()
+                        ));
+                    }
                 }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to check for shadowed actors: {}", e),
             }
-        },
-    )
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to list actors: {}", e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to list actors: {}", e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
 }
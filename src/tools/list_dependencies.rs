@@ -0,0 +1,103 @@
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::dependencies;
+use crate::registry::Registry;
+use crate::utils;
+
+pub fn register_list_dependencies_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "list-dependencies",
+        Some("Lists the Cargo dependencies of one or more actors"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (optional if actors or all is given)"
+                },
+                "actors": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Names of several actors (optional)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "List dependencies of every actor in the registry (optional)"
+                },
+                "format": {
+                    "type": "string",
+                    "description": "Output format (optional: text, json)"
+                }
+            }
+        }),
+        move |args| {
+            let names = utils::target_actor_names(&args);
+            let all = args.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+            let format = args
+                .get("format")
+                .and_then(|v| v.as_str())
+                .unwrap_or("text");
+
+            debug!("Listing dependencies for actors {:?} (all: {})", names, all);
+
+            let result = registry.select_actors(&names, all).and_then(|actors| {
+                actors
+                    .into_iter()
+                    .map(|actor| {
+                        let deps = dependencies::list_dependencies(&actor)?;
+                        Ok((actor.name, deps))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
+            });
+
+            match result {
+                Ok(listing) => {
+                    let text = if format == "json" {
+                        let map: serde_json::Map<String, serde_json::Value> = listing
+                            .iter()
+                            .map(|(name, deps)| (name.clone(), json!(deps)))
+                            .collect();
+                        serde_json::to_string_pretty(&map)?
+                    } else {
+                        let mut text = String::new();
+                        for (name, deps) in &listing {
+                            text.push_str(&format!("# {}\n\n", name));
+                            if deps.is_empty() {
+                                text.push_str("No dependencies\n");
+                            }
+                            for dep in deps {
+                                text.push_str(&format!("- {}\n", dep.to_text()));
+                            }
+                            text.push('\n');
+                        }
+                        text
+                    };
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to list dependencies: {}", e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to list dependencies: {}", e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,88 @@
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::Registry;
+
+pub fn register_list_remote_actors_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "list-remote-actors",
+        Some("Lists the actors available from the configured remote registries"),
+        json!({
+            "type": "object",
+            "properties": {
+                "remote": {
+                    "type": "string",
+                    "description": "Name of the remote to list (optional, defaults to all remotes)"
+                }
+            }
+        }),
+        move |args| {
+            let remote = args.get("remote").and_then(|v| v.as_str());
+
+            debug!("Listing remote actors from {:?}", remote);
+
+            match registry.list_remote_actors(remote) {
+                Ok(listings) if listings.is_empty() => {
+                    let content = vec![ToolContent::Text {
+                        text: "No remotes are configured. Add [[remotes]] entries to .registry.config.toml.".to_string(),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Ok(listings) => {
+                    let mut text = String::new();
+
+                    for listing in &listings {
+                        text.push_str(&format!(
+                            "Remote '{}' ({}){}: {} actors\n",
+                            listing.remote,
+                            listing.url,
+                            if listing.from_cache { " [offline, cached]" } else { "" },
+                            listing.actors.len()
+                        ));
+
+                        for actor in &listing.actors {
+                            text.push_str(&format!(
+                                "- {} {} [{}]",
+                                actor.name,
+                                actor.version.as_deref().unwrap_or("(unversioned)"),
+                                actor.build_status
+                            ));
+                            if let Some(description) = &actor.description {
+                                text.push_str(&format!(" - {}", description));
+                            }
+                            text.push('\n');
+                        }
+                        text.push('\n');
+                    }
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to list remote actors: {}", e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to list remote actors: {}", e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
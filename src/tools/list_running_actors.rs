@@ -0,0 +1,63 @@
+use anyhow::Result;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::Registry;
+
+pub fn register_list_running_actors_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "list-running-actors",
+        Some("Lists the actors running in the configured Theater server"),
+        json!({
+            "type": "object",
+            "properties": {}
+        }),
+        move |_args| handle(&registry),
+    )
+}
+
+pub fn handle(registry: &Registry) -> Result<ToolCallResult> {
+    let client = registry.theater_client();
+
+    debug!("Listing actors running in Theater at {}", client.addr());
+
+    match client.list_actors() {
+        Ok(actors) => {
+            let mut text = format!(
+                "Found {} running actors in Theater at {}:\n\n",
+                actors.len(),
+                client.addr()
+            );
+
+            for actor in &actors {
+                match &actor.name {
+                    Some(name) => text.push_str(&format!("- {} ({})\n", actor.id, name)),
+                    None => text.push_str(&format!("- {}\n", actor.id)),
+                }
+            }
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to list running actors: {}", e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to list running actors: {:#}", e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
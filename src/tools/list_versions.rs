@@ -0,0 +1,72 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::release;
+use crate::registry::Registry;
+
+pub fn register_list_versions_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "list-versions",
+        Some("Lists the released versions of an actor"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+
+            debug!("Listing versions of actor '{}'", name);
+
+            let result = registry.find_actor(name).and_then(|actor| {
+                let current = release::current_version(&actor)?;
+                let versions = release::list_versions(&actor)?;
+                Ok((current, versions))
+            });
+
+            match result {
+                Ok((current, versions)) => {
+                    let mut text = format!("# Versions of {}\n\nCurrent: {}\n\n", name, current);
+
+                    if versions.is_empty() {
+                        text.push_str("No releases yet\n");
+                    } else {
+                        text.push_str("## Releases\n\n");
+                        for version in versions.iter().rev() {
+                            text.push_str(&format!("- {}@{}\n", name, version));
+                        }
+                    }
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to list versions of actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to list versions of actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
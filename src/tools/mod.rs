@@ -1,5 +1,36 @@
-pub mod create_actor;
-pub mod list_actors;
+pub mod add_dependency;
+pub mod align_dependency;
 pub mod build_actor;
+pub mod check_compatibility;
+pub mod create_actor;
+pub mod dependency_report;
 pub mod get_actor_info;
 pub mod get_actor_path;
+pub mod get_manifest;
+pub mod list_actors;
+pub mod list_dependencies;
+pub mod list_versions;
+pub mod release_actor;
+pub mod remove_dependency;
+pub mod update_manifest;
+pub mod export_actor;
+pub mod import_actor;
+pub mod list_remote_actors;
+pub mod publish_actor;
+pub mod get_registry_config;
+pub mod set_registry_config;
+pub mod init_registry;
+pub mod start_actor;
+pub mod stop_actor;
+pub mod list_running_actors;
+pub mod send_message;
+pub mod request_actor;
+pub mod get_actor_events;
+pub mod smoke_test_actor;
+pub mod test_actor;
+pub mod get_actor_changes;
+pub mod commit_actor;
+pub mod get_actor_history;
+pub mod tag_actor_release;
+pub mod clone_actor;
+pub mod update_actor_from_source;
@@ -0,0 +1,74 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::Registry;
+
+pub fn register_publish_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "publish-actor",
+        Some("Publishes a local actor to a remote registry"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor to publish (required)"
+                },
+                "remote": {
+                    "type": "string",
+                    "description": "Name of the remote to publish to (optional, defaults to the first configured remote)"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace the actor if the remote already has it (optional, defaults to false)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let remote = args.get("remote").and_then(|v| v.as_str());
+            let overwrite = args
+                .get("overwrite")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            debug!("Publishing actor '{}' to remote {:?}", name, remote);
+
+            match registry.publish_actor(name, remote, overwrite) {
+                Ok(summary) => {
+                    info!("Published actor '{}'", name);
+                    let text = format!(
+                        "Actor '{}' {} published\n",
+                        summary.name,
+                        summary.version.as_deref().unwrap_or("(unversioned)")
+                    );
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to publish actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to publish actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::build::BuildOptions;
+use crate::registry::release::{self, VersionBump};
+use crate::registry::Registry;
+
+pub fn register_release_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "release-actor",
+        Some("Bumps an actor's version, builds it and stores the component and manifest under releases/<version>/"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "bump": {
+                    "type": "string",
+                    "description": "major, minor, patch or an exact version such as 1.2.0 (optional, defaults to patch)"
+                },
+                "release": {
+                    "type": "boolean",
+                    "description": "Build in release mode (optional)"
+                },
+                "clean": {
+                    "type": "boolean",
+                    "description": "Clean the target directory before building (optional)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let bump = args.get("bump").and_then(|v| v.as_str()).unwrap_or("patch");
+
+            debug!("Releasing actor '{}' with bump '{}'", name, bump);
+
+            let options = BuildOptions::from_args(&args)
+                .with_theater_binary(registry.config().theater_binary());
+            let result = bump.parse::<VersionBump>().and_then(|bump| {
+                let actor = registry.find_writable_actor(name)?;
+                release::release(&actor, &bump, &options)
+            });
+
+            match result {
+                Ok(info) => {
+                    info!("Released actor '{}' {}", name, info.version);
+                    let text = format!(
+                        "Released actor '{}' {} (previously {}).\n\nRelease directory: {}\nComponent: {}\n\nReference it as '{}@{}'.\n\nBuild output:\n{}",
+                        name,
+                        info.version,
+                        info.previous_version,
+                        info.path.display(),
+                        info.component.display(),
+                        name,
+                        info.version,
+                        info.build_output
+                    );
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to release actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to release actor '{}': {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,102 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::dependencies;
+use crate::registry::Registry;
+use crate::utils;
+
+pub fn register_remove_dependency_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "remove-dependency",
+        Some("Removes a Cargo dependency from one or more actors"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (optional if actors or all is given)"
+                },
+                "actors": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Names of several actors to apply the change to (optional)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "Apply the change to every actor in the registry (optional)"
+                },
+                "dependency": {
+                    "type": "string",
+                    "description": "Name of the crate to remove (required)"
+                }
+            },
+            "required": ["dependency"]
+        }),
+        move |args| {
+            let dependency = args
+                .get("dependency")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: dependency"))?;
+            let names = utils::target_actor_names(&args);
+            let all = args.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            debug!(
+                "Removing dependency '{}' from actors {:?}",
+                dependency, names
+            );
+
+            let actors = match registry.select_writable_actors(&names, all) {
+                Ok(actors) => actors,
+                Err(e) => {
+                    error!("Failed to remove dependency '{}': {}", dependency, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to remove dependency '{}': {}", dependency, e),
+                    }];
+
+                    return Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    });
+                }
+            };
+
+            let mut text = format!("## Removing {}\n\n", dependency);
+            let mut failed = false;
+            for actor in &actors {
+                match dependencies::remove_dependency(actor, dependency) {
+                    Ok(Some(_)) => {
+                        info!(
+                            "Removed dependency '{}' from actor '{}'",
+                            dependency, actor.name
+                        );
+                        text.push_str(&format!("- {}: removed\n", actor.name));
+                    }
+                    Ok(None) => {
+                        text.push_str(&format!("- {}: not a dependency\n", actor.name));
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to remove dependency '{}' from actor '{}': {}",
+                            dependency, actor.name, e
+                        );
+                        failed = true;
+                        text.push_str(&format!("- {}: failed: {}\n", actor.name, e));
+                    }
+                }
+            }
+
+            let content = vec![ToolContent::Text { text }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(failed),
+            })
+        },
+    )
+}
@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use tracing::{debug, error};
+
+use crate::registry::Registry;
+use crate::theater_client::{describe_payload, payload_from_args};
+
+pub fn register_request_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "request-actor",
+        Some("Sends a request to a running actor's handle-request and returns its response"),
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Runtime id returned by start-actor (required)"
+                },
+                "payload": {
+                    "description": "Request as any JSON value, sent serialized as JSON"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Request as text, sent as UTF-8 bytes"
+                },
+                "base64": {
+                    "type": "string",
+                    "description": "Request as base64-encoded bytes"
+                }
+            },
+            "required": ["id"]
+        }),
+        move |args| handle(&registry, &args),
+    )
+}
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let id = args
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: id"))?;
+
+    debug!("Sending request to actor {}", id);
+
+    let result =
+        payload_from_args(args).and_then(|data| registry.theater_client().request(id, data));
+
+    match result {
+        Ok(response) => {
+            let content = vec![ToolContent::Text {
+                text: format!(
+                    "Actor {} responded with {} bytes:\n\n{}",
+                    id,
+                    response.len(),
+                    describe_payload(&response)
+                ),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Request to actor {} failed: {}", id, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Request to actor {} failed: {:#}", id, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
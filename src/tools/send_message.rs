@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use tracing::{debug, error, info};
+
+use crate::registry::Registry;
+use crate::theater_client::payload_from_args;
+
+pub fn register_send_message_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "send-message",
+        Some("Sends a one-way message to a running actor's handle-send"),
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Runtime id returned by start-actor (required)"
+                },
+                "payload": {
+                    "description": "Message as any JSON value, sent serialized as JSON"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Message as text, sent as UTF-8 bytes"
+                },
+                "base64": {
+                    "type": "string",
+                    "description": "Message as base64-encoded bytes"
+                }
+            },
+            "required": ["id"]
+        }),
+        move |args| handle(&registry, &args),
+    )
+}
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let id = args
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: id"))?;
+
+    debug!("Sending message to actor {}", id);
+
+    let result = payload_from_args(args).and_then(|data| {
+        let len = data.len();
+        registry.theater_client().send_message(id, data)?;
+        Ok(len)
+    });
+
+    match result {
+        Ok(len) => {
+            info!("Sent {} bytes to actor {}", len, id);
+            let content = vec![ToolContent::Text {
+                text: format!("Sent {} bytes to actor {}.", len, id),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to send message to actor {}: {}", id, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to send message to actor {}: {:#}", id, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
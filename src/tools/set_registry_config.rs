@@ -0,0 +1,91 @@
+use anyhow::{anyhow, Context};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::registry::config::ConfigSource;
+use crate::registry::Registry;
+use crate::tools::get_registry_config::describe;
+
+pub fn register_set_registry_config_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "set-registry-config",
+        Some("Sets or removes a value in the registry's .registry.config.toml"),
+        json!({
+            "type": "object",
+            "properties": {
+                "key": {
+                    "type": "string",
+                    "description": "Key to set, e.g. default_template, theater_binary or logging.filter (required)"
+                },
+                "value": {
+                    "description": "New value in its TOML type; omit or pass null to remove the key so the user config or defaults apply (optional)"
+                }
+            },
+            "required": ["key"]
+        }),
+        move |args| {
+            let key = args
+                .get("key")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: key"))?;
+            let value = args.get("value").filter(|v| !v.is_null());
+
+            debug!("Setting registry config '{}' to {:?}", key, value);
+
+            let result = value
+                .map(|v| {
+                    toml::Value::try_from(v.clone())
+                        .with_context(|| format!("'{}' cannot be stored in TOML", v))
+                })
+                .transpose()
+                .and_then(|value| registry.set_config(key, value.as_ref()))
+                .and_then(|resolved| Ok((describe(&resolved, Some(key))?, resolved)));
+
+            match result {
+                Ok((described, resolved)) => {
+                    let action = if value.is_some() { "Updated" } else { "Removed" };
+                    let mut text = format!(
+                        "{} '{}' in {}\n\n{}",
+                        action,
+                        key,
+                        registry.config_file().display(),
+                        described
+                    );
+
+                    let top = key.split('.').next().unwrap_or(key);
+                    if resolved.sources.get(top) == Some(&ConfigSource::Overrides) {
+                        text.push_str(
+                            "\nThe environment or command line overrides this key for the running server.\n",
+                        );
+                    }
+                    if top == "extra_roots" || top == "logging" {
+                        text.push_str("\nRestart the server for this change to take effect.\n");
+                    }
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to set registry config '{}': {}", key, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to set registry config '{}': {:#}", key, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,94 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tracing::{debug, error};
+
+use crate::registry::build::BuildOptions;
+use crate::registry::smoke::{self, SmokeOptions};
+use crate::registry::Registry;
+
+pub fn register_smoke_test_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "smoke-test-actor",
+        Some("Builds an actor, starts it in the configured Theater server, runs the messages and requests in its tests/smoke.toml, checks the responses and logs, stops it and reports each step"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "init_state": {
+                    "description": "Initial state, any JSON value, overriding the script's init_state (optional)"
+                },
+                "script": {
+                    "type": "string",
+                    "description": "Script to run instead of the actor's tests/smoke.toml (optional)"
+                },
+                "build": {
+                    "type": "boolean",
+                    "description": "Build the actor first (default: true)"
+                },
+                "release": {
+                    "type": "boolean",
+                    "description": "Build in release mode (optional)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| handle(&registry, &args),
+    )
+}
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+
+    debug!("Smoke testing actor '{}'", name);
+
+    let options = SmokeOptions {
+        build: args.get("build").and_then(|v| v.as_bool()).unwrap_or(true),
+        build_options: BuildOptions::from_args(args)
+            .with_theater_binary(registry.config().theater_binary()),
+        init_state: args.get("init_state").filter(|v| !v.is_null()).cloned(),
+        script: args
+            .get("script")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from),
+    };
+
+    let result = if options.build {
+        registry.find_writable_actor(name)
+    } else {
+        registry.find_actor(name)
+    }
+    .and_then(|actor| smoke::run(&actor, &registry.theater_client(), &options));
+
+    match result {
+        Ok(report) => {
+            let content = vec![ToolContent::Text {
+                text: report.to_text(),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(!report.passed()),
+            })
+        }
+        Err(e) => {
+            error!("Failed to smoke test actor '{}': {}", name, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to smoke test actor '{}': {:#}", name, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use tracing::{debug, error, info};
+
+use crate::registry::compat::component_path;
+use crate::registry::Registry;
+
+pub fn register_start_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "start-actor",
+        Some("Starts a built actor in the configured Theater server and returns its runtime id"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor, or name@version for a release (required)"
+                },
+                "init_state": {
+                    "description": "Initial state passed to the actor instead of the manifest's (optional, any JSON value)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| handle(&registry, &args),
+    )
+}
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+
+    debug!("Starting actor '{}'", name);
+
+    match start(registry, name, args.get("init_state")) {
+        Ok(id) => {
+            info!("Started actor '{}' as {}", name, id);
+            let content = vec![ToolContent::Text {
+                text: format!(
+                    "Actor '{}' started in Theater at {}.\n\nActor ID: {}",
+                    name,
+                    registry.config().theater_addr(),
+                    id
+                ),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to start actor '{}': {}", name, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to start actor '{}': {:#}", name, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
+
+fn start(registry: &Registry, name: &str, init_state: Option<&Value>) -> Result<String> {
+    let actor = registry.resolve_actor(name)?;
+
+    let component = component_path(&actor)?;
+    if !component.exists() {
+        return Err(anyhow!(
+            "Component {} does not exist; build the actor with build-actor first",
+            component.display()
+        ));
+    }
+
+    let manifest = actor.path.join("manifest.toml").canonicalize()?;
+    let initial_state = match init_state {
+        None | Some(Value::Null) => None,
+        Some(state) => Some(serde_json::to_vec(state)?),
+    };
+
+    registry
+        .theater_client()
+        .start_actor(&manifest.to_string_lossy(), initial_state)
+}
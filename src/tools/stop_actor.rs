@@ -0,0 +1,59 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use tracing::{debug, error, info};
+
+use crate::registry::Registry;
+
+pub fn register_stop_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "stop-actor",
+        Some("Stops an actor running in the configured Theater server"),
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Runtime id returned by start-actor (required)"
+                }
+            },
+            "required": ["id"]
+        }),
+        move |args| handle(&registry, &args),
+    )
+}
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let id = args
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: id"))?;
+
+    debug!("Stopping actor {}", id);
+
+    match registry.theater_client().stop_actor(id) {
+        Ok(()) => {
+            info!("Stopped actor {}", id);
+            let content = vec![ToolContent::Text {
+                text: format!("Actor {} stopped.", id),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(false),
+            })
+        }
+        Err(e) => {
+            error!("Failed to stop actor {}: {}", id, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to stop actor {}: {:#}", id, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
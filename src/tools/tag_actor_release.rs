@@ -0,0 +1,99 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::history::ActorRepo;
+use crate::registry::release;
+use crate::registry::Registry;
+
+pub fn register_tag_actor_release_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "tag-actor-release",
+        Some("Tags the current git commit as a release of an actor, named <actor>@<version>"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor, optionally as name@version (required)"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Version to tag (optional, defaults to the actor's current version)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Tag message (optional)"
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Move the tag if it already exists (optional, defaults to false)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let reference = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let version = args.get("version").and_then(|v| v.as_str());
+            let message = args.get("message").and_then(|v| v.as_str());
+            let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            debug!("Tagging a release of actor '{}'", reference);
+
+            let result = release::parse_reference(reference).and_then(|(name, reference_version)| {
+                let actor = registry.find_writable_actor(name)?;
+                let version = match (version, reference_version) {
+                    (Some(version), _) => semver::Version::parse(version)
+                        .map_err(|e| anyhow!("Invalid version '{}': {}", version, e))?,
+                    (None, Some(version)) => version,
+                    (None, None) => release::current_version(&actor)?,
+                };
+                let tag =
+                    ActorRepo::open(&actor)?.tag_release(&version.to_string(), message, force)?;
+                Ok((name, tag))
+            });
+
+            match result {
+                Ok((name, tag)) => {
+                    info!("Tagged {} at {}", tag.name, tag.target);
+                    let mut text = format!(
+                        "Tagged {} at {} ({}).",
+                        tag.name, tag.target, tag.summary
+                    );
+                    if tag.dirty {
+                        text.push_str(&format!(
+                            "\n\nNote: actor '{}' has uncommitted changes that are not part of the tag. Commit them with commit-actor and retag with force if they belong in the release.",
+                            name
+                        ));
+                    }
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to tag a release of actor '{}': {}", reference, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to tag a release of actor '{}': {}", reference, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
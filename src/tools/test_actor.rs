@@ -0,0 +1,79 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tracing::{debug, error};
+
+use crate::registry::harness::{self, HarnessOptions};
+use crate::registry::Registry;
+
+pub fn register_test_actor_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "test-actor",
+        Some("Runs an actor's built component without Theater, with mocked runtime imports, calling init and then handle-send/handle-request for each step of its tests/smoke.toml"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor, or name@version for a release (required)"
+                },
+                "init_state": {
+                    "description": "Initial state, any JSON value, overriding the script's init_state (optional)"
+                },
+                "script": {
+                    "type": "string",
+                    "description": "Script to run instead of the actor's tests/smoke.toml (optional)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| handle(&registry, &args),
+    )
+}
+
+pub fn handle(registry: &Registry, args: &Value) -> Result<ToolCallResult> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+
+    debug!("Testing actor '{}' in the harness", name);
+
+    let options = HarnessOptions {
+        init_state: args.get("init_state").filter(|v| !v.is_null()).cloned(),
+        script: args
+            .get("script")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from),
+    };
+
+    let result = registry
+        .find_actor(name)
+        .and_then(|actor| harness::run(&actor, &options));
+
+    match result {
+        Ok(report) => {
+            let content = vec![ToolContent::Text {
+                text: report.to_text(),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(!report.passed()),
+            })
+        }
+        Err(e) => {
+            error!("Failed to test actor '{}': {}", name, e);
+            let content = vec![ToolContent::Text {
+                text: format!("Failed to test actor '{}': {:#}", name, e),
+            }];
+
+            Ok(ToolCallResult {
+                content,
+                is_error: Some(true),
+            })
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::anyhow;
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::json;
+use tracing::{debug, error, info};
+
+use crate::registry::source::UpdateOptions;
+use crate::registry::Registry;
+
+pub fn register_update_actor_from_source_tool(
+    builder: ServerBuilder,
+    registry: Registry,
+) -> ServerBuilder {
+    builder.with_tool(
+        "update-actor-from-source",
+        Some("Pulls upstream changes into an actor created by clone-actor. Local changes to files upstream didn't touch are kept."),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch, tag or commit to follow from now on (optional, defaults to the recorded one)"
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Overwrite local changes to files that also changed upstream (optional, defaults to false)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Show what would change without changing anything (optional, defaults to false)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let options = UpdateOptions {
+                reference: args
+                    .get("ref")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                force: args.get("force").and_then(|v| v.as_bool()).unwrap_or(false),
+                dry_run: args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false),
+            };
+
+            debug!("Updating actor '{}' from its source", name);
+
+            match registry.update_actor_from_source(name, &options) {
+                Ok(update) => {
+                    let short = |commit: &str| commit.chars().take(7).collect::<String>();
+                    let mut text = if update.is_unchanged() {
+                        format!(
+                            "Actor '{}' is up to date with {} at {}.\n",
+                            name,
+                            update.source.describe(),
+                            short(&update.source.commit)
+                        )
+                    } else {
+                        info!("Updated actor '{}' from its source", name);
+                        format!(
+                            "{} actor '{}' from {}: {} -> {}\n",
+                            if options.dry_run { "Would update" } else { "Updated" },
+                            name,
+                            update.source.describe(),
+                            short(&update.previous_commit),
+                            short(&update.source.commit)
+                        )
+                    };
+
+                    for (label, files) in [
+                        ("Added", &update.added),
+                        ("Modified", &update.modified),
+                        ("Removed", &update.removed),
+                        ("Local changes overwritten", &update.overwritten),
+                    ] {
+                        if files.is_empty() {
+                            continue;
+                        }
+                        text.push_str(&format!("\n{}:\n", label));
+                        for file in files {
+                            text.push_str(&format!("- {}\n", file));
+                        }
+                    }
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to update actor '{}' from its source: {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to update actor '{}' from its source: {}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
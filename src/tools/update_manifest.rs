@@ -0,0 +1,154 @@
+use anyhow::{anyhow, Result};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use modelcontextprotocol_server::ServerBuilder;
+use serde_json::{json, Value};
+use tracing::{debug, error, info};
+
+use crate::registry::manifest::{parse_handler, ManifestDocument, ManifestField};
+use crate::registry::Registry;
+
+fn apply_updates(doc: &mut ManifestDocument, args: &Value) -> Result<Vec<String>> {
+    let mut changes = Vec::new();
+
+    for field in ManifestField::ALL {
+        if let Some(new_value) = args.get(field.key()) {
+            doc.set_field(field, new_value)?;
+            changes.push(format!("set {} = {}", field.key(), new_value));
+        }
+    }
+
+    if let Some(types) = args.get("remove_handlers").and_then(|v| v.as_array()) {
+        for handler_type in types {
+            let handler_type = handler_type
+                .as_str()
+                .ok_or_else(|| anyhow!("remove_handlers must be a list of handler types"))?;
+            let removed = doc.remove_handlers(handler_type);
+            if removed == 0 {
+                return Err(anyhow!("No '{}' handler in manifest", handler_type));
+            }
+            changes.push(format!("removed {} '{}' handler(s)", removed, handler_type));
+        }
+    }
+
+    if let Some(entries) = args.get("add_handlers").and_then(|v| v.as_array()) {
+        for entry in entries {
+            let handler = parse_handler(entry)?;
+            doc.add_handler(&handler)?;
+            changes.push(format!("added handler {}", entry));
+        }
+    }
+
+    Ok(changes)
+}
+
+pub fn register_update_manifest_tool(builder: ServerBuilder, registry: Registry) -> ServerBuilder {
+    builder.with_tool(
+        "update-manifest",
+        Some("Updates fields and handlers of an actor's manifest.toml, preserving comments and formatting"),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the actor (required)"
+                },
+                "description": {
+                    "type": ["string", "null"],
+                    "description": "New description, null to remove (optional)"
+                },
+                "long_description": {
+                    "type": ["string", "null"],
+                    "description": "New long description, null to remove (optional)"
+                },
+                "init_state": {
+                    "type": ["string", "null"],
+                    "description": "Path to the initial state file, null to remove (optional)"
+                },
+                "save_chain": {
+                    "type": ["boolean", "null"],
+                    "description": "Whether Theater should persist the actor's event chain (optional)"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "New manifest version (optional)"
+                },
+                "add_handlers": {
+                    "type": "array",
+                    "items": { "type": "object" },
+                    "description": "Handlers to add, as in manifest.toml, e.g. {\"type\": \"http-client\", \"config\": {}} (optional)"
+                },
+                "remove_handlers": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Handler types to remove, e.g. \"http-client\" (optional)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Show the updated manifest without writing it (optional)"
+                }
+            },
+            "required": ["name"]
+        }),
+        move |args| {
+            let name = args
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
+            let dry_run = args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            debug!("Updating manifest for actor '{}'", name);
+
+            let result = registry
+                .find_writable_actor(name)
+                .and_then(|actor| ManifestDocument::load(actor.path.join("manifest.toml")))
+                .and_then(|mut doc| {
+                    let changes = apply_updates(&mut doc, &args)?;
+                    if dry_run {
+                        // Still validate so a dry run reports what a real run would
+                        doc.manifest()?;
+                    } else {
+                        doc.save()?;
+                    }
+                    Ok((changes, doc.to_string()))
+                });
+
+            match result {
+                Ok((changes, manifest)) => {
+                    info!("Updated manifest for actor '{}': {:?}", name, changes);
+                    let mut text = if dry_run {
+                        format!("Dry run: manifest for actor '{}' not written.\n\n", name)
+                    } else {
+                        format!("Manifest for actor '{}' updated.\n\n", name)
+                    };
+
+                    text.push_str("## Changes\n\n");
+                    if changes.is_empty() {
+                        text.push_str("- none\n");
+                    }
+                    for change in &changes {
+                        text.push_str(&format!("- {}\n", change));
+                    }
+                    text.push_str(&format!("\n## manifest.toml\n\n{}", manifest));
+
+                    let content = vec![ToolContent::Text { text }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(false),
+                    })
+                }
+                Err(e) => {
+                    error!("Failed to update manifest for actor '{}': {}", name, e);
+                    let content = vec![ToolContent::Text {
+                        text: format!("Failed to update manifest for actor '{}': {:#}", name, e),
+                    }];
+
+                    Ok(ToolCallResult {
+                        content,
+                        is_error: Some(true),
+                    })
+                }
+            }
+        },
+    )
+}
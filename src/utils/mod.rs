@@ -87,4 +87,25 @@ pub fn calculate_file_hash<P: AsRef<Path>>(path: P) -> Result<String> {
 pub fn get_file_size<P: AsRef<Path>>(path: P) -> Result<u64> {
     let metadata = fs::metadata(path)?;
     Ok(metadata.len())
-}
\ No newline at end of file
+}
+
+/// Collects the actor names a tool call targets from its `name` and
+/// `actors` arguments
+pub fn target_actor_names(args: &serde_json::Value) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Some(name) = args.get("name").and_then(|v| v.as_str()) {
+        names.push(name.to_string());
+    }
+
+    if let Some(actors) = args.get("actors").and_then(|v| v.as_array()) {
+        names.extend(
+            actors
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string()),
+        );
+    }
+
+    names
+}
@@ -0,0 +1,348 @@
+mod common;
+
+use common::{assert_contains, TestEnv};
+use serde_json::json;
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+#[test]
+fn every_tool_is_registered() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    let tools = server.list_tools();
+    for tool in [
+        "create-new-actor",
+        "list-actors-in-registry",
+        "build-actor",
+        "get-actor-info",
+        "get-actor-path",
+        "check-actor-compatibility",
+        "get-manifest",
+        "update-manifest",
+        "list-dependencies",
+        "add-dependency",
+        "remove-dependency",
+        "dependency-report",
+        "align-dependency",
+        "release-actor",
+        "list-versions",
+        "export-actor",
+        "import-actor",
+        "list-remote-actors",
+        "publish-actor",
+        "get-registry-config",
+        "set-registry-config",
+        "init-registry",
+        "get-actor-changes",
+        "commit-actor",
+        "get-actor-history",
+        "tag-actor-release",
+        "clone-actor",
+        "update-actor-from-source",
+        "start-actor",
+        "stop-actor",
+        "list-running-actors",
+        "send-message",
+        "request-actor",
+        "get-actor-events",
+        "smoke-test-actor",
+        "test-actor",
+    ] {
+        assert!(
+            tools.iter().any(|t| t == tool),
+            "{} is not registered",
+            tool
+        );
+    }
+}
+
+#[test]
+fn create_actor_scaffolds_a_crate_and_manifest() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    let text = server.call_ok("create-new-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["Actor 'echo' successfully created"]);
+
+    let path = env.actor_path("echo");
+    for file in ["Cargo.toml", "manifest.toml", "src/lib.rs", "README.md"] {
+        assert!(path.join(file).is_file(), "{} was not created", file);
+    }
+
+    let manifest: toml::Table =
+        toml::from_str(&fs::read_to_string(path.join("manifest.toml")).unwrap()).unwrap();
+    assert_eq!(manifest["name"].as_str(), Some("echo"));
+    assert_eq!(manifest["version"].as_str(), Some("0.1.0"));
+
+    let cargo = fs::read_to_string(path.join("Cargo.toml")).unwrap();
+    let cargo: toml::Table = toml::from_str(&cargo).unwrap();
+    assert_eq!(cargo["package"]["name"].as_str(), Some("echo"));
+    assert!(cargo["dependencies"].is_table());
+}
+
+#[test]
+#[cfg(unix)]
+fn created_actor_has_default_directory_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    // The server inherits our umask, so a directory we create ourselves
+    // shows the mode it should have used
+    let reference = env.path().join("reference");
+    fs::create_dir(&reference).unwrap();
+    let mode = |path: &std::path::Path| fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+
+    assert_eq!(mode(&env.actor_path("echo")), mode(&reference));
+}
+
+#[test]
+fn create_actor_rejects_duplicates_and_bad_names() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    env.create_actor(&mut server, "echo");
+    let text = server.call_err("create-new-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["echo"]);
+
+    server.call_err("create-new-actor", json!({ "name": "../escape" }));
+    assert!(!env.path().join("escape").exists());
+
+    server.call_err("create-new-actor", json!({}));
+    server.call_err(
+        "create-new-actor",
+        json!({ "name": "other", "template": "no-such-template" }),
+    );
+}
+
+#[test]
+fn failed_create_leaves_nothing_behind() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    server.call_err(
+        "create-new-actor",
+        json!({ "name": "team-b/echo", "template": "no-such-template" }),
+    );
+    assert!(!env.actor_path("team-b").exists());
+
+    let text = server.call_err(
+        "create-new-actor",
+        json!({ "name": "echo", "template": "no-such-template" }),
+    );
+    assert_contains(&text, &["Unknown template"]);
+    assert!(!env.actor_path("echo").exists());
+    env.create_actor(&mut server, "echo");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn create_rolls_back_when_copying_wit_files_fails() {
+    // Reading another process's memory at address 0 fails even for root,
+    // which makes the WIT copy fail after the other files are written
+    let unreadable = Path::new("/proc/self/mem");
+    if !unreadable.exists() {
+        return;
+    }
+
+    let env = TestEnv::new();
+    let wit = env.path().join("wit");
+    fs::create_dir_all(&wit).unwrap();
+    std::os::unix::fs::symlink(unreadable, wit.join("broken.wit")).unwrap();
+    let env = env.with_env("THEATER_WIT_PATH", wit.to_str().unwrap());
+    let mut server = env.server();
+
+    server.call_err("create-new-actor", json!({ "name": "team-b/echo" }));
+    let entries: Vec<_> = fs::read_dir(&env.registry)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name != ".registry.config.toml")
+        .collect();
+    assert!(entries.is_empty(), "left behind: {:?}", entries);
+
+    // The retry isn't blocked by a half-built directory
+    fs::remove_file(wit.join("broken.wit")).unwrap();
+    fs::write(wit.join("actor.wit"), "package ntwk:theater;\n").unwrap();
+    env.create_actor(&mut server, "team-b/echo");
+    assert!(env.actor_path("team-b/echo/wit/actor.wit").is_file());
+}
+
+#[test]
+fn create_actor_in_a_namespace() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    env.create_actor(&mut server, "team-a/logger");
+    assert!(env.actor_path("team-a/logger/manifest.toml").is_file());
+
+    let text = server.call_ok("list-actors-in-registry", json!({ "namespace": "team-a" }));
+    assert_contains(&text, &["team-a/logger"]);
+}
+
+#[test]
+fn list_actors() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    let text = server.call_ok("list-actors-in-registry", json!({}));
+    assert!(!text.contains("echo"));
+
+    env.create_actor(&mut server, "echo");
+    env.create_actor(&mut server, "counter");
+    let text = server.call_ok("list-actors-in-registry", json!({}));
+    assert_contains(&text, &["Found 2 actors", "echo", "counter"]);
+}
+
+#[test]
+fn get_actor_path_and_info() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let text = server.call_ok("get-actor-path", json!({ "name": "echo" }));
+    assert_contains(&text, &[env.actor_path("echo").to_str().unwrap()]);
+
+    let text = server.call_ok("get-actor-info", json!({ "name": "echo" }));
+    assert_contains(&text, &["# Actor: echo", "## Dependencies", "serde"]);
+
+    server.call_err("get-actor-path", json!({ "name": "missing" }));
+    server.call_err("get-actor-info", json!({ "name": "missing" }));
+    server.call_err("get-actor-info", json!({}));
+}
+
+#[test]
+fn get_manifest_as_toml_and_json() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let text = server.call_ok("get-manifest", json!({ "name": "echo" }));
+    assert_contains(&text, &["name = \"echo\"", "[[handler]]"]);
+
+    let text = server.call_ok("get-manifest", json!({ "name": "echo", "format": "json" }));
+    let start = text.find('{').expect("no JSON in the output");
+    let manifest: serde_json::Value = serde_json::from_str(&text[start..]).unwrap();
+    assert_eq!(manifest["name"], "echo");
+
+    server.call_err("get-manifest", json!({ "name": "missing" }));
+}
+
+#[test]
+fn update_manifest_edits_in_place() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+    let manifest_path = env.actor_path("echo").join("manifest.toml");
+
+    let before = fs::read_to_string(&manifest_path).unwrap();
+    server.call_ok(
+        "update-manifest",
+        json!({ "name": "echo", "description": "Changed", "dry_run": true }),
+    );
+    assert_eq!(fs::read_to_string(&manifest_path).unwrap(), before);
+
+    // A comment the user added must survive the edit
+    fs::write(&manifest_path, format!("# keep me\n{}", before)).unwrap();
+    server.call_ok(
+        "update-manifest",
+        json!({
+            "name": "echo",
+            "description": "Echoes messages",
+            "add_handlers": [{ "type": "http-client", "config": {} }]
+        }),
+    );
+
+    let after = fs::read_to_string(&manifest_path).unwrap();
+    assert_contains(&after, &["# keep me", "Echoes messages", "http-client"]);
+
+    server.call_ok(
+        "update-manifest",
+        json!({ "name": "echo", "remove_handlers": ["http-client"] }),
+    );
+    assert!(!fs::read_to_string(&manifest_path)
+        .unwrap()
+        .contains("http-client"));
+
+    server.call_err(
+        "update-manifest",
+        json!({ "name": "missing", "description": "x" }),
+    );
+}
+
+#[test]
+fn build_actor_runs_the_configured_theater() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let text = server.call_ok("build-actor", json!({ "name": "echo", "release": true }));
+    assert_contains(
+        &text,
+        &[
+            "Actor 'echo' successfully built",
+            "Compiled echo",
+            "Result: compatible",
+        ],
+    );
+
+    let calls = env.theater.calls();
+    assert_eq!(calls.len(), 1);
+    assert!(calls[0].starts_with("build --release"), "{}", calls[0]);
+    assert!(calls[0].ends_with(env.actor_path("echo").to_str().unwrap()));
+}
+
+#[test]
+fn build_actor_reports_compile_errors() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    env.theater.fail_builds(true);
+    let text = server.call_err("build-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["Failed to build actor 'echo'", "error[E0425]"]);
+
+    let text = server.call_err("build-actor", json!({ "name": "missing" }));
+    assert_contains(&text, &["missing"]);
+    assert_eq!(env.theater.calls().len(), 1);
+}
+
+#[test]
+fn build_actor_reports_a_missing_theater() {
+    let env = TestEnv::new();
+    let missing = env.path().join("no-theater");
+    let env = env.with_env("THEATER_BINARY", missing.to_str().unwrap());
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let text = server.call_err("build-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["Failed to execute theater build command"]);
+}
+
+#[test]
+fn check_compatibility_before_and_after_build() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let text = server.call_err("check-actor-compatibility", json!({ "name": "echo" }));
+    assert_contains(&text, &["not been built"]);
+
+    server.call_ok("build-actor", json!({ "name": "echo" }));
+    let text = server.call_ok("check-actor-compatibility", json!({ "name": "echo" }));
+    assert_contains(&text, &["ntwk:theater/runtime", "Result: compatible"]);
+
+    // Without the runtime handler the component's import is unserved
+    server.call_ok(
+        "update-manifest",
+        json!({ "name": "echo", "remove_handlers": ["runtime"] }),
+    );
+    let output = server.call("check-actor-compatibility", json!({ "name": "echo" }));
+    assert_contains(
+        &output.text,
+        &["Missing Handlers", "runtime", "Result: incompatible"],
+    );
+}
@@ -0,0 +1,477 @@
+mod common;
+
+use actor_registry_mcp::registry::server::RegistryServer;
+use actor_registry_mcp::registry::Registry;
+use common::{assert_contains, repack, TestEnv};
+use serde_json::json;
+use std::fs;
+
+#[test]
+fn release_actor_and_list_versions() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let text = server.call_ok("release-actor", json!({ "name": "echo", "bump": "minor" }));
+    assert_contains(
+        &text,
+        &[
+            "Released actor 'echo' 0.2.0 (previously 0.1.0)",
+            "echo@0.2.0",
+        ],
+    );
+    let release = env.actor_path("echo").join("releases/0.2.0");
+    assert!(release.join("echo.wasm").is_file());
+    let manifest = fs::read_to_string(release.join("manifest.toml")).unwrap();
+    assert!(
+        manifest.contains("component = \"echo.wasm\""),
+        "{}",
+        manifest
+    );
+
+    server.call_ok("release-actor", json!({ "name": "echo" }));
+    let text = server.call_ok("list-versions", json!({ "name": "echo" }));
+    assert_contains(&text, &["Current: 0.2.1", "echo@0.2.0", "echo@0.2.1"]);
+
+    // A released version can be looked up by reference
+    let text = server.call_ok("get-actor-path", json!({ "name": "echo@0.2.0" }));
+    assert_contains(&text, &["0.2.0"]);
+    let text = server.call_ok("get-actor-info", json!({ "name": "echo@0.2.0" }));
+    assert_contains(&text, &["## Build Status\n\nSuccess"]);
+
+    // Releases are frozen
+    let text = server.call_err(
+        "update-manifest",
+        json!({ "name": "echo@0.2.0", "version": "9.9.9" }),
+    );
+    assert_contains(&text, &["release snapshot"]);
+    assert_eq!(
+        fs::read_to_string(release.join("manifest.toml")).unwrap(),
+        manifest
+    );
+}
+
+#[test]
+fn release_actor_errors() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    server.call_err(
+        "release-actor",
+        json!({ "name": "echo", "bump": "sideways" }),
+    );
+    server.call_err("release-actor", json!({ "name": "missing" }));
+
+    // Exact versions can't go backwards or repeat the current one
+    for version in ["0.0.9", "0.1.0"] {
+        let text = server.call_err("release-actor", json!({ "name": "echo", "bump": version }));
+        assert_contains(&text, &["not newer than the current version 0.1.0"]);
+    }
+
+    // A failed build must not bump the version
+    env.theater.fail_builds(true);
+    let text = server.call_err("release-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["error[E0425]"]);
+    let text = server.call_ok("list-versions", json!({ "name": "echo" }));
+    assert_contains(&text, &["Current: 0.1.0"]);
+
+    server.call_err("list-versions", json!({ "name": "missing" }));
+}
+
+#[test]
+fn release_references_must_be_versions() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+    env.create_actor(&mut server, "other");
+
+    let registry = Registry::new(&env.registry).unwrap();
+    assert!(registry.find_actor("echo@0.1.0").is_err());
+    for reference in ["echo@../other", "echo@../../registry/other", "echo@/tmp"] {
+        let err = registry.find_actor(reference).unwrap_err().to_string();
+        assert!(err.contains("Invalid version"), "{}: {}", reference, err);
+    }
+
+    let text = server.call_err("get-actor-path", json!({ "name": "echo@../other" }));
+    assert_contains(&text, &["Invalid version"]);
+}
+
+#[test]
+fn export_and_import_round_trip() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_built_actor(&mut server, "echo");
+
+    let bundle = env.path().join("echo.tar.zst");
+    let text = server.call_ok(
+        "export-actor",
+        json!({
+            "name": "echo",
+            "output": bundle.to_str().unwrap(),
+            "include_component": true
+        }),
+    );
+    assert_contains(&text, &["Actor 'echo' exported", "SHA-256"]);
+    assert!(bundle.is_file());
+
+    let text = server.call_err(
+        "import-actor",
+        json!({ "bundle": bundle.to_str().unwrap() }),
+    );
+    assert_contains(&text, &["already exists"]);
+
+    let text = server.call_ok(
+        "import-actor",
+        json!({ "bundle": bundle.to_str().unwrap(), "on_collision": "rename" }),
+    );
+    assert_contains(&text, &["Actor 'echo-2' imported", "Checksums: verified"]);
+
+    server.call_ok(
+        "import-actor",
+        json!({ "bundle": bundle.to_str().unwrap(), "name": "copy" }),
+    );
+    let imported = env.actor_path("copy");
+    assert_eq!(
+        fs::read_to_string(imported.join("src/lib.rs")).unwrap(),
+        fs::read_to_string(env.actor_path("echo").join("src/lib.rs")).unwrap()
+    );
+
+    // The bundled component is usable without rebuilding
+    let text = server.call_ok("check-actor-compatibility", json!({ "name": "copy" }));
+    assert_contains(&text, &["Result: compatible"]);
+}
+
+#[test]
+fn import_rejects_bad_bundles() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    let missing = env.path().join("missing.tar.zst");
+    server.call_err(
+        "import-actor",
+        json!({ "bundle": missing.to_str().unwrap() }),
+    );
+
+    let garbage = env.path().join("garbage.tar.zst");
+    fs::write(&garbage, b"not a bundle").unwrap();
+    server.call_err(
+        "import-actor",
+        json!({ "bundle": garbage.to_str().unwrap() }),
+    );
+
+    server.call_err("import-actor", json!({}));
+    server.call_err("export-actor", json!({ "name": "missing" }));
+
+    // Bundles without checksums are only imported when asked to
+    env.create_actor(&mut server, "plain");
+    let unverified = env.path().join("plain.tar.zst");
+    server.call_ok(
+        "export-actor",
+        json!({ "name": "plain", "output": unverified.to_str().unwrap(), "checksums": false }),
+    );
+    let text = server.call_err(
+        "import-actor",
+        json!({ "bundle": unverified.to_str().unwrap(), "name": "copy" }),
+    );
+    assert_contains(&text, &["CHECKSUMS.sha256"]);
+    assert!(!env.actor_path("copy").exists());
+    let text = server.call_ok(
+        "import-actor",
+        json!({
+            "bundle": unverified.to_str().unwrap(),
+            "name": "copy",
+            "allow_unverified": true
+        }),
+    );
+    assert_contains(&text, &["Checksums: none"]);
+
+    // There is no component to include before the first build
+    server.call_ok("create-new-actor", json!({ "name": "echo" }));
+    server.call_err(
+        "export-actor",
+        json!({ "name": "echo", "include_component": true }),
+    );
+}
+
+#[test]
+fn import_rejects_unsafe_names_and_modes() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    // Without checksums, so the edited metadata reaches the later checks
+    let bundle = env.path().join("echo.tar.zst");
+    server.call_ok(
+        "export-actor",
+        json!({ "name": "echo", "output": bundle.to_str().unwrap(), "checksums": false }),
+    );
+
+    // A bundled name that climbs out of the registry is refused, even when
+    // overwriting is allowed
+    let outside = env.path().join("outside");
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(outside.join("keep.txt"), "keep").unwrap();
+    let escaping = env.path().join("escaping.tar.zst");
+    fs::copy(&bundle, &escaping).unwrap();
+    repack(&escaping, |path, data, _| {
+        if path == "bundle.toml" {
+            *data = String::from_utf8(data.clone())
+                .unwrap()
+                .replace("name = \"echo\"", "name = \"../outside\"")
+                .into_bytes();
+        }
+    });
+    let text = server.call_err(
+        "import-actor",
+        json!({
+            "bundle": escaping.to_str().unwrap(),
+            "on_collision": "overwrite",
+            "allow_unverified": true
+        }),
+    );
+    assert_contains(&text, &["invalid actor name"]);
+    assert!(outside.join("keep.txt").is_file());
+
+    // Overwriting replaces actors, never a whole namespace
+    env.create_actor(&mut server, "team/echo");
+    let text = server.call_err(
+        "import-actor",
+        json!({
+            "bundle": bundle.to_str().unwrap(),
+            "name": "team",
+            "on_collision": "overwrite",
+            "allow_unverified": true
+        }),
+    );
+    assert_contains(&text, &["not an actor"]);
+    assert!(env.actor_path("team/echo").join("manifest.toml").is_file());
+
+    // Modes are reduced to 0755/0644 like the exporter writes them
+    repack(&bundle, |path, _, mode| {
+        *mode = if path == "README.md" { 0o4777 } else { 0o666 };
+    });
+    server.call_ok(
+        "import-actor",
+        json!({ "bundle": bundle.to_str().unwrap(), "name": "copy", "allow_unverified": true }),
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |file: &str| {
+            let path = env.actor_path("copy").join(file);
+            fs::metadata(path).unwrap().permissions().mode() & 0o7777
+        };
+        assert_eq!(mode("README.md"), 0o755);
+        assert_eq!(mode("src/lib.rs"), 0o644);
+    }
+}
+
+#[test]
+fn import_rejects_unsafe_component_paths() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_built_actor(&mut server, "echo");
+
+    let bundle = env.path().join("echo.tar.zst");
+    server.call_ok(
+        "export-actor",
+        json!({
+            "name": "echo",
+            "output": bundle.to_str().unwrap(),
+            "include_component": true,
+            "checksums": false
+        }),
+    );
+
+    for component in [
+        "../../outside.wasm",
+        "/etc/passwd",
+        "component/missing.wasm",
+    ] {
+        let tampered = env.path().join("tampered.tar.zst");
+        fs::copy(&bundle, &tampered).unwrap();
+        repack(&tampered, |path, data, _| {
+            if path == "bundle.toml" {
+                *data = String::from_utf8(data.clone())
+                    .unwrap()
+                    .replace(
+                        "component = \"component/echo.wasm\"",
+                        &format!("component = {:?}", component),
+                    )
+                    .into_bytes();
+            }
+        });
+        let text = server.call_err(
+            "import-actor",
+            json!({
+                "bundle": tampered.to_str().unwrap(),
+                "name": "copy",
+                "allow_unverified": true
+            }),
+        );
+        assert_contains(&text, &["not a file in the bundle"]);
+        assert!(!env.actor_path("copy").exists());
+    }
+}
+
+#[test]
+fn import_rejects_tampered_metadata() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let bundle = env.path().join("echo.tar.zst");
+    server.call_ok(
+        "export-actor",
+        json!({ "name": "echo", "output": bundle.to_str().unwrap() }),
+    );
+    repack(&bundle, |path, data, _| {
+        if path == "bundle.toml" {
+            *data = String::from_utf8(data.clone())
+                .unwrap()
+                .replace("name = \"echo\"", "name = \"renamed\"")
+                .into_bytes();
+        }
+    });
+
+    let text = server.call_err(
+        "import-actor",
+        json!({ "bundle": bundle.to_str().unwrap() }),
+    );
+    assert_contains(&text, &["Checksum mismatch for bundle.toml"]);
+    assert!(!env.actor_path("renamed").exists());
+}
+
+#[test]
+fn publish_to_and_list_a_remote() {
+    let env = TestEnv::new();
+    let remote_dir = env.path().join("remote");
+    fs::create_dir_all(&remote_dir).unwrap();
+    let remote = RegistryServer::bind(Registry::new(&remote_dir).unwrap(), "127.0.0.1:0")
+        .unwrap()
+        .spawn()
+        .unwrap();
+
+    let mut server = env.server();
+    let text = server.call_ok("list-remote-actors", json!({}));
+    assert_contains(&text, &["No remotes are configured"]);
+    server.call_err("publish-actor", json!({ "name": "echo" }));
+
+    server.call_ok(
+        "set-registry-config",
+        json!({ "key": "remotes", "value": [{ "name": "team", "url": remote.url() }] }),
+    );
+    env.create_built_actor(&mut server, "echo");
+
+    server.call_ok("publish-actor", json!({ "name": "echo" }));
+    assert!(remote_dir.join("echo/manifest.toml").is_file());
+
+    let text = server.call_err("publish-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["echo"]);
+    server.call_ok(
+        "publish-actor",
+        json!({ "name": "echo", "overwrite": true }),
+    );
+
+    let text = server.call_ok("list-remote-actors", json!({ "remote": "team" }));
+    assert_contains(&text, &["Remote 'team'", "1 actors", "echo"]);
+
+    server.call_err("list-remote-actors", json!({ "remote": "nope" }));
+    server.call_err("publish-actor", json!({ "name": "echo", "remote": "nope" }));
+
+    remote.shutdown();
+}
+
+#[test]
+fn remote_rejects_encoded_traversal_in_versions() {
+    let env = TestEnv::new();
+    let remote_dir = env.path().join("remote");
+    fs::create_dir_all(&remote_dir).unwrap();
+    let remote = RegistryServer::bind(Registry::new(&remote_dir).unwrap(), "127.0.0.1:0")
+        .unwrap()
+        .spawn()
+        .unwrap();
+
+    let mut server = env.server();
+    server.call_ok(
+        "set-registry-config",
+        json!({ "key": "remotes", "value": [{ "name": "team", "url": remote.url() }] }),
+    );
+    env.create_built_actor(&mut server, "echo");
+    server.call_ok("publish-actor", json!({ "name": "echo" }));
+
+    // A directory outside the actor that looks like a release
+    let secret = env.path().join("secret");
+    fs::create_dir_all(&secret).unwrap();
+    fs::copy(
+        remote_dir.join("echo/manifest.toml"),
+        secret.join("manifest.toml"),
+    )
+    .unwrap();
+
+    let actors = format!("{}/api/v1/actors", remote.url());
+    assert!(ureq::get(&format!("{}/echo", actors)).call().is_ok());
+    for path in [
+        "echo@..%2F..%2F..%2Fsecret",
+        "echo@..%2f..%2f..%2fsecret/bundle",
+        "echo@0.1.0%2F..%2F..%2F..%2F..%2Fsecret/bundle",
+    ] {
+        match ureq::get(&format!("{}/{}", actors, path)).call() {
+            Err(ureq::Error::Status(code, _)) => assert_eq!(code, 400, "{}", path),
+            other => panic!("{} was not rejected: {:?}", path, other.map(|r| r.status())),
+        }
+    }
+
+    remote.shutdown();
+}
+
+#[test]
+fn only_read_tools_resolve_remote_actors() {
+    let publisher = TestEnv::new();
+    let remote_dir = publisher.path().join("remote");
+    fs::create_dir_all(&remote_dir).unwrap();
+    let remote = RegistryServer::bind(Registry::new(&remote_dir).unwrap(), "127.0.0.1:0")
+        .unwrap()
+        .spawn()
+        .unwrap();
+    let remotes = json!({ "key": "remotes", "value": [{ "name": "team", "url": remote.url() }] });
+
+    let mut server = publisher.server();
+    server.call_ok("set-registry-config", remotes.clone());
+    publisher.create_built_actor(&mut server, "echo");
+    server.call_ok("publish-actor", json!({ "name": "echo" }));
+
+    let env = TestEnv::new();
+    let mut server = env.server();
+    server.call_ok("set-registry-config", remotes);
+
+    let text = server.call_ok("get-actor-path", json!({ "name": "echo" }));
+    assert_contains(&text, &[".remote-cache"]);
+    let text = server.call_ok("get-actor-info", json!({ "name": "echo" }));
+    assert_contains(&text, &["# Actor: echo"]);
+
+    // Tools that change or inspect local actors never go to the remotes
+    let text = server.call_err("get-manifest", json!({ "name": "echo" }));
+    assert_contains(&text, &["not found"]);
+    server.call_err("list-dependencies", json!({ "name": "echo" }));
+
+    remote.shutdown();
+}
+
+#[test]
+fn unreachable_remote() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    // Nothing listens on the discard port
+    server.call_ok(
+        "set-registry-config",
+        json!({ "key": "remotes", "value": [{ "name": "gone", "url": "http://127.0.0.1:9" }] }),
+    );
+    env.create_actor(&mut server, "echo");
+
+    server.call_err("publish-actor", json!({ "name": "echo" }));
+    let output = server.call("list-remote-actors", json!({}));
+    assert_contains(&output.text, &["gone"]);
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use actor_registry_mcp::registry::bundle::{Collision, ExportOptions, ImportOptions};
+use common::{assert_contains, repack, TestRegistry};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+const FULL: ExportOptions = ExportOptions {
+    include_component: true,
+    checksums: true,
+};
+
+const UNVERIFIED: ExportOptions = ExportOptions {
+    include_component: false,
+    checksums: false,
+};
+
+fn export(test: &TestRegistry, name: &str, options: &ExportOptions) -> PathBuf {
+    let bundle = test.path().join(format!("{}.tar.zst", name));
+    test.registry
+        .export_actor(name, Some(&bundle), options)
+        .unwrap();
+    bundle
+}
+
+fn import_as(name: &str, allow_unverified: bool) -> ImportOptions {
+    ImportOptions {
+        name: Some(name.to_string()),
+        allow_unverified,
+        ..Default::default()
+    }
+}
+
+/// Replaces `from` with `to` in the bundle's metadata file
+fn edit_metadata(bundle: &Path, from: &str, to: &str) {
+    repack(bundle, |path, data, _| {
+        if path == "bundle.toml" {
+            *data = String::from_utf8(data.clone())
+                .unwrap()
+                .replace(from, to)
+                .into_bytes();
+        }
+    });
+}
+
+#[test]
+fn export_and_import_round_trip() {
+    let test = TestRegistry::new();
+    test.create_built_actor("echo");
+
+    let bundle = export(&test, "echo", &FULL);
+    let first = fs::read(&bundle).unwrap();
+
+    // Bundles are reproducible
+    let info = test
+        .registry
+        .export_actor("echo", Some(&bundle), &FULL)
+        .unwrap();
+    assert_eq!(fs::read(&bundle).unwrap(), first);
+    assert_eq!(info.size, first.len() as u64);
+
+    let other = TestRegistry::new();
+    let (actor, info) = other
+        .registry
+        .import_actor(&bundle, &ImportOptions::default())
+        .unwrap();
+    assert_eq!(actor.name, "echo");
+    assert!(info.verified);
+    assert_eq!(
+        fs::read_to_string(other.actor_path("echo").join("src/lib.rs")).unwrap(),
+        fs::read_to_string(test.actor_path("echo").join("src/lib.rs")).unwrap()
+    );
+
+    // Build output is left behind and the manifest points at the bundled
+    // component instead
+    let component = other.actor_path("echo").join("component/echo.wasm");
+    assert_eq!(info.component.as_deref(), Some(component.as_path()));
+    assert!(!other.actor_path("echo").join("target").exists());
+    let report = other.registry.check_compatibility("echo").unwrap();
+    assert!(report.is_compatible(), "{}", report.to_text());
+}
+
+#[test]
+fn name_collisions_follow_the_strategy() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    let bundle = export(&test, "echo", &UNVERIFIED);
+    let options = |on_collision| ImportOptions {
+        on_collision,
+        allow_unverified: true,
+        ..Default::default()
+    };
+
+    let err = test
+        .registry
+        .import_actor(&bundle, &options(Collision::Error))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["already exists"]);
+
+    let (actor, _) = test
+        .registry
+        .import_actor(&bundle, &options(Collision::Rename))
+        .unwrap();
+    assert_eq!(actor.name, "echo-2");
+    assert_eq!(actor.manifest.unwrap().name, "echo-2");
+
+    fs::write(test.actor_path("echo").join("extra.txt"), "local").unwrap();
+    test.registry
+        .import_actor(&bundle, &options(Collision::Overwrite))
+        .unwrap();
+    assert!(!test.actor_path("echo").join("extra.txt").exists());
+
+    // Only actors are ever replaced
+    fs::create_dir_all(test.actor_path("notes")).unwrap();
+    fs::write(test.actor_path("notes/keep.txt"), "keep").unwrap();
+    let err = test
+        .registry
+        .import_actor(
+            &bundle,
+            &ImportOptions {
+                name: Some("notes".to_string()),
+                ..options(Collision::Overwrite)
+            },
+        )
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["not an actor"]);
+    assert!(test.actor_path("notes/keep.txt").is_file());
+
+    assert!("sideways".parse::<Collision>().is_err());
+}
+
+#[test]
+fn checksums_cover_every_file_including_the_metadata() {
+    let test = TestRegistry::new();
+    test.create_built_actor("echo");
+    let bundle = export(&test, "echo", &FULL);
+
+    let tampered = test.path().join("tampered.tar.zst");
+    fs::copy(&bundle, &tampered).unwrap();
+    edit_metadata(&tampered, "name = \"echo\"", "name = \"renamed\"");
+    let err = test
+        .registry
+        .import_actor(&tampered, &import_as("copy", false))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["Checksum mismatch for bundle.toml"]);
+
+    fs::copy(&bundle, &tampered).unwrap();
+    repack(&tampered, |path, data, _| {
+        if path == "src/lib.rs" {
+            data.extend_from_slice(b"\n// injected\n");
+        }
+    });
+    let err = test
+        .registry
+        .import_actor(&tampered, &import_as("copy", false))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["Checksum mismatch for src/lib.rs"]);
+    assert!(!test.actor_path("copy").exists());
+
+    // Bundles without a listing are only taken on trust when asked to
+    let unverified = export(&test, "echo", &UNVERIFIED);
+    let err = test
+        .registry
+        .import_actor(&unverified, &import_as("copy", false))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["CHECKSUMS.sha256"]);
+    let (_, info) = test
+        .registry
+        .import_actor(&unverified, &import_as("copy", true))
+        .unwrap();
+    assert!(!info.verified);
+}
+
+#[test]
+fn unsafe_names_component_paths_and_modes_are_refused() {
+    let test = TestRegistry::new();
+    test.create_built_actor("echo");
+    let bundle = test.path().join("echo.tar.zst");
+    let options = ExportOptions {
+        include_component: true,
+        checksums: false,
+    };
+    test.registry
+        .export_actor("echo", Some(&bundle), &options)
+        .unwrap();
+
+    let tampered = test.path().join("tampered.tar.zst");
+    fs::copy(&bundle, &tampered).unwrap();
+    edit_metadata(&tampered, "name = \"echo\"", "name = \"../outside\"");
+    let err = test
+        .registry
+        .import_actor(
+            &tampered,
+            &ImportOptions {
+                on_collision: Collision::Overwrite,
+                allow_unverified: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert_contains(&format!("{:#}", err), &["invalid actor name"]);
+
+    for component in [
+        "../../outside.wasm",
+        "/etc/passwd",
+        "component/missing.wasm",
+    ] {
+        fs::copy(&bundle, &tampered).unwrap();
+        edit_metadata(
+            &tampered,
+            "component = \"component/echo.wasm\"",
+            &format!("component = {:?}", component),
+        );
+        let err = test
+            .registry
+            .import_actor(&tampered, &import_as("copy", true))
+            .unwrap_err();
+        assert_contains(&err.to_string(), &["not a file in the bundle"]);
+        assert!(!test.actor_path("copy").exists());
+    }
+
+    // Modes are reduced to 0755/0644 like the exporter writes them
+    fs::copy(&bundle, &tampered).unwrap();
+    repack(&tampered, |path, _, mode| {
+        *mode = if path == "Cargo.toml" { 0o4777 } else { 0o666 };
+    });
+    test.registry
+        .import_actor(&tampered, &import_as("copy", true))
+        .unwrap();
+    let mode = |file: &str| {
+        let path = test.actor_path("copy").join(file);
+        fs::metadata(path).unwrap().permissions().mode() & 0o7777
+    };
+    assert_eq!(mode("Cargo.toml"), 0o755);
+    assert_eq!(mode("src/lib.rs"), 0o644);
+}
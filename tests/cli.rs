@@ -0,0 +1,122 @@
+mod common;
+
+use common::{assert_contains, cli, run, run_err, run_ok, TestEnv};
+use std::fs;
+
+#[test]
+fn commands_run_the_tool_handlers() {
+    let env = TestEnv::new();
+
+    let text = run_ok(cli(&env, &["create", "echo"]));
+    assert_contains(&text, &["Actor 'echo' successfully created"]);
+
+    let text = run_ok(cli(&env, &["list"]));
+    assert_contains(&text, &["Found 1 actors", "echo"]);
+
+    let text = run_ok(cli(&env, &["path", "echo"]));
+    assert_contains(&text, &[env.actor_path("echo").to_str().unwrap()]);
+
+    run_ok(cli(&env, &["build", "echo", "--release"]));
+    assert!(env.theater.calls()[0].starts_with("build --release"));
+
+    // Failures go to stderr with a non-zero exit code
+    let (ok, stdout, stderr) = run(cli(&env, &["info", "missing"]));
+    assert!(!ok);
+    assert!(stdout.is_empty(), "{}", stdout);
+    assert_contains(&stderr, &["missing"]);
+}
+
+#[test]
+fn registry_flag_beats_env_beats_user_config() {
+    let env = TestEnv::new();
+    let registries = ["from-flag", "from-env", "from-user-config"].map(|name| {
+        let root = env.path().join(name);
+        fs::create_dir_all(&root).unwrap();
+        run_ok(cli(
+            &env,
+            &["--registry", root.to_str().unwrap(), "create", name],
+        ));
+        root
+    });
+
+    let user_config = env.path().join("config/actor-registry/config.toml");
+    fs::create_dir_all(user_config.parent().unwrap()).unwrap();
+    fs::write(
+        &user_config,
+        format!("registry = {:?}\n", registries[2].to_str().unwrap()),
+    )
+    .unwrap();
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env_remove("THEATER_ACTORS_PATH");
+    let text = run_ok(cmd);
+    assert_contains(&text, &["from-user-config"]);
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env("THEATER_ACTORS_PATH", &registries[1]);
+    let text = run_ok(cmd);
+    assert_contains(&text, &["from-env"]);
+    assert!(!text.contains("from-user-config"), "{}", text);
+
+    let mut cmd = cli(
+        &env,
+        &["--registry", registries[0].to_str().unwrap(), "list"],
+    );
+    cmd.env("THEATER_ACTORS_PATH", &registries[1]);
+    let text = run_ok(cmd);
+    assert_contains(&text, &["from-flag"]);
+    assert!(!text.contains("from-env"), "{}", text);
+}
+
+#[test]
+fn config_flags_beat_env_beats_registry_file() {
+    let env = TestEnv::new();
+    run_ok(cli(&env, &["create", "echo"]));
+
+    let missing = env.path().join("no-theater");
+    fs::write(
+        env.registry.join(".registry.config.toml"),
+        format!("theater_binary = {:?}\n", missing.to_str().unwrap()),
+    )
+    .unwrap();
+
+    // THEATER_BINARY points at the fake theater and wins over the file
+    run_ok(cli(&env, &["build", "echo"]));
+
+    let mut cmd = cli(&env, &["build", "echo"]);
+    cmd.env_remove("THEATER_BINARY");
+    let text = run_err(cmd);
+    assert_contains(&text, &["Failed to execute theater build command"]);
+
+    let text = run_err(cli(
+        &env,
+        &[
+            "--theater-binary",
+            missing.to_str().unwrap(),
+            "build",
+            "echo",
+        ],
+    ));
+    assert_contains(&text, &["Failed to execute theater build command"]);
+}
+
+#[test]
+fn default_registry_must_be_initialized() {
+    let env = TestEnv::new();
+    let default = env.path().join("data/actor-registry");
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env_remove("THEATER_ACTORS_PATH");
+    let text = run_err(cmd);
+    assert_contains(&text, &[default.to_str().unwrap(), "run `init`"]);
+
+    let mut cmd = cli(&env, &["init", "--no-git"]);
+    cmd.env_remove("THEATER_ACTORS_PATH");
+    run_ok(cmd);
+    assert!(default.join(".registry.config.toml").is_file());
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env_remove("THEATER_ACTORS_PATH");
+    let text = run_ok(cmd);
+    assert_contains(&text, &["Found 0 actors"]);
+}
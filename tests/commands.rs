@@ -0,0 +1,105 @@
+mod common;
+
+use actor_registry_mcp::cli::{self, Cli, Command};
+use clap::Parser;
+use common::{assert_contains, install_fake_theater, TestRegistry};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use std::path::PathBuf;
+
+fn text(result: &ToolCallResult) -> String {
+    result
+        .content
+        .iter()
+        .map(|content| match content {
+            ToolContent::Text { text } => text.clone(),
+            _ => panic!("expected only text content"),
+        })
+        .collect()
+}
+
+/// Parses `args` as a command against the test registry and runs it
+fn run(test: &TestRegistry, args: &[&str]) -> ToolCallResult {
+    let registry = test.registry.path().to_str().unwrap();
+    let cli = Cli::try_parse_from(
+        ["actor-registry", "--registry", registry]
+            .iter()
+            .chain(args)
+            .copied(),
+    )
+    .unwrap();
+    assert_eq!(cli.registry_roots(None), [test.registry.path()]);
+    cli.command.unwrap().run(&test.registry).unwrap()
+}
+
+#[test]
+fn commands_share_the_tool_handlers() {
+    let test = TestRegistry::new();
+
+    let created = run(&test, &["create", "team-a/echo"]);
+    assert_eq!(created.is_error, Some(false));
+    assert_contains(
+        &text(&created),
+        &["Actor 'team-a/echo' successfully created"],
+    );
+
+    let path = run(&test, &["path", "team-a/echo"]);
+    assert_eq!(PathBuf::from(text(&path)), test.actor_path("team-a/echo"));
+
+    let listed = text(&run(&test, &["list", "--namespace", "team-a"]));
+    assert_contains(
+        &listed,
+        &["Found 1 actors in namespace 'team-a'", "- team-a/echo"],
+    );
+
+    let info = text(&run(&test, &["info", "team-a/echo"]));
+    assert_contains(&info, &["team-a/echo"]);
+
+    install_fake_theater();
+    let built = run(&test, &["build", "team-a/echo", "--release"]);
+    assert_ne!(built.is_error, Some(true), "{}", text(&built));
+    assert!(test
+        .actor_path("team-a/echo/target/wasm32-unknown-unknown/release/echo.wasm")
+        .is_file());
+}
+
+#[test]
+fn failures_are_tool_errors() {
+    let test = TestRegistry::new();
+
+    let missing = run(&test, &["path", "missing"]);
+    assert_eq!(missing.is_error, Some(true));
+    assert_contains(&text(&missing), &["Failed to get actor path", "not found"]);
+
+    test.create_actor("echo");
+    let duplicate = run(&test, &["create", "echo"]);
+    assert_eq!(duplicate.is_error, Some(true));
+    assert_contains(&text(&duplicate), &["already exists"]);
+
+    // `serve` is handled by the binary, not dispatched to a tool
+    let serve = Cli::try_parse_from(["actor-registry", "serve", "--http"]).unwrap();
+    assert!(matches!(
+        &serve.command,
+        Some(Command::Serve { http: Some(None) })
+    ));
+    assert!(serve.command.unwrap().run(&test.registry).is_err());
+    assert!(cli::print_result(&ToolCallResult {
+        content: Vec::new(),
+        is_error: None,
+    }));
+}
+
+#[test]
+fn arguments_are_checked_by_the_parser() {
+    assert!(Cli::try_parse_from(["actor-registry", "create"]).is_err());
+    assert!(Cli::try_parse_from(["actor-registry", "launch", "echo"]).is_err());
+
+    let cli = Cli::try_parse_from(["actor-registry"]).unwrap();
+    assert!(cli.command.is_none());
+
+    // Several roots are separated like PATH
+    let cli = Cli::try_parse_from(["actor-registry", "--registry", "/a:/b:", "list"]).unwrap();
+    assert_eq!(
+        cli.registry_roots(Some("/elsewhere".to_string())),
+        [PathBuf::from("/a"), PathBuf::from("/b")]
+    );
+}
@@ -0,0 +1,300 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use tracing::{debug, error};
+
+use actor_registry_mcp::theater_client::{
+    read_frame, write_frame, ActorListEntry, ChainEvent, ManagementCommand, ManagementResponse,
+};
+
+/// An unhashed event stamped with the current time, as the fake server
+/// records them
+pub fn chain_event(event_type: &str, data: Vec<u8>, description: Option<String>) -> ChainEvent {
+    ChainEvent {
+        hash: Vec::new(),
+        parent_hash: None,
+        event_type: event_type.to_string(),
+        data,
+        timestamp: Utc::now().timestamp_millis() as u64,
+        description,
+    }
+}
+
+/// An actor "running" in the fake server
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeActor {
+    pub name: String,
+    pub manifest: String,
+    pub initial_state: Option<Vec<u8>>,
+    /// Payloads of the messages and requests it received, in order
+    pub received: Vec<Vec<u8>>,
+    /// Its event chain, starting with `actor-started`
+    pub events: Vec<ChainEvent>,
+}
+
+/// Computes a fake actor's response to a request
+pub type Responder = Box<dyn Fn(&FakeActor, &[u8]) -> Vec<u8> + Send>;
+
+#[derive(Default)]
+struct FakeState {
+    actors: BTreeMap<String, FakeActor>,
+    commands: Vec<ManagementCommand>,
+    next_id: u64,
+    responder: Option<Responder>,
+    log_messages: bool,
+}
+
+/// Stand-in for a Theater server that speaks the management protocol
+/// without running anything, for the runtime tool tests
+///
+/// Requests are answered with their own payload unless `respond_with` sets
+/// a responder.
+pub struct FakeTheaterServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FakeTheaterServer {
+    /// Listens on a free port on localhost
+    pub fn start() -> Result<Self> {
+        Self::bind("127.0.0.1:0")
+    }
+
+    pub fn bind(addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(FakeState::default()));
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let state = state.clone();
+            let stopping = stopping.clone();
+            std::thread::Builder::new()
+                .name("fake-theater".to_string())
+                .spawn(move || {
+                    for stream in listener.incoming() {
+                        if stopping.load(Ordering::SeqCst) {
+                            break;
+                        }
+                        match stream {
+                            Ok(stream) => {
+                                let state = state.clone();
+                                std::thread::spawn(move || serve(stream, &state));
+                            }
+                            Err(e) => error!("Fake Theater failed to accept: {}", e),
+                        }
+                    }
+                })?
+        };
+
+        debug!("Fake Theater listening on {}", addr);
+        Ok(Self {
+            addr,
+            state,
+            stopping,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Actors currently running, by id
+    pub fn actors(&self) -> BTreeMap<String, FakeActor> {
+        self.state.lock().unwrap().actors.clone()
+    }
+
+    /// Appends an event to a running actor's chain
+    pub fn push_event(&self, id: &str, event: ChainEvent) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let actor = state
+            .actors
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("Actor {} not found", id))?;
+        actor.events.push(event);
+        Ok(())
+    }
+
+    /// Answers requests with `responder` instead of echoing the payload
+    pub fn respond_with<F>(&self, responder: F)
+    where
+        F: Fn(&FakeActor, &[u8]) -> Vec<u8> + Send + 'static,
+    {
+        self.state.lock().unwrap().responder = Some(Box::new(responder));
+    }
+
+    /// Has actors log the text of every message sent to them, like an actor
+    /// that calls `log` in `handle-send`
+    pub fn log_messages(&self, log: bool) {
+        self.state.lock().unwrap().log_messages = log;
+    }
+
+    /// Every command received, in order
+    pub fn commands(&self) -> Vec<ManagementCommand> {
+        self.state.lock().unwrap().commands.clone()
+    }
+
+    /// Stops accepting connections and waits for the server thread
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.stopping.store(true, Ordering::SeqCst);
+            // Wake the accept loop so it sees the flag
+            let _ = TcpStream::connect(self.addr);
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for FakeTheaterServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn serve(mut stream: TcpStream, state: &Mutex<FakeState>) {
+    loop {
+        let frame = match read_frame(&mut stream) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return,
+            Err(e) => {
+                debug!("Fake Theater connection failed: {}", e);
+                return;
+            }
+        };
+
+        let response = match serde_json::from_slice::<ManagementCommand>(&frame) {
+            Ok(command) => handle(state, command),
+            Err(e) => error_response(format!("Invalid command: {}", e)),
+        };
+
+        if let Err(e) = write_frame(&mut stream, &response) {
+            debug!("Fake Theater failed to respond: {}", e);
+            return;
+        }
+    }
+}
+
+fn handle(state: &Mutex<FakeState>, command: ManagementCommand) -> ManagementResponse {
+    let mut state = state.lock().unwrap();
+    state.commands.push(command.clone());
+
+    match command {
+        ManagementCommand::StartActor {
+            manifest,
+            initial_state,
+            ..
+        } => match manifest_name(Path::new(&manifest)) {
+            Ok(name) => {
+                state.next_id += 1;
+                let id = format!("fake-{:08}", state.next_id);
+                let started = chain_event(
+                    "actor-started",
+                    initial_state.clone().unwrap_or_default(),
+                    Some(format!("Started from {}", manifest)),
+                );
+                state.actors.insert(
+                    id.clone(),
+                    FakeActor {
+                        name,
+                        manifest,
+                        initial_state,
+                        received: Vec::new(),
+                        events: vec![started],
+                    },
+                );
+                ManagementResponse::ActorStarted { id }
+            }
+            Err(e) => error_response(e.to_string()),
+        },
+        ManagementCommand::StopActor { id } => match state.actors.remove(&id) {
+            Some(_) => ManagementResponse::ActorStopped { id },
+            None => error_response(format!("Actor {} not found", id)),
+        },
+        ManagementCommand::SendActorMessage { id, data } => {
+            let log_messages = state.log_messages;
+            match state.actors.get_mut(&id) {
+                Some(actor) => {
+                    actor
+                        .events
+                        .push(chain_event("message-received", data.clone(), None));
+                    if log_messages {
+                        let line = String::from_utf8_lossy(&data).to_string();
+                        actor
+                            .events
+                            .push(chain_event("log", data.clone(), Some(line)));
+                    }
+                    actor.received.push(data);
+                    ManagementResponse::SentMessage { id }
+                }
+                None => error_response(format!("Actor {} not found", id)),
+            }
+        }
+        ManagementCommand::RequestActorMessage { id, data } => {
+            let state = &mut *state;
+            match state.actors.get_mut(&id) {
+                Some(actor) => {
+                    let message = match &state.responder {
+                        Some(responder) => responder(actor, &data),
+                        None => data.clone(),
+                    };
+                    actor
+                        .events
+                        .push(chain_event("request-received", data.clone(), None));
+                    actor
+                        .events
+                        .push(chain_event("response-sent", message.clone(), None));
+                    actor.received.push(data);
+                    ManagementResponse::RequestedMessage { id, message }
+                }
+                None => error_response(format!("Actor {} not found", id)),
+            }
+        }
+        ManagementCommand::GetActorEvents { id } => match state.actors.get(&id) {
+            Some(actor) => ManagementResponse::ActorEvents {
+                id,
+                events: actor.events.clone(),
+            },
+            None => error_response(format!("Actor {} not found", id)),
+        },
+        ManagementCommand::ListActors => ManagementResponse::ActorList {
+            actors: state
+                .actors
+                .iter()
+                .map(|(id, actor)| ActorListEntry::Named(id.clone(), actor.name.clone()))
+                .collect(),
+        },
+    }
+}
+
+/// The actor name a manifest declares, as Theater would read it
+fn manifest_name(path: &Path) -> Result<String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read manifest {}: {}", path.display(), e))?;
+    let manifest: toml::Table = toml::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse manifest {}: {}", path.display(), e))?;
+
+    manifest
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Manifest {} has no name", path.display()))
+}
+
+fn error_response(message: String) -> ManagementResponse {
+    ManagementResponse::Error {
+        error: serde_json::Value::String(message),
+    }
+}
@@ -0,0 +1,570 @@
+// Each test crate uses a different part of the harness
+#![allow(dead_code)]
+
+pub mod fake_theater;
+
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Once;
+use std::time::Duration;
+use tempfile::TempDir;
+
+use actor_registry_mcp::registry::Registry;
+
+/// How long a single tool call may take before the test gives up
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Environment variables of the developer's shell that would leak into the
+/// server's configuration
+const INHERITED_VARS: &[&str] = &[
+    "THEATER_ACTORS_PATH",
+    "THEATER_BINARY",
+    "THEATER_WIT_PATH",
+    "THEATER_SERVER_ADDR",
+    "THEATER_HOME",
+    "ACTOR_REGISTRY_CONFIG",
+    "ACTOR_REGISTRY_SERVER_ADDR",
+    "ACTOR_REGISTRY_LOG_FILE",
+    "ACTOR_REGISTRY_LOG_FORMAT",
+    "ACTOR_REGISTRY_LOG_ROTATION",
+    "ACTOR_REGISTRY_LOG_TO_CLIENT",
+    "RUST_LOG",
+];
+
+/// A temporary registry with a fake `theater` CLI, isolated from the user's
+/// own config, state and Theater home
+pub struct TestEnv {
+    dir: TempDir,
+    pub registry: PathBuf,
+    pub theater: FakeTheater,
+    env: Vec<(String, String)>,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let registry = dir.path().join("registry");
+        for sub in ["registry", "home", "config", "data", "state", "theater"] {
+            fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+
+        let component = dir.path().join("echo.wasm");
+        fs::write(&component, echo_component()).unwrap();
+        let theater = FakeTheater::install(&dir.path().join("bin"), &component);
+
+        Self {
+            registry,
+            theater,
+            env: Vec::new(),
+            dir,
+        }
+    }
+
+    /// Sets an environment variable for servers started afterwards
+    pub fn with_env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Scratch directory for files a test needs outside the registry
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn actor_path(&self, name: &str) -> PathBuf {
+        self.registry.join(name)
+    }
+
+    /// Where Theater would save event chains in this environment
+    pub fn chain_store(&self) -> PathBuf {
+        self.dir.path().join("theater").join("chains")
+    }
+
+    /// The registry binary, set up to run on this registry
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_actor-registry-mcp"));
+        for var in INHERITED_VARS {
+            cmd.env_remove(var);
+        }
+
+        cmd.env("THEATER_ACTORS_PATH", &self.registry)
+            .env("THEATER_BINARY", self.theater.path())
+            .env("THEATER_HOME", self.dir.path().join("theater"))
+            .env("HOME", self.dir.path().join("home"))
+            .env("XDG_CONFIG_HOME", self.dir.path().join("config"))
+            .env("XDG_DATA_HOME", self.dir.path().join("data"))
+            .env("XDG_STATE_HOME", self.dir.path().join("state"))
+            .envs(self.env.iter().map(|(k, v)| (k, v)));
+        cmd
+    }
+
+    /// Starts the MCP server on this registry
+    pub fn server(&self) -> McpServer {
+        self.server_for(self.command())
+    }
+
+    /// Starts the MCP server with `cmd`, e.g. one built by [`cli`] with
+    /// extra flags
+    pub fn server_for(&self, mut cmd: Command) -> McpServer {
+        let log = fs::File::create(self.dir.path().join("server.log")).unwrap();
+        cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(log);
+
+        McpServer::spawn(cmd, self.dir.path().join("server.log"))
+    }
+
+    /// Creates an actor from the basic template, failing the test if the
+    /// server refuses
+    pub fn create_actor(&self, server: &mut McpServer, name: &str) {
+        server.call_ok("create-new-actor", json!({ "name": name }));
+    }
+
+    /// Creates an actor and builds it with the fake `theater`
+    pub fn create_built_actor(&self, server: &mut McpServer, name: &str) {
+        self.create_actor(server, name);
+        server.call_ok("build-actor", json!({ "name": name }));
+    }
+}
+
+/// The registry binary with `args`, on the registry of `env`
+pub fn cli(env: &TestEnv, args: &[&str]) -> Command {
+    let mut cmd = env.command();
+    cmd.args(args);
+    cmd
+}
+
+/// Runs a CLI command, returning whether it succeeded, its stdout and its
+/// stderr
+pub fn run(mut cmd: Command) -> (bool, String, String) {
+    let output = cmd.output().expect("Failed to run the registry binary");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    )
+}
+
+/// Runs a CLI command that must succeed, returning its stdout
+pub fn run_ok(cmd: Command) -> String {
+    let (ok, stdout, stderr) = run(cmd);
+    assert!(ok, "command failed: {}", stderr);
+    stdout
+}
+
+/// Runs a CLI command that must fail, returning its stderr
+pub fn run_err(cmd: Command) -> String {
+    let (ok, stdout, stderr) = run(cmd);
+    assert!(!ok, "command succeeded: {}", stdout);
+    stderr
+}
+
+/// A registry in a temporary directory
+pub struct TestRegistry {
+    dir: TempDir,
+    pub registry: Registry,
+}
+
+impl TestRegistry {
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let root = dir.path().join("registry");
+        fs::create_dir_all(&root).unwrap();
+        let registry = Registry::new(&root).expect("Failed to open the test registry");
+
+        Self { dir, registry }
+    }
+
+    /// Scratch directory for files a test needs outside the registry
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn actor_path(&self, name: &str) -> PathBuf {
+        self.registry.path().join(name)
+    }
+
+    /// Creates an actor from the basic template, failing the test if the
+    /// registry refuses
+    pub fn create_actor(&self, name: &str) {
+        self.registry
+            .create_actor(name, None)
+            .unwrap_or_else(|e| panic!("Failed to create actor '{}': {}", name, e));
+    }
+
+    /// Creates an actor and "builds" it the way `theater build` does:
+    /// the echo component is copied into the actor's target directory and
+    /// the manifest is pointed at it
+    pub fn create_built_actor(&self, name: &str) {
+        self.create_actor(name);
+        self.build_actor(name);
+    }
+
+    pub fn build_actor(&self, name: &str) {
+        let actor = self.actor_path(name);
+        let crate_name = name.rsplit('/').next().unwrap();
+        let out = format!("target/wasm32-unknown-unknown/release/{}.wasm", crate_name);
+        fs::create_dir_all(actor.join("target/wasm32-unknown-unknown/release")).unwrap();
+        fs::write(actor.join(&out), echo_component()).unwrap();
+
+        let manifest = fs::read_to_string(actor.join("manifest.toml")).unwrap();
+        let manifest: Vec<String> = manifest
+            .lines()
+            .map(|line| {
+                if line.starts_with("component = ") {
+                    format!("component = {:?}", out)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        fs::write(actor.join("manifest.toml"), manifest.join("\n") + "\n").unwrap();
+    }
+}
+
+/// Puts a [`FakeTheater`] first on PATH for the rest of the test process,
+/// for tests that call the registry library rather than the binary
+pub fn install_fake_theater() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+            .join("fake-theater")
+            .join(std::process::id().to_string());
+        fs::create_dir_all(&dir).unwrap();
+
+        let component = dir.join("echo.wasm");
+        fs::write(&component, echo_component()).unwrap();
+        FakeTheater::install(&dir, &component);
+
+        let mut paths = vec![dir];
+        paths.extend(env::split_paths(&env::var_os("PATH").unwrap_or_default()));
+        env::set_var("PATH", env::join_paths(paths).unwrap());
+    });
+}
+
+/// The echo component from `tests/fixtures/echo.wat`: `init` logs and keeps
+/// its state, `handle-send` stores the message as state and `handle-request`
+/// answers with the request, except that requests starting with `x` fail
+/// and ones starting with `l` never return
+pub fn echo_component() -> Vec<u8> {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/echo.wat");
+    wat::parse_file(&fixture).expect("Failed to assemble the echo component")
+}
+
+/// Shell script standing in for the Theater CLI. `build` copies the echo
+/// component into the actor's target directory and points the manifest at
+/// it, like the real build does, unless the actor contains a `FAIL_BUILD`
+/// file; every call is logged.
+pub struct FakeTheater {
+    dir: PathBuf,
+}
+
+impl FakeTheater {
+    fn install(dir: &Path, component: &Path) -> Self {
+        fs::create_dir_all(dir).unwrap();
+        let script = format!(
+            r#"#!/bin/sh
+echo "$*" >> "{dir}/calls.log"
+for arg; do actor="$arg"; done
+if [ -e "{dir}/fail" ] || [ -e "$actor/FAIL_BUILD" ]; then
+    echo "error[E0425]: cannot find value in this scope" >&2
+    exit 1
+fi
+[ "$1" = build ] || exit 0
+name=$(basename "$actor")
+out="target/wasm32-unknown-unknown/release/$name.wasm"
+mkdir -p "$actor/target/wasm32-unknown-unknown/release"
+cp "{component}" "$actor/$out"
+sed -i.bak "s|^component = .*|component = \"$out\"|" "$actor/manifest.toml"
+rm -f "$actor/manifest.toml.bak"
+echo "Compiled $name"
+"#,
+            dir = dir.display(),
+            component = component.display()
+        );
+
+        let path = dir.join("theater");
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join("theater")
+    }
+
+    /// Makes every later call fail like a compile error would
+    pub fn fail_builds(&self, fail: bool) {
+        let marker = self.dir.join("fail");
+        if fail {
+            fs::write(marker, "").unwrap();
+        } else {
+            let _ = fs::remove_file(marker);
+        }
+    }
+
+    /// Arguments of each call so far
+    pub fn calls(&self) -> Vec<String> {
+        fs::read_to_string(self.dir.join("calls.log"))
+            .map(|log| log.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// What a tool call returned
+#[derive(Debug)]
+pub struct ToolOutput {
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// The server binary driven over its stdin and stdout with newline
+/// delimited JSON-RPC
+pub struct McpServer {
+    child: Child,
+    stdin: ChildStdin,
+    messages: Receiver<Value>,
+    /// Notifications received while waiting for responses
+    notifications: Vec<Value>,
+    next_id: u64,
+    log: PathBuf,
+}
+
+impl McpServer {
+    fn spawn(mut cmd: Command, log: PathBuf) -> Self {
+        let mut child = cmd.spawn().expect("Failed to start actor-registry-mcp");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        let (sender, messages) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Value>(&line) {
+                    Ok(message) => {
+                        if sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => eprintln!("Server wrote a line that is not JSON ({}): {}", e, line),
+                }
+            }
+        });
+
+        let mut server = Self {
+            child,
+            stdin,
+            messages,
+            notifications: Vec::new(),
+            next_id: 0,
+            log,
+        };
+
+        server
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": { "name": "integration-tests", "version": "0.1.0" }
+                }),
+            )
+            .expect("Server failed to initialize");
+        server.notify("notifications/initialized", json!({}));
+        server
+    }
+
+    fn send(&mut self, message: &Value) {
+        writeln!(self.stdin, "{}", message).expect("Failed to write to the server");
+        self.stdin.flush().unwrap();
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    /// Sends a request and waits for its response, skipping notifications
+    /// such as forwarded log records. Returns the JSON-RPC error message if
+    /// the server answered with one.
+    pub fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+
+        loop {
+            let message = match self.messages.recv_timeout(RESPONSE_TIMEOUT) {
+                Ok(message) => message,
+                Err(e) => panic!(
+                    "No response to {} ({}); server log:\n{}",
+                    method,
+                    e,
+                    self.server_log()
+                ),
+            };
+            if message.get("id").and_then(|v| v.as_u64()) != Some(id) {
+                if message.get("id").is_none() {
+                    self.notifications.push(message);
+                }
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                return Err(error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or_default()
+                    .to_string());
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    /// Waits for a notification `method` whose params satisfy `matches`,
+    /// including ones that arrived while waiting for earlier responses
+    pub fn wait_for_notification(
+        &mut self,
+        method: &str,
+        matches: impl Fn(&Value) -> bool,
+    ) -> Value {
+        let wanted = |message: &Value| message["method"] == method && matches(&message["params"]);
+        if let Some(found) = self.notifications.iter().position(wanted) {
+            return self.notifications.remove(found);
+        }
+
+        loop {
+            let message = match self.messages.recv_timeout(RESPONSE_TIMEOUT) {
+                Ok(message) => message,
+                Err(e) => panic!(
+                    "No {} notification ({}); server log:\n{}",
+                    method,
+                    e,
+                    self.server_log()
+                ),
+            };
+            if wanted(&message) {
+                return message;
+            }
+            if message.get("id").is_none() {
+                self.notifications.push(message);
+            }
+        }
+    }
+
+    pub fn list_tools(&mut self) -> Vec<String> {
+        let result = self.request("tools/list", json!({})).unwrap();
+        result["tools"]
+            .as_array()
+            .expect("tools/list returned no tools")
+            .iter()
+            .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// Calls a tool; a JSON-RPC error counts as an error result so tests
+    /// don't depend on how the server reports a failed handler
+    pub fn call(&mut self, tool: &str, args: Value) -> ToolOutput {
+        match self.request("tools/call", json!({ "name": tool, "arguments": args })) {
+            Ok(result) => {
+                let text = result["content"]
+                    .as_array()
+                    .map(|content| {
+                        content
+                            .iter()
+                            .filter_map(|c| c["text"].as_str())
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })
+                    .unwrap_or_default();
+                ToolOutput {
+                    text,
+                    is_error: result["isError"].as_bool().unwrap_or(false),
+                }
+            }
+            Err(message) => ToolOutput {
+                text: message,
+                is_error: true,
+            },
+        }
+    }
+
+    /// Calls a tool that must succeed and returns its text
+    pub fn call_ok(&mut self, tool: &str, args: Value) -> String {
+        let output = self.call(tool, args.clone());
+        assert!(
+            !output.is_error,
+            "{} {} failed:\n{}",
+            tool, args, output.text
+        );
+        output.text
+    }
+
+    /// Calls a tool that must fail and returns its error text
+    pub fn call_err(&mut self, tool: &str, args: Value) -> String {
+        let output = self.call(tool, args.clone());
+        assert!(
+            output.is_error,
+            "{} {} should have failed:\n{}",
+            tool, args, output.text
+        );
+        output.text
+    }
+
+    fn server_log(&self) -> String {
+        fs::read_to_string(&self.log).unwrap_or_default()
+    }
+}
+
+impl Drop for McpServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Rewrites every entry of a bundle through `edit`, which gets the entry's
+/// path, contents and mode
+pub fn repack(bundle: &Path, edit: impl Fn(&str, &mut Vec<u8>, &mut u32)) {
+    let file = fs::File::open(bundle).unwrap();
+    let mut archive = tar::Archive::new(zstd::stream::read::Decoder::new(file).unwrap());
+    let mut entries = Vec::new();
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().to_string_lossy().to_string();
+        let mut mode = entry.header().mode().unwrap();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        edit(&path, &mut data, &mut mode);
+        entries.push((path, data, mode));
+    }
+
+    let file = fs::File::create(bundle).unwrap();
+    let mut builder = tar::Builder::new(zstd::stream::write::Encoder::new(file, 0).unwrap());
+    for (path, data, mode) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(mode);
+        header.set_entry_type(tar::EntryType::Regular);
+        builder
+            .append_data(&mut header, path, data.as_slice())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+/// Asserts that `text` contains every one of `needles`
+#[track_caller]
+pub fn assert_contains(text: &str, needles: &[&str]) {
+    for needle in needles {
+        assert!(text.contains(needle), "expected {:?} in:\n{}", needle, text);
+    }
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use actor_registry_mcp::registry::compat;
+use common::{assert_contains, TestRegistry};
+use std::fs;
+
+#[test]
+fn built_actor_matches_its_handlers() {
+    let test = TestRegistry::new();
+    test.create_built_actor("echo");
+
+    let report = test.registry.check_compatibility("echo").unwrap();
+    assert!(report.is_compatible(), "{}", report.to_text());
+    assert_eq!(report.imports, ["ntwk:theater/runtime"]);
+    assert_eq!(
+        report.exports,
+        ["ntwk:theater/actor", "ntwk:theater/message-server-client"]
+    );
+    assert_eq!(report.configured_handlers, ["runtime"]);
+    assert!(report.unused_handlers.is_empty());
+    assert_contains(&report.to_text(), &["Result: compatible"]);
+}
+
+#[test]
+fn missing_and_unused_handlers_are_reported() {
+    let test = TestRegistry::new();
+    test.create_built_actor("echo");
+
+    // Swap the runtime handler for one the component has no use for
+    let manifest_path = test.actor_path("echo").join("manifest.toml");
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+    let manifest = manifest.replace("type = \"runtime\"", "type = \"http-client\"");
+    fs::write(&manifest_path, manifest).unwrap();
+
+    let report = test.registry.check_compatibility("echo").unwrap();
+    assert!(!report.is_compatible());
+    assert_eq!(report.missing_handlers.len(), 1);
+    assert_eq!(report.missing_handlers[0].handler, "runtime");
+    assert_eq!(report.missing_handlers[0].interface, "ntwk:theater/runtime");
+    assert_eq!(report.unused_handlers, ["http-client"]);
+    assert_contains(
+        &report.to_text(),
+        &[
+            "Missing Handlers",
+            "Unused Handlers",
+            "Result: incompatible",
+        ],
+    );
+}
+
+#[test]
+fn unbuilt_actors_and_core_modules_are_rejected() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+
+    let err = test.registry.check_compatibility("echo").unwrap_err();
+    assert_contains(&err.to_string(), &["not been built"]);
+
+    let module = test.path().join("module.wasm");
+    fs::write(&module, wat::parse_str("(module)").unwrap()).unwrap();
+    let err = compat::component_interfaces(&module).unwrap_err();
+    assert_contains(&err.to_string(), &["core WebAssembly module"]);
+}
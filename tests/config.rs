@@ -0,0 +1,251 @@
+mod common;
+
+use actor_registry_mcp::registry::config::{ConfigLayers, ConfigSource};
+use actor_registry_mcp::registry::Registry;
+use actor_registry_mcp::tools::build_actor;
+use common::{assert_contains, cli, run_ok, TestEnv, TestRegistry};
+use mcp_protocol::types::tool::ToolContent;
+use serde_json::json;
+use std::fs;
+
+fn string(value: &str) -> toml::Value {
+    toml::Value::String(value.to_string())
+}
+
+#[test]
+fn layers_apply_in_precedence_order() {
+    let test = TestRegistry::new();
+    let user_file = test.path().join("user.toml");
+    fs::write(
+        &user_file,
+        "registry = \"/elsewhere\"\nbuild_cache_enabled = false\nserver_addr = \"127.0.0.1:9001\"\ndefault_template = \"http\"\n",
+    )
+    .unwrap();
+    let config_file = test.registry.config_file();
+    fs::write(&config_file, "server_addr = \"127.0.0.1:9002\"\n").unwrap();
+
+    let mut overrides = toml::Table::new();
+    overrides.insert("default_template".to_string(), string("supervisor"));
+    let layers = ConfigLayers {
+        user_file: Some(user_file.clone()),
+        overrides,
+    };
+    assert_eq!(
+        layers.user_registry().unwrap().as_deref(),
+        Some("/elsewhere")
+    );
+
+    let registry = Registry::with_config(vec![test.registry.path().to_path_buf()], layers).unwrap();
+    let resolved = registry.resolve_config().unwrap();
+    assert!(!resolved.config.build_cache_enabled);
+    assert_eq!(resolved.config.server_addr(), "127.0.0.1:9002");
+    assert_eq!(resolved.config.default_template, "supervisor");
+    assert_eq!(resolved.config.default_interfaces, ["ntwk:theater/actor"]);
+
+    let source = |key: &str| resolved.sources[key].clone();
+    assert_eq!(source("default_interfaces"), ConfigSource::Default);
+    assert_eq!(
+        source("build_cache_enabled"),
+        ConfigSource::UserFile(user_file)
+    );
+    assert_eq!(
+        source("server_addr"),
+        ConfigSource::RegistryFile(config_file)
+    );
+    assert_eq!(source("default_template"), ConfigSource::Overrides);
+    // The user config's registry is not a config value
+    assert!(!resolved.sources.contains_key("registry"));
+
+    // Every layer is validated
+    let mut overrides = toml::Table::new();
+    overrides.insert("server_addr".to_string(), string("nope"));
+    let layers = ConfigLayers {
+        user_file: None,
+        overrides,
+    };
+    let err = Registry::with_config(vec![test.registry.path().to_path_buf()], layers)
+        .map(|_| ())
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["not of the form host:port"]);
+}
+
+#[test]
+fn new_registries_only_comment_the_defaults() {
+    let test = TestRegistry::new();
+    let config = fs::read_to_string(test.registry.config_file()).unwrap();
+    assert_contains(&config, &["# default_template = \"basic\""]);
+    // Only the schema version is set
+    let parsed: toml::Table = toml::from_str(&config).unwrap();
+    assert_eq!(parsed.keys().collect::<Vec<_>>(), ["schema_version"]);
+
+    let resolved = test.registry.resolve_config().unwrap();
+    assert!(resolved
+        .sources
+        .iter()
+        .filter(|(key, _)| *key != "schema_version")
+        .all(|(_, source)| *source == ConfigSource::Default));
+}
+
+#[test]
+fn set_config_keeps_comments_and_removes_keys() {
+    let test = TestRegistry::new();
+    let config_file = test.registry.config_file();
+    fs::write(
+        &config_file,
+        "# team settings\nbuild_cache_enabled = false # for now\n",
+    )
+    .unwrap();
+
+    test.registry
+        .set_config("default_template", Some(&string("supervisor")))
+        .unwrap();
+    test.registry
+        .set_config("logging.filter", Some(&string("debug")))
+        .unwrap();
+    assert_eq!(test.registry.config().default_template, "supervisor");
+
+    let resolved = test.registry.set_config("default_template", None).unwrap();
+    assert_eq!(resolved.config.default_template, "basic");
+    assert_eq!(resolved.sources["default_template"], ConfigSource::Default);
+
+    let config = fs::read_to_string(&config_file).unwrap();
+    assert_contains(
+        &config,
+        &[
+            "# team settings",
+            "build_cache_enabled = false # for now",
+            "[logging]",
+            "filter = \"debug\"",
+        ],
+    );
+    assert!(!config.contains("default_template"), "{}", config);
+}
+
+#[test]
+fn set_config_rejects_invalid_values_before_writing() {
+    let test = TestRegistry::new();
+    let config_file = test.registry.config_file();
+    let before = fs::read_to_string(&config_file).unwrap();
+
+    let err = test
+        .registry
+        .set_config("bogus", Some(&string("1")))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["Unknown config key 'bogus'"]);
+    assert!(test
+        .registry
+        .set_config("logging.colour", Some(&string("red")))
+        .is_err());
+
+    let invalid = [
+        ("server_addr", string("nope"), "not of the form host:port"),
+        (
+            "default_template",
+            string("fancy"),
+            "is not one of basic, http, supervisor",
+        ),
+        (
+            "default_interfaces",
+            toml::Value::Array(vec![string("actor")]),
+            "namespace:package/interface",
+        ),
+        ("logging.filter", string("info,x=loud"), "logging.filter"),
+    ];
+    for (key, value, message) in invalid {
+        let err = test.registry.set_config(key, Some(&value)).unwrap_err();
+        assert_contains(&err.to_string(), &[message]);
+    }
+
+    let remotes = toml::Value::try_from(json!([
+        { "name": "origin", "url": "ftp://example.com" },
+        { "name": "../up", "url": "http://example.com" },
+    ]))
+    .unwrap();
+    let err = test
+        .registry
+        .set_config("remotes", Some(&remotes))
+        .unwrap_err();
+    assert_contains(
+        &err.to_string(),
+        &[
+            "expected http:// or https://",
+            "'../up' is not a valid name",
+        ],
+    );
+
+    assert_eq!(fs::read_to_string(&config_file).unwrap(), before);
+    assert!(test.registry.config().server_addr.is_none());
+}
+
+#[test]
+fn builds_and_new_actors_use_the_configured_paths() {
+    let test = TestRegistry::new();
+    fs::create_dir_all(test.registry.path().join("wit-src")).unwrap();
+    fs::write(
+        test.registry.path().join("wit-src/shared.wit"),
+        "package ntwk:shared;\n",
+    )
+    .unwrap();
+
+    // Relative WIT sources are resolved against the registry
+    test.registry
+        .set_config("wit_source", Some(&string("wit-src")))
+        .unwrap();
+    test.create_actor("echo");
+    assert!(test.actor_path("echo/wit/shared.wit").is_file());
+
+    let missing = test.path().join("no-such-theater");
+    test.registry
+        .set_config("theater_binary", Some(&string(missing.to_str().unwrap())))
+        .unwrap();
+    assert_eq!(test.registry.config().theater_binary(), missing);
+
+    let result = build_actor::handle(&test.registry, &json!({ "name": "echo" })).unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let ToolContent::Text { text } = &result.content[0] else {
+        panic!("expected text content");
+    };
+    assert_contains(text, &["Failed to execute theater build command"]);
+}
+
+#[test]
+fn the_user_config_can_name_the_registry() {
+    let env = TestEnv::new();
+    let user_config = env.path().join("config/actor-registry/config.toml");
+    fs::create_dir_all(user_config.parent().unwrap()).unwrap();
+    fs::write(
+        &user_config,
+        format!("registry = {:?}\n", env.registry.to_str().unwrap()),
+    )
+    .unwrap();
+
+    let mut cmd = cli(&env, &["create", "counter"]);
+    cmd.env_remove("THEATER_ACTORS_PATH");
+    run_ok(cmd);
+    assert!(env.registry.join("counter/manifest.toml").is_file());
+
+    // `--config` points at another user config
+    let other = env.path().join("other.toml");
+    let elsewhere = env.path().join("elsewhere");
+    fs::create_dir_all(&elsewhere).unwrap();
+    fs::write(
+        &other,
+        format!("registry = {:?}\n", elsewhere.to_str().unwrap()),
+    )
+    .unwrap();
+    let mut cmd = cli(
+        &env,
+        &["--config", other.to_str().unwrap(), "create", "timer"],
+    );
+    cmd.env_remove("THEATER_ACTORS_PATH");
+    run_ok(cmd);
+    assert!(elsewhere.join("timer/manifest.toml").is_file());
+
+    // THEATER_ACTORS_PATH wins over the user config
+    run_ok(cli(
+        &env,
+        &["--config", other.to_str().unwrap(), "create", "logger"],
+    ));
+    assert!(env.registry.join("logger/manifest.toml").is_file());
+    assert!(!elsewhere.join("logger").exists());
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use common::{assert_contains, cli, run_err, run_ok, TestEnv};
+use serde_json::json;
+use std::fs;
+
+#[test]
+fn get_registry_config_shows_where_values_come_from() {
+    let env = TestEnv::new();
+
+    let user_config = env.path().join("config/actor-registry");
+    fs::create_dir_all(&user_config).unwrap();
+    fs::write(
+        user_config.join("config.toml"),
+        "build_cache_enabled = false\n",
+    )
+    .unwrap();
+
+    let mut server = env.server();
+    let text = server.call_ok("get-registry-config", json!({}));
+    assert_contains(
+        &text,
+        &[
+            "default_template = \"basic\"  [built-in default]",
+            "build_cache_enabled = false  [user config",
+            "theater_binary",
+        ],
+    );
+
+    let text = server.call_ok("get-registry-config", json!({ "key": "default_template" }));
+    assert!(!text.contains("build_cache_enabled"), "{}", text);
+
+    server.call_err("get-registry-config", json!({ "key": "bogus" }));
+}
+
+#[test]
+fn config_layers_apply_in_precedence_order() {
+    let env = TestEnv::new();
+    let theater_addr = |cmd| {
+        let mut server = env.server_for(cmd);
+        server.call_ok("get-registry-config", json!({ "key": "theater_addr" }))
+    };
+
+    let text = theater_addr(cli(&env, &[]));
+    assert_contains(&text, &["theater_addr = (unset)  [built-in default]"]);
+
+    let user_config = env.path().join("config/actor-registry/config.toml");
+    fs::create_dir_all(user_config.parent().unwrap()).unwrap();
+    fs::write(&user_config, "theater_addr = \"127.0.0.1:9001\"\n").unwrap();
+    let text = theater_addr(cli(&env, &[]));
+    assert_contains(&text, &["\"127.0.0.1:9001\"  [user config"]);
+
+    let registry_config = env.registry.join(".registry.config.toml");
+    fs::write(&registry_config, "theater_addr = \"127.0.0.1:9002\"\n").unwrap();
+    let text = theater_addr(cli(&env, &[]));
+    assert_contains(&text, &["\"127.0.0.1:9002\"  [registry config"]);
+
+    let mut cmd = cli(&env, &[]);
+    cmd.env("THEATER_SERVER_ADDR", "127.0.0.1:9003");
+    let text = theater_addr(cmd);
+    assert_contains(
+        &text,
+        &["\"127.0.0.1:9003\"  [environment or command line]"],
+    );
+
+    let mut cmd = cli(&env, &["--theater-addr", "127.0.0.1:9004"]);
+    cmd.env("THEATER_SERVER_ADDR", "127.0.0.1:9003");
+    let text = theater_addr(cmd);
+    assert_contains(
+        &text,
+        &["\"127.0.0.1:9004\"  [environment or command line]"],
+    );
+
+    // Every layer is validated, including the ones below the registry file
+    fs::write(&registry_config, "").unwrap();
+    fs::write(&user_config, "theater_addr = \"nope\"\n").unwrap();
+    let text = run_err(cli(&env, &["list"]));
+    assert_contains(&text, &["not of the form host:port"]);
+
+    fs::remove_file(&user_config).unwrap();
+    let text = run_err(cli(&env, &["--theater-addr", "nope", "list"]));
+    assert_contains(&text, &["not of the form host:port"]);
+}
+
+#[test]
+fn set_registry_config_keeps_comments_and_removes_keys() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    let config_path = env.registry.join(".registry.config.toml");
+
+    let text = server.call_ok(
+        "set-registry-config",
+        json!({ "key": "default_template", "value": "supervisor" }),
+    );
+    assert_contains(&text, &["Updated 'default_template'"]);
+    let text = server.call_ok("get-registry-config", json!({ "key": "default_template" }));
+    assert_contains(
+        &text,
+        &["default_template = \"supervisor\"  [registry config"],
+    );
+
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, format!("# team settings\n{}", config)).unwrap();
+
+    let text = server.call_ok(
+        "set-registry-config",
+        json!({ "key": "logging.filter", "value": "debug" }),
+    );
+    assert_contains(&text, &["Restart the server"]);
+
+    server.call_ok("set-registry-config", json!({ "key": "default_template" }));
+    let text = server.call_ok("get-registry-config", json!({ "key": "default_template" }));
+    assert_contains(&text, &["default_template = \"basic\"  [built-in default]"]);
+
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert_contains(
+        &config,
+        &["# team settings", "[logging]", "filter = \"debug\""],
+    );
+    let parsed: toml::Table = toml::from_str(&config).unwrap();
+    assert!(parsed.get("default_template").is_none());
+}
+
+#[test]
+fn set_registry_config_rejects_invalid_values() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    let text = server.call_err("set-registry-config", json!({ "key": "bogus", "value": 1 }));
+    assert_contains(&text, &["Unknown config key 'bogus'"]);
+
+    let text = server.call_err(
+        "set-registry-config",
+        json!({ "key": "server_addr", "value": "nope" }),
+    );
+    assert_contains(&text, &["not of the form host:port"]);
+
+    server.call_err(
+        "set-registry-config",
+        json!({ "key": "remotes", "value": [{ "name": "x", "url": "ftp://example.com" }] }),
+    );
+    server.call_err("set-registry-config", json!({}));
+
+    // Nothing invalid reached the file
+    let text = server.call_ok("get-registry-config", json!({ "key": "server_addr" }));
+    assert_contains(&text, &["(unset)"]);
+}
+
+#[test]
+fn old_configs_are_migrated_with_a_backup() {
+    let env = TestEnv::new();
+    let config_path = env.registry.join(".registry.config.toml");
+    let v1 = "# hand-written before schema versions\ndefault_template = \"http\"\n";
+    fs::write(&config_path, v1).unwrap();
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env("RUST_LOG", "info");
+    run_ok(cmd);
+
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert_contains(
+        &config,
+        &[
+            "# hand-written before schema versions",
+            "default_template = \"http\"",
+            "schema_version = 2",
+        ],
+    );
+
+    let backups: Vec<_> = fs::read_dir(&env.registry)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with(".registry.config.toml.v1-") && name.ends_with(".bak")
+        })
+        .collect();
+    assert_eq!(backups.len(), 1, "{:?}", backups);
+    assert_eq!(fs::read_to_string(&backups[0]).unwrap(), v1);
+
+    let log =
+        fs::read_to_string(env.path().join("state/actor-registry/actor-registry.log")).unwrap();
+    assert_contains(
+        &log,
+        &["from schema version 1 to 2 (record the schema version)"],
+    );
+
+    // A current config is left alone
+    run_ok(cli(&env, &["list"]));
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), config);
+
+    fs::write(&config_path, "schema_version = 99\n").unwrap();
+    let text = run_err(cli(&env, &["list"]));
+    assert_contains(&text, &["schema version 99", "upgrade the actor registry"]);
+}
+
+#[test]
+fn init_registry_creates_and_is_idempotent() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    let wit = env.path().join("wit");
+    fs::create_dir_all(&wit).unwrap();
+    fs::write(wit.join("actor.wit"), "package ntwk:theater;\n").unwrap();
+
+    let target = env.path().join("new-registry");
+    let text = server.call_ok(
+        "init-registry",
+        json!({
+            "path": target.to_str().unwrap(),
+            "wit_source": wit.to_str().unwrap(),
+            "git": true
+        }),
+    );
+    assert_contains(&text, &["Initialized registry", "config: created"]);
+    assert!(target.join(".registry.config.toml").is_file());
+    assert!(target.join(".git").is_dir());
+    assert!(target.join(".gitignore").is_file());
+    assert!(target.join("wit/actor.wit").is_file());
+
+    let config = fs::read_to_string(target.join(".registry.config.toml")).unwrap();
+    assert_contains(&config, &["wit_source = \"wit\""]);
+    fs::write(
+        target.join(".registry.config.toml"),
+        format!("{}\n# edited by hand\n", config),
+    )
+    .unwrap();
+
+    let text = server.call_ok(
+        "init-registry",
+        json!({ "path": target.to_str().unwrap(), "git": false }),
+    );
+    assert_contains(&text, &["already set up", "config: unchanged"]);
+    assert!(fs::read_to_string(target.join(".registry.config.toml"))
+        .unwrap()
+        .contains("# edited by hand"));
+}
+
+#[test]
+fn init_registry_errors() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    // A file where the registry should go
+    let file = env.path().join("not-a-dir");
+    fs::write(&file, "").unwrap();
+    server.call_err("init-registry", json!({ "path": file.to_str().unwrap() }));
+
+    let missing_wit = env.path().join("no-wit");
+    server.call_err(
+        "init-registry",
+        json!({
+            "path": env.path().join("other").to_str().unwrap(),
+            "wit_source": missing_wit.to_str().unwrap()
+        }),
+    );
+}
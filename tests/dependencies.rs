@@ -0,0 +1,250 @@
+mod common;
+
+use actor_registry_mcp::registry::dependencies::{self, DependencyReport, DependencySpec};
+use common::{assert_contains, TestRegistry};
+use serde_json::json;
+use std::fs;
+
+fn spec(args: serde_json::Value) -> DependencySpec {
+    DependencySpec::from_json(&args).unwrap()
+}
+
+fn cargo_toml(test: &TestRegistry, actor: &str) -> String {
+    fs::read_to_string(test.actor_path(actor).join("Cargo.toml")).unwrap()
+}
+
+#[test]
+fn add_list_and_remove_keep_the_rest_of_cargo_toml() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    let path = test.actor_path("echo").join("Cargo.toml");
+    let original = cargo_toml(&test, "echo");
+    let original = original.replace(
+        "serde_json = \"1.0\"",
+        "serde_json = \"1.0\" # pinned by hand",
+    );
+    fs::write(&path, format!("# The echo actor\n{}", original)).unwrap();
+    let actor = test.registry.find_actor("echo").unwrap();
+
+    let previous = dependencies::add_dependency(
+        &actor,
+        &spec(json!({ "name": "anyhow", "version": "1.0", "features": ["backtrace"] })),
+        false,
+    )
+    .unwrap();
+    assert_eq!(previous, None);
+
+    // Replacing an entry keeps its trailing comment
+    let previous = dependencies::add_dependency(
+        &actor,
+        &spec(json!({ "name": "serde_json", "version": "1.0.100" })),
+        false,
+    )
+    .unwrap();
+    assert_eq!(previous.unwrap().version.as_deref(), Some("1.0"));
+
+    let cargo = cargo_toml(&test, "echo");
+    assert_contains(
+        &cargo,
+        &[
+            "# The echo actor",
+            "serde_json = \"1.0.100\" # pinned by hand",
+            "anyhow = { version = \"1.0\", features = [\"backtrace\"] }",
+        ],
+    );
+
+    let listed = dependencies::list_dependencies(&actor).unwrap();
+    let anyhow = listed.iter().find(|d| d.name == "anyhow").unwrap();
+    assert_eq!(anyhow.features, ["backtrace"]);
+    assert_eq!(anyhow.to_text(), "anyhow: 1.0 [features: backtrace]");
+
+    let removed = dependencies::remove_dependency(&actor, "anyhow").unwrap();
+    assert_eq!(removed.unwrap().name, "anyhow");
+    assert_eq!(
+        dependencies::remove_dependency(&actor, "anyhow").unwrap(),
+        None
+    );
+    assert!(!cargo_toml(&test, "echo").contains("anyhow"));
+}
+
+#[test]
+fn invalid_specs_are_refused_before_writing() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    let actor = test.registry.find_actor("echo").unwrap();
+    let before = cargo_toml(&test, "echo");
+
+    let invalid = [
+        (
+            json!({ "name": "log", "version": "^1.x.y" }),
+            "Invalid version requirement '^1.x.y'",
+        ),
+        (
+            json!({ "name": "log" }),
+            "needs a version, path or git source",
+        ),
+        (
+            json!({ "name": "log", "path": "../log", "git": "https://example.com/log" }),
+            "both a path and a git source",
+        ),
+        (
+            json!({ "name": "log", "version": "0.4", "branch": "main" }),
+            "no git source",
+        ),
+        (
+            json!({ "name": "log", "git": "https://example.com/log", "tag": "v1", "rev": "abc" }),
+            "only specify one of branch, tag or rev",
+        ),
+    ];
+    for (args, message) in invalid {
+        let err = dependencies::add_dependency(&actor, &spec(args), false).unwrap_err();
+        assert_contains(&err.to_string(), &[message]);
+    }
+
+    assert!(DependencySpec::from_json(&json!({ "version": "1.0" })).is_err());
+    assert_eq!(cargo_toml(&test, "echo"), before);
+}
+
+#[test]
+fn wasm_incompatible_crates_need_force() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    let actor = test.registry.find_actor("echo").unwrap();
+
+    let mio = spec(json!({ "name": "mio", "version": "1" }));
+    let err = dependencies::add_dependency(&actor, &mio, false).unwrap_err();
+    assert_contains(&err.to_string(), &["mio", "wasm32-unknown-unknown"]);
+    assert!(!cargo_toml(&test, "echo").contains("mio"));
+
+    dependencies::add_dependency(&actor, &mio, true).unwrap();
+    assert_contains(&cargo_toml(&test, "echo"), &["mio = \"1\""]);
+
+    // Both build for wasm32 with the right features
+    assert_eq!(dependencies::wasm_incompatibility("tokio"), None);
+    assert_eq!(
+        dependencies::wasm_incompatibility("crossbeam-channel"),
+        None
+    );
+}
+
+#[test]
+fn bulk_operations_select_named_or_all_actors() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    test.create_actor("counter");
+
+    let all = test.registry.select_actors(&[], true).unwrap();
+    assert_eq!(all.len(), 2);
+
+    let named = test
+        .registry
+        .select_actors(&["counter".to_string()], false)
+        .unwrap();
+    assert_eq!(named[0].name, "counter");
+
+    assert!(test.registry.select_actors(&[], false).is_err());
+    assert!(test
+        .registry
+        .select_actors(&["missing".to_string()], false)
+        .is_err());
+}
+
+fn set_dependency(test: &TestRegistry, actor: &str, args: serde_json::Value) {
+    let actor = test.registry.find_actor(actor).unwrap();
+    dependencies::add_dependency(&actor, &spec(args), false).unwrap();
+}
+
+#[test]
+fn drift_report_groups_equivalent_requirements() {
+    let test = TestRegistry::new();
+    for actor in ["echo", "counter", "timer"] {
+        test.create_actor(actor);
+    }
+    set_dependency(&test, "echo", json!({ "name": "log", "version": "0.4" }));
+    set_dependency(
+        &test,
+        "counter",
+        json!({ "name": "log", "version": "^0.4.0" }),
+    );
+    set_dependency(&test, "timer", json!({ "name": "log", "version": "0.4" }));
+
+    let actors = test.registry.list_actors().unwrap();
+    let report = DependencyReport::build(&actors);
+    assert!(report.drifted().is_empty(), "{}", report.to_text(false));
+    assert_eq!(report.sources("log").len(), 1);
+
+    set_dependency(&test, "timer", json!({ "name": "log", "version": "0.3" }));
+    set_dependency(
+        &test,
+        "counter",
+        json!({ "name": "serde_json", "path": "../serde_json" }),
+    );
+
+    let actors = test.registry.list_actors().unwrap();
+    let report = DependencyReport::build(&actors);
+    let drifted: Vec<_> = report.drifted().into_iter().collect();
+    assert_eq!(drifted, ["log", "serde_json"]);
+
+    let sources = report.sources("serde_json");
+    assert_eq!(sources["path:../serde_json"], ["counter"]);
+    assert_contains(
+        &report.to_text(true),
+        &["## log (drift)", "- 0.3: timer", "## serde_json (drift)"],
+    );
+    assert!(!report.to_text(true).contains("## serde\n"));
+}
+
+#[test]
+fn align_rewrites_the_requirement_and_reports_a_diff() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    set_dependency(
+        &test,
+        "echo",
+        json!({ "name": "log", "version": "0.3", "features": ["std"] }),
+    );
+    let actor = test.registry.find_actor("echo").unwrap();
+    let before = cargo_toml(&test, "echo");
+
+    // A dry run only reports
+    let diff = dependencies::align_dependency(&actor, "log", "0.4", true)
+        .unwrap()
+        .unwrap();
+    assert_contains(&diff, &["a/echo/Cargo.toml", "-log = {", "+log = {"]);
+    assert_eq!(cargo_toml(&test, "echo"), before);
+
+    dependencies::align_dependency(&actor, "log", "0.4", false).unwrap();
+    let cargo = cargo_toml(&test, "echo");
+    assert_contains(&cargo, &["version = \"0.4\"", "features = [\"std\"]"]);
+
+    // Nothing left to change, and actors without the crate are skipped
+    assert_eq!(
+        dependencies::align_dependency(&actor, "serde_json", "1.0", false).unwrap(),
+        None
+    );
+    assert_eq!(
+        dependencies::align_dependency(&actor, "rand", "0.8", false).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn align_refuses_bad_requirements_and_local_sources() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    set_dependency(&test, "echo", json!({ "name": "log", "path": "../log" }));
+    let actor = test.registry.find_actor("echo").unwrap();
+    let before = cargo_toml(&test, "echo");
+
+    let err =
+        dependencies::align_dependency(&actor, "serde_json", "not-a-version", false).unwrap_err();
+    assert_contains(
+        &err.to_string(),
+        &["Invalid version requirement 'not-a-version'"],
+    );
+
+    let err = dependencies::align_dependency(&actor, "log", "0.4", false).unwrap_err();
+    assert_contains(&err.to_string(), &["path or git source"]);
+
+    assert_eq!(cargo_toml(&test, "echo"), before);
+}
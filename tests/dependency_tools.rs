@@ -0,0 +1,232 @@
+mod common;
+
+use common::{assert_contains, McpServer, TestEnv};
+use serde_json::json;
+use std::fs;
+
+fn cargo_toml(env: &TestEnv, actor: &str) -> String {
+    fs::read_to_string(env.actor_path(actor).join("Cargo.toml")).unwrap()
+}
+
+fn two_actors() -> (TestEnv, McpServer) {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+    env.create_actor(&mut server, "counter");
+    (env, server)
+}
+
+#[test]
+fn list_dependencies_of_one_or_all_actors() {
+    let (_env, mut server) = two_actors();
+
+    let text = server.call_ok("list-dependencies", json!({ "name": "echo" }));
+    assert_contains(&text, &["serde", "serde_json", "wit-bindgen-rt"]);
+
+    let text = server.call_ok(
+        "list-dependencies",
+        json!({ "all": true, "format": "json" }),
+    );
+    let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let rendered = value.to_string();
+    assert_contains(&rendered, &["echo", "counter", "serde_json"]);
+
+    server.call_err("list-dependencies", json!({ "name": "missing" }));
+    server.call_err("list-dependencies", json!({}));
+}
+
+#[test]
+fn add_dependency_keeps_the_rest_of_cargo_toml() {
+    let (env, mut server) = two_actors();
+
+    let path = env.actor_path("echo").join("Cargo.toml");
+    let original = cargo_toml(&env, "echo");
+    fs::write(&path, format!("# pinned by hand\n{}", original)).unwrap();
+
+    let text = server.call_ok(
+        "add-dependency",
+        json!({
+            "name": "echo",
+            "dependency": { "name": "anyhow", "version": "1.0", "features": ["backtrace"] }
+        }),
+    );
+    assert_contains(&text, &["anyhow", "echo: added"]);
+
+    let cargo = cargo_toml(&env, "echo");
+    assert_contains(&cargo, &["# pinned by hand", "anyhow", "backtrace"]);
+    let parsed: toml::Table = toml::from_str(&cargo).unwrap();
+    assert!(parsed["dependencies"].get("anyhow").is_some());
+    assert!(!cargo_toml(&env, "counter").contains("anyhow"));
+}
+
+#[test]
+fn add_dependency_to_every_actor() {
+    let (env, mut server) = two_actors();
+
+    server.call_ok(
+        "add-dependency",
+        json!({ "all": true, "dependency": { "name": "log", "version": "0.4" } }),
+    );
+    for actor in ["echo", "counter"] {
+        assert!(cargo_toml(&env, actor).contains("log = \"0.4\""));
+    }
+}
+
+#[test]
+fn add_dependency_errors() {
+    let (env, mut server) = two_actors();
+
+    server.call_err("add-dependency", json!({ "name": "echo" }));
+    server.call_err(
+        "add-dependency",
+        json!({ "name": "echo", "dependency": { "version": "1.0" } }),
+    );
+    server.call_err(
+        "add-dependency",
+        json!({ "name": "missing", "dependency": { "name": "log", "version": "0.4" } }),
+    );
+
+    // Malformed requirements are refused before Cargo.toml is touched
+    let before = cargo_toml(&env, "echo");
+    let text = server.call_err(
+        "add-dependency",
+        json!({ "name": "echo", "dependency": { "name": "log", "version": "^1.x.y" } }),
+    );
+    assert_contains(&text, &["Invalid version requirement '^1.x.y'", "log"]);
+    assert_eq!(cargo_toml(&env, "echo"), before);
+
+    // Crates that can't run in wasm need force
+    let text = server.call_err(
+        "add-dependency",
+        json!({ "name": "echo", "dependency": { "name": "mio", "version": "1" } }),
+    );
+    assert_contains(&text, &["mio"]);
+    assert!(!cargo_toml(&env, "echo").contains("mio"));
+
+    server.call_ok(
+        "add-dependency",
+        json!({
+            "name": "echo",
+            "dependency": { "name": "mio", "version": "1" },
+            "force": true
+        }),
+    );
+    assert!(cargo_toml(&env, "echo").contains("mio = \"1\""));
+
+    // tokio builds for wasm32 with the features that don't need an OS
+    server.call_ok(
+        "add-dependency",
+        json!({
+            "name": "echo",
+            "dependency": { "name": "tokio", "version": "1", "features": ["sync"] }
+        }),
+    );
+    assert!(cargo_toml(&env, "echo").contains("tokio"));
+}
+
+#[test]
+fn add_dependency_replaces_an_existing_version() {
+    let (env, mut server) = two_actors();
+
+    let text = server.call_ok(
+        "add-dependency",
+        json!({ "name": "echo", "dependency": { "name": "serde_json", "version": "0.9" } }),
+    );
+    assert_contains(&text, &["echo: updated (was serde_json: 1.0)"]);
+    assert!(cargo_toml(&env, "echo").contains("serde_json = \"0.9\""));
+}
+
+#[test]
+fn remove_dependency() {
+    let (env, mut server) = two_actors();
+
+    let text = server.call_ok(
+        "remove-dependency",
+        json!({ "actors": ["echo", "counter"], "dependency": "serde_json" }),
+    );
+    assert_contains(&text, &["echo: removed", "counter: removed"]);
+    for actor in ["echo", "counter"] {
+        let parsed: toml::Table = toml::from_str(&cargo_toml(&env, actor)).unwrap();
+        assert!(parsed["dependencies"].get("serde_json").is_none());
+    }
+
+    let output = server.call(
+        "remove-dependency",
+        json!({ "name": "echo", "dependency": "serde_json" }),
+    );
+    assert!(!output.text.contains("echo: removed"), "{}", output.text);
+
+    server.call_err("remove-dependency", json!({ "name": "echo" }));
+}
+
+#[test]
+fn dependency_report_and_align() {
+    let (env, mut server) = two_actors();
+
+    server.call_ok(
+        "add-dependency",
+        json!({
+            "name": "echo",
+            "dependency": { "name": "serde_json", "version": "0.9" }
+        }),
+    );
+
+    let text = server.call_ok("dependency-report", json!({ "drift_only": true }));
+    assert_contains(&text, &["serde_json", "0.9", "1.0"]);
+    assert!(!text.contains("wit-bindgen-rt"), "{}", text);
+
+    let before = cargo_toml(&env, "echo");
+    let text = server.call_ok(
+        "align-dependency",
+        json!({ "dependency": "serde_json", "version": "1.0", "dry_run": true }),
+    );
+    assert_contains(&text, &["0.9"]);
+    assert_eq!(cargo_toml(&env, "echo"), before);
+
+    server.call_ok(
+        "align-dependency",
+        json!({ "dependency": "serde_json", "version": "1.0" }),
+    );
+    assert!(cargo_toml(&env, "echo").contains("serde_json = \"1.0\""));
+
+    let text = server.call_ok("dependency-report", json!({ "drift_only": true }));
+    assert!(!text.contains("serde_json"), "{}", text);
+
+    server.call_err("align-dependency", json!({ "dependency": "serde_json" }));
+
+    let text = server.call_err(
+        "align-dependency",
+        json!({ "dependency": "serde_json", "version": "^1.x.y" }),
+    );
+    assert_contains(&text, &["Invalid version requirement '^1.x.y'"]);
+    assert!(cargo_toml(&env, "echo").contains("serde_json = \"1.0\""));
+}
+
+#[test]
+fn equivalent_version_requirements_are_not_drift() {
+    let (_env, mut server) = two_actors();
+
+    // counter keeps the template's "1.0" and "0.39.0"
+    for (dependency, version) in [("serde_json", "1"), ("wit-bindgen-rt", "^0.39")] {
+        server.call_ok(
+            "add-dependency",
+            json!({
+                "name": "echo",
+                "dependency": { "name": dependency, "version": version }
+            }),
+        );
+    }
+
+    let text = server.call_ok("dependency-report", json!({ "drift_only": true }));
+    assert_contains(&text, &["0 with version drift"]);
+
+    server.call_ok(
+        "add-dependency",
+        json!({
+            "name": "echo",
+            "dependency": { "name": "wit-bindgen-rt", "version": "~0.39.1" }
+        }),
+    );
+    let text = server.call_ok("dependency-report", json!({ "drift_only": true }));
+    assert_contains(&text, &["wit-bindgen-rt (drift)", "~0.39.1", "0.39.0"]);
+}
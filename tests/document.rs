@@ -0,0 +1,121 @@
+mod common;
+
+use actor_registry_mcp::registry::actor::ActorCargoConfig;
+use actor_registry_mcp::registry::config::RegistryConfig;
+use actor_registry_mcp::registry::document::TomlDocument;
+use common::{assert_contains, TestRegistry};
+use serde_json::json;
+use std::fs;
+
+const CARGO_TOML: &str = r#"# The echo actor
+[package]
+name = "echo"
+version = "0.1.0"
+edition = "2021"
+authors = ["someone"]  # not modelled, kept anyway
+
+[lib]
+crate-type = ["cdylib"]
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }  # inline on purpose
+serde_json = "1.0"
+
+[features]
+default = []
+
+[profile.release]
+opt-level = "s"
+"#;
+
+#[test]
+fn cargo_toml_round_trips_unchanged() {
+    let test = TestRegistry::new();
+    let path = test.path().join("Cargo.toml");
+    fs::write(&path, CARGO_TOML).unwrap();
+
+    let config: ActorCargoConfig = toml::from_str(CARGO_TOML).unwrap();
+    config.save(&path).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), CARGO_TOML);
+
+    let mut config = config;
+    config.dependencies.insert("log".to_string(), json!("0.4"));
+    config.save(&path).unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_contains(
+        &content,
+        &[
+            "# The echo actor",
+            "authors = [\"someone\"]  # not modelled, kept anyway",
+            "serde = { version = \"1.0\", features = [\"derive\"] }  # inline on purpose",
+            "log = \"0.4\"",
+            "[features]",
+            "[profile.release]",
+        ],
+    );
+}
+
+#[test]
+fn config_saves_keep_comments() {
+    let test = TestRegistry::new();
+    let path = test.registry.path().join(".registry.config.toml");
+    fs::write(
+        &path,
+        "# Registry settings\ndefault_template = \"basic\"\ndefault_interfaces = [\"ntwk:theater/actor\"]\nbuild_cache_enabled = true # for now\n",
+    )
+    .unwrap();
+
+    let mut config = RegistryConfig::load(&path).unwrap();
+    config.build_cache_enabled = false;
+    config.save(&path).unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_contains(
+        &content,
+        &[
+            "# Registry settings",
+            "build_cache_enabled = false # for now",
+        ],
+    );
+    assert!(!RegistryConfig::load(&path).unwrap().build_cache_enabled);
+}
+
+#[test]
+fn merge_replaces_changed_values_and_drops_removed_keys() {
+    let test = TestRegistry::new();
+    let path = test.path().join("doc.toml");
+    fs::write(&path, "a = 1 # one\nb = \"two\"\n\n[table]\nc = true\n").unwrap();
+
+    let mut doc = TomlDocument::load(&path).unwrap();
+    doc.merge_from(&json!({ "a": 2, "table": { "c": true, "d": "new" } }))
+        .unwrap();
+    doc.save().unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_contains(&content, &["a = 2 # one", "c = true", "d = \"new\""]);
+    assert!(!content.contains("b = "), "{}", content);
+}
+
+#[test]
+fn created_actors_are_written_through_the_document_layer() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    let actor = test.actor_path("echo");
+
+    // Writing a created file back leaves it untouched
+    let cargo_toml = fs::read_to_string(actor.join("Cargo.toml")).unwrap();
+    let config: ActorCargoConfig = toml::from_str(&cargo_toml).unwrap();
+    config.save(actor.join("Cargo.toml")).unwrap();
+    assert_eq!(
+        fs::read_to_string(actor.join("Cargo.toml")).unwrap(),
+        cargo_toml
+    );
+
+    let mut manifest = test.registry.manifest_document("echo").unwrap();
+    manifest.set_component("target/echo.wasm");
+    manifest.save().unwrap();
+
+    let actor = test.registry.find_actor("echo").unwrap();
+    assert_eq!(actor.manifest.unwrap().component, "target/echo.wasm");
+}
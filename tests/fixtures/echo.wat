@@ -0,0 +1,83 @@
+(component
+  (import "ntwk:theater/runtime" (instance $rt
+    (export "log" (func (param "msg" string)))
+    (export "get-chain" (func (result (list string))))
+  ))
+  (core module $shim
+    (type $t (func (param i32 i32)))
+    (table (export "$imports") 1 1 funcref)
+    (func (export "log") (param i32 i32) local.get 0 local.get 1 i32.const 0 call_indirect (type $t))
+  )
+  (core module $fixup
+    (type $t (func (param i32 i32)))
+    (import "" "$imports" (table 1 1 funcref))
+    (import "" "log" (func $f (type $t)))
+    (elem (i32.const 0) func $f)
+  )
+  (core module $m
+    (import "rt" "log" (func $log (param i32 i32)))
+    (memory (export "memory") 1)
+    (global $bump (mut i32) (i32.const 4096))
+    (data (i32.const 100) "initialized")
+    (data (i32.const 120) "boom")
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $p i32)
+      global.get $bump
+      local.get 2 i32.add i32.const 1 i32.sub
+      i32.const 0 local.get 2 i32.sub i32.and
+      local.tee $p
+      local.get 3 i32.add global.set $bump
+      local.get $p)
+    (func (export "init") (param $sd i32) (param $sp i32) (param $sl i32) (param $pp i32) (param $pl i32) (result i32)
+      i32.const 100 i32.const 11 call $log
+      local.get $pp local.get $pl call $log
+      (i32.store8 (i32.const 1024) (i32.const 0))
+      (i32.store8 (i32.const 1028) (local.get $sd))
+      (i32.store (i32.const 1032) (local.get $sp))
+      (i32.store (i32.const 1036) (local.get $sl))
+      i32.const 1024)
+    (func (export "handle-send") (param $sd i32) (param $sp i32) (param $sl i32) (param $dp i32) (param $dl i32) (result i32)
+      local.get $dp local.get $dl call $log
+      (i32.store8 (i32.const 1024) (i32.const 0))
+      (i32.store8 (i32.const 1028) (i32.const 1))
+      (i32.store (i32.const 1032) (local.get $dp))
+      (i32.store (i32.const 1036) (local.get $dl))
+      i32.const 1024)
+    (func (export "handle-request") (param $sd i32) (param $sp i32) (param $sl i32) (param $ip i32) (param $il i32) (param $dp i32) (param $dl i32) (result i32)
+      local.get $ip local.get $il call $log
+      (if (i32.eq (i32.load8_u (local.get $dp)) (i32.const 120))
+        (then
+          (i32.store8 (i32.const 1024) (i32.const 1))
+          (i32.store (i32.const 1028) (i32.const 120))
+          (i32.store (i32.const 1032) (i32.const 4))
+          (return (i32.const 1024))))
+      (if (i32.eq (i32.load8_u (local.get $dp)) (i32.const 108))
+        (then (loop $l (br $l))))
+      (i32.store8 (i32.const 1024) (i32.const 0))
+      (i32.store8 (i32.const 1028) (local.get $sd))
+      (i32.store (i32.const 1032) (local.get $sp))
+      (i32.store (i32.const 1036) (local.get $sl))
+      (i32.store8 (i32.const 1040) (i32.const 1))
+      (i32.store (i32.const 1044) (local.get $dp))
+      (i32.store (i32.const 1048) (local.get $dl))
+      i32.const 1024)
+  )
+  (core instance $shim_i (instantiate $shim))
+  (core instance $main (instantiate $m (with "rt" (instance (export "log" (func $shim_i "log"))))))
+  (alias core export $main "memory" (core memory $mem))
+  (alias core export $main "realloc" (core func $realloc))
+  (alias export $rt "log" (func $rt_log))
+  (core func $log_lowered (canon lower (func $rt_log) (memory $mem)))
+  (core instance (instantiate $fixup (with "" (instance (export "$imports" (table $shim_i "$imports")) (export "log" (func $log_lowered))))))
+  (type $state (option (list u8)))
+  (func $init (param "state" $state) (param "params" (tuple string)) (result (result (tuple $state) (error string)))
+    (canon lift (core func $main "init") (memory $mem) (realloc $realloc)))
+  (func $send (param "state" $state) (param "params" (tuple (list u8))) (result (result (tuple $state) (error string)))
+    (canon lift (core func $main "handle-send") (memory $mem) (realloc $realloc)))
+  (func $request (param "state" $state) (param "params" (tuple string (list u8))) (result (result (tuple $state (tuple $state)) (error string)))
+    (canon lift (core func $main "handle-request") (memory $mem) (realloc $realloc)))
+  (instance $actor (export "init" (func $init)))
+  (instance $msg (export "handle-send" (func $send)) (export "handle-request" (func $request)))
+  (export "ntwk:theater/actor" (instance $actor))
+  (export "ntwk:theater/message-server-client" (instance $msg))
+)
@@ -0,0 +1,193 @@
+mod common;
+
+use common::{assert_contains, McpServer, TestEnv};
+use git2::{Repository, Signature};
+use serde_json::json;
+use std::fs;
+
+/// A registry that is a git repository with one commit holding a README
+/// outside any actor, and an uncommitted `echo` actor
+fn git_env() -> (TestEnv, McpServer, Repository) {
+    let env = TestEnv::new();
+    let repo = Repository::init(&env.registry).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    fs::write(env.registry.join("README.md"), "# actors\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(std::path::Path::new("README.md")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = Signature::now("Test User", "test@example.com").unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Initial commit",
+        &tree,
+        &[],
+    )
+    .unwrap();
+    drop(tree);
+
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+    (env, server, repo)
+}
+
+fn head_summary(repo: &Repository) -> String {
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    head.summary().unwrap().to_string()
+}
+
+#[test]
+fn changes_and_commits_are_scoped_to_the_actor() {
+    let (env, mut server, repo) = git_env();
+
+    let text = server.call_ok("get-actor-changes", json!({ "name": "echo" }));
+    assert_contains(
+        &text,
+        &["Not staged", "manifest.toml (added)", "src/lib.rs (added)"],
+    );
+
+    // An edit elsewhere in the repository must stay out of the commit
+    fs::write(env.registry.join("README.md"), "# actors, edited\n").unwrap();
+    let text = server.call_ok("get-actor-changes", json!({ "name": "echo" }));
+    assert!(!text.contains("modified"), "{}", text);
+
+    let text = server.call_ok("commit-actor", json!({ "name": "echo" }));
+    assert_contains(
+        &text,
+        &[
+            "Add actor echo",
+            "Author: Test User",
+            "- added manifest.toml",
+        ],
+    );
+    assert_eq!(head_summary(&repo), "Add actor echo");
+
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    let tree = head.tree().unwrap();
+    assert!(tree.get_path("echo/Cargo.toml".as_ref()).is_ok());
+    let readme = repo
+        .find_blob(tree.get_path("README.md".as_ref()).unwrap().id())
+        .unwrap();
+    assert_eq!(readme.content(), b"# actors\n");
+    assert!(repo
+        .statuses(None)
+        .unwrap()
+        .iter()
+        .any(|s| s.path() == Some("README.md")));
+
+    let text = server.call_ok("get-actor-changes", json!({ "name": "echo" }));
+    assert_contains(&text, &["no uncommitted changes"]);
+    let text = server.call_err("commit-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["No changes to commit"]);
+
+    fs::write(env.actor_path("echo").join("src/lib.rs"), "// rewritten\n").unwrap();
+    let text = server.call_ok("get-actor-changes", json!({ "name": "echo", "diff": true }));
+    assert_contains(&text, &["src/lib.rs (modified)", "Diff:", "+// rewritten"]);
+
+    server.call_ok(
+        "commit-actor",
+        json!({ "name": "echo", "message": "Rewrite echo by hand" }),
+    );
+    assert_eq!(head_summary(&repo), "Rewrite echo by hand");
+}
+
+#[test]
+fn generated_messages_describe_the_change() {
+    let (env, mut server, repo) = git_env();
+    server.call_ok("commit-actor", json!({ "name": "echo" }));
+
+    server.call_ok(
+        "add-dependency",
+        json!({ "name": "echo", "dependency": { "name": "log", "version": "0.4" } }),
+    );
+    server.call_ok("commit-actor", json!({ "name": "echo" }));
+    assert_eq!(head_summary(&repo), "Update dependencies of echo");
+
+    server.call_ok(
+        "update-manifest",
+        json!({ "name": "echo", "version": "0.2.0" }),
+    );
+    let cargo = env.actor_path("echo").join("Cargo.toml");
+    let content = fs::read_to_string(&cargo).unwrap();
+    fs::write(&cargo, content.replace("0.1.0", "0.2.0")).unwrap();
+    server.call_ok("commit-actor", json!({ "name": "echo" }));
+    assert_eq!(head_summary(&repo), "Release echo 0.2.0 (from 0.1.0)");
+
+    fs::write(env.actor_path("echo").join("src/lib.rs"), "// edited\n").unwrap();
+    server.call_ok("commit-actor", json!({ "name": "echo" }));
+    assert_eq!(head_summary(&repo), "Update echo: src/lib.rs");
+}
+
+#[test]
+fn history_and_release_tags() {
+    let (env, mut server, _repo) = git_env();
+    env.create_actor(&mut server, "other");
+
+    let text = server.call_ok("get-actor-history", json!({ "name": "echo" }));
+    assert_contains(&text, &["no commits yet"]);
+
+    server.call_ok("commit-actor", json!({ "name": "other" }));
+    server.call_ok("commit-actor", json!({ "name": "echo" }));
+
+    let text = server.call_ok("tag-actor-release", json!({ "name": "echo" }));
+    assert_contains(&text, &["Tagged echo@0.1.0", "Add actor echo"]);
+    assert!(!text.contains("uncommitted"), "{}", text);
+
+    let text = server.call_ok("get-actor-history", json!({ "name": "echo" }));
+    assert_contains(
+        &text,
+        &["1 commit(s)", "Test User: Add actor echo [echo@0.1.0]"],
+    );
+    assert!(!text.contains("other"), "{}", text);
+    assert!(!text.contains("Initial commit"), "{}", text);
+
+    let text = server.call_err("tag-actor-release", json!({ "name": "echo" }));
+    assert_contains(&text, &["already exists"]);
+
+    fs::write(env.actor_path("echo").join("src/lib.rs"), "// edited\n").unwrap();
+    let text = server.call_ok(
+        "tag-actor-release",
+        json!({ "name": "echo@0.1.0", "force": true }),
+    );
+    assert_contains(&text, &["Tagged echo@0.1.0", "uncommitted changes"]);
+
+    server.call_ok("commit-actor", json!({ "name": "echo" }));
+    let text = server.call_ok("get-actor-history", json!({ "name": "echo", "limit": 1 }));
+    assert_contains(&text, &["1 commit(s)", "Update echo: src/lib.rs"]);
+}
+
+#[test]
+fn git_tool_errors() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let text = server.call_err("get-actor-changes", json!({ "name": "echo" }));
+    assert_contains(&text, &["not in a git repository"]);
+    server.call_err("commit-actor", json!({ "name": "echo" }));
+
+    Repository::init(&env.registry).unwrap();
+    let text = server.call_err("tag-actor-release", json!({ "name": "echo" }));
+    assert_contains(&text, &["no commits"]);
+    server.call_err(
+        "tag-actor-release",
+        json!({ "name": "echo", "version": "not-a-version" }),
+    );
+    server.call_err("commit-actor", json!({ "name": "missing" }));
+    server.call_err("get-actor-history", json!({}));
+
+    // A malformed reference is a failed call, not a protocol error
+    let result = server
+        .request(
+            "tools/call",
+            json!({ "name": "tag-actor-release", "arguments": { "name": "echo@latest" } }),
+        )
+        .unwrap();
+    assert_eq!(result["isError"], json!(true), "{}", result);
+    assert_contains(&result.to_string(), &["Invalid version"]);
+}
@@ -0,0 +1,289 @@
+mod bindings;
+
+use crate::bindings::exports::ntwk::theater::actor::Guest;
+use crate::bindings::exports::ntwk::theater::message_server_client::Guest as MessageServerClient;
+use crate::bindings::exports::ntwk::theater::supervisor::Guest as SupervisorGuest;
+use crate::bindings::ntwk::theater::runtime::log;
+use crate::bindings::ntwk::theater::supervisor as supervisor_host;
+use crate::bindings::ntwk::theater::types::State;
+
+struct Component;
+
+impl Guest for Component {
+    fn init(_state: State, params: (String,)) -> Result<(State,), String> {
+        log("Initializing echo supervisor actor");
+        let (param,) = params;
+        log(&format!("Init parameter: {}", param));
+
+        // Store initial state
+        let state_data = serde_json::json!({
+            "children": [],
+            "parameters": param,
+        });
+        
+        let state_bytes = serde_json::to_vec(&state_data)
+            .map_err(|e| format!("Failed to serialize state: {}", e))?;
+
+        Ok((Some(state_bytes),))
+    }
+}
+
+impl SupervisorGuest for Component {
+    fn handle_spawn_child(
+        state: Option<Vec<u8>>,
+        params: (String, Option<String>, Option<Vec<u8>>),
+    ) -> Result<(Option<Vec<u8>>, (String,)), String> {
+        let (manifest_path, id, init_state) = params;
+        log(&format!("Spawning child from manifest: {}", manifest_path));
+        
+        // Spawn the child actor
+        let child_id = supervisor_host::spawn_child(
+            &manifest_path,
+            id.as_deref(),
+            init_state.as_deref(),
+        )?;
+        
+        log(&format!("Spawned child with ID: {}", child_id));
+        
+        // Update state to track the new child
+        let mut state_data: serde_json::Value = if let Some(state_bytes) = state {
+            serde_json::from_slice(&state_bytes)
+                .map_err(|e| format!("Failed to deserialize state: {}", e))?
+        } else {
+            serde_json::json!({ "children": [] })
+        };
+        
+        // Add the new child to the list
+        if let Some(children) = state_data.get_mut("children").and_then(|c| c.as_array_mut()) {
+            children.push(serde_json::json!({
+                "id": child_id,
+                "manifest": manifest_path,
+                "status": "spawned"
+            }));
+        }
+        
+        let updated_state = serde_json::to_vec(&state_data)
+            .map_err(|e| format!("Failed to serialize updated state: {}", e))?;
+        
+        Ok((Some(updated_state), (child_id,)))
+    }
+
+    fn handle_stop_child(
+        state: Option<Vec<u8>>,
+        params: (String,),
+    ) -> Result<(Option<Vec<u8>>,), String> {
+        let (child_id,) = params;
+        log(&format!("Stopping child: {}", child_id));
+        
+        // Stop the child actor
+        supervisor_host::stop_child(&child_id)?;
+        
+        // Update state to mark the child as stopped
+        let mut state_data: serde_json::Value = if let Some(state_bytes) = state {
+            serde_json::from_slice(&state_bytes)
+                .map_err(|e| format!("Failed to deserialize state: {}", e))?
+        } else {
+            serde_json::json!({ "children": [] })
+        };
+        
+        // Update the child's status
+        if let Some(children) = state_data.get_mut("children").and_then(|c| c.as_array_mut()) {
+            for child in children {
+                if let Some(id) = child.get("id").and_then(|id| id.as_str()) {
+                    if id == child_id {
+                        if let Some(status) = child.get_mut("status") {
+                            *status = serde_json::json!("stopped");
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        
+        let updated_state = serde_json::to_vec(&state_data)
+            .map_err(|e| format!("Failed to serialize updated state: {}", e))?;
+        
+        Ok((Some(updated_state),))
+    }
+
+    fn handle_restart_child(
+        state: Option<Vec<u8>>,
+        params: (String,),
+    ) -> Result<(Option<Vec<u8>>,), String> {
+        let (child_id,) = params;
+        log(&format!("Restarting child: {}", child_id));
+        
+        // Restart the child actor
+        supervisor_host::restart_child(&child_id)?;
+        
+        // Update state to mark the child as restarted
+        let mut state_data: serde_json::Value = if let Some(state_bytes) = state {
+            serde_json::from_slice(&state_bytes)
+                .map_err(|e| format!("Failed to deserialize state: {}", e))?
+        } else {
+            serde_json::json!({ "children": [] })
+        };
+        
+        // Update the child's status
+        if let Some(children) = state_data.get_mut("children").and_then(|c| c.as_array_mut()) {
+            for child in children {
+                if let Some(id) = child.get("id").and_then(|id| id.as_str()) {
+                    if id == child_id {
+                        if let Some(status) = child.get_mut("status") {
+                            *status = serde_json::json!("running");
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        
+        let updated_state = serde_json::to_vec(&state_data)
+            .map_err(|e| format!("Failed to serialize updated state: {}", e))?;
+        
+        Ok((Some(updated_state),))
+    }
+
+    fn handle_list_children(
+        state: Option<Vec<u8>>,
+        _params: (),
+    ) -> Result<(Option<Vec<u8>>, (Vec<String>,)), String> {
+        log("Listing children");
+        
+        // Get all child actors from the supervisor
+        let children = supervisor_host::list_children()?;
+        log(&format!("Found {} children", children.len()));
+        
+        Ok((state, (children,)))
+    }
+
+    fn handle_get_child_status(
+        state: Option<Vec<u8>>,
+        params: (String,),
+    ) -> Result<(Option<Vec<u8>>, (String,)), String> {
+        let (child_id,) = params;
+        log(&format!("Getting status for child: {}", child_id));
+        
+        // Get the child's status
+        let status = supervisor_host::get_child_status(&child_id)?;
+        log(&format!("Child status: {}", status));
+        
+        Ok((state, (status,)))
+    }
+
+    fn handle_get_child_state(
+        state: Option<Vec<u8>>,
+        params: (String,),
+    ) -> Result<(Option<Vec<u8>>, (Option<Vec<u8>>,)), String> {
+        let (child_id,) = params;
+        log(&format!("Getting state for child: {}", child_id));
+        
+        // Get the child's state
+        let child_state = supervisor_host::get_child_state(&child_id)?;
+        
+        Ok((state, (child_state,)))
+    }
+
+    fn handle_update_child_state(
+        state: Option<Vec<u8>>,
+        params: (String, Option<Vec<u8>>),
+    ) -> Result<(Option<Vec<u8>>,), String> {
+        let (child_id, new_state) = params;
+        log(&format!("Updating state for child: {}", child_id));
+        
+        // Update the child's state
+        supervisor_host::update_child_state(&child_id, new_state.as_deref())?;
+        log("Child state updated");
+        
+        Ok((state,))
+    }
+
+    fn handle_get_child_events(
+        state: Option<Vec<u8>>,
+        params: (String, u32),
+    ) -> Result<(Option<Vec<u8>>, (Vec<String>,)), String> {
+        let (child_id, limit) = params;
+        log(&format!("Getting events for child: {}, limit: {}", child_id, limit));
+        
+        // Get the child's events
+        let events = supervisor_host::get_child_events(&child_id, limit)?;
+        log(&format!("Retrieved {} events", events.len()));
+        
+        Ok((state, (events,)))
+    }
+}
+
+impl MessageServerClient for Component {
+    fn handle_send(
+        state: Option<Vec<u8>>,
+        params: (Vec<u8>,),
+    ) -> Result<(Option<Vec<u8>>,), String> {
+        log("Handling send message");
+        let (data,) = params;
+        log(&format!("Received data: {:?}", data));
+        Ok((state,))
+    }
+
+    fn handle_request(
+        state: Option<Vec<u8>>,
+        params: (String, Vec<u8>),
+    ) -> Result<(Option<Vec<u8>>, (Option<Vec<u8>>,)), String> {
+        log("Handling request message");
+        let (request_id, data) = params;
+        log(&format!(
+            "[req id] {} [data] {}",
+            request_id,
+            String::from_utf8(data.clone()).expect("Failed to convert data to string")
+        ));
+
+        Ok((state, (Some(data),)))
+    }
+
+    fn handle_channel_open(
+        state: Option<bindings::exports::ntwk::theater::message_server_client::Json>,
+        params: (bindings::exports::ntwk::theater::message_server_client::Json,),
+    ) -> Result<
+        (
+            Option<bindings::exports::ntwk::theater::message_server_client::Json>,
+            (bindings::exports::ntwk::theater::message_server_client::ChannelAccept,),
+        ),
+        String,
+    > {
+        log("Handling channel open message");
+        log(&format!("Channel open message: {:?}", params));
+        Ok((
+            state,
+            (
+                bindings::exports::ntwk::theater::message_server_client::ChannelAccept {
+                    accepted: true,
+                    message: None,
+                },
+            ),
+        ))
+    }
+
+    fn handle_channel_close(
+        state: Option<bindings::exports::ntwk::theater::message_server_client::Json>,
+        params: (String,),
+    ) -> Result<(Option<bindings::exports::ntwk::theater::message_server_client::Json>,), String>
+    {
+        log("Handling channel close message");
+        log(&format!("Channel close message: {:?}", params));
+        Ok((state,))
+    }
+
+    fn handle_channel_message(
+        state: Option<bindings::exports::ntwk::theater::message_server_client::Json>,
+        params: (
+            String,
+            bindings::exports::ntwk::theater::message_server_client::Json,
+        ),
+    ) -> Result<(Option<bindings::exports::ntwk::theater::message_server_client::Json>,), String>
+    {
+        log("Received channel message");
+        log(&format!("Channel message: {:?}", params));
+        Ok((state,))
+    }
+}
+
+bindings::export!(Component with_types_in bindings);
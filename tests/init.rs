@@ -0,0 +1,149 @@
+mod common;
+
+use actor_registry_mcp::registry::init::{self, InitOptions, StepStatus};
+use actor_registry_mcp::registry::Registry;
+use common::{assert_contains, cli, run_err, run_ok, TestEnv};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn status(report: &init::InitReport, step: &str) -> StepStatus {
+    report
+        .steps
+        .iter()
+        .find(|s| s.name == step)
+        .unwrap_or_else(|| panic!("no step '{}' in {:?}", step, report))
+        .status
+        .clone()
+}
+
+fn wit_source(dir: &Path) -> PathBuf {
+    let wit = dir.join("theater-wit");
+    fs::create_dir_all(wit.join("deps")).unwrap();
+    fs::write(wit.join("actor.wit"), "package ntwk:theater;\n").unwrap();
+    fs::write(wit.join("deps/runtime.wit"), "interface runtime {}\n").unwrap();
+    wit
+}
+
+#[test]
+fn init_sets_up_a_registry_and_is_idempotent() {
+    let dir = tempfile::tempdir().unwrap();
+    let wit = wit_source(dir.path());
+    let target = dir.path().join("registry");
+    let options = InitOptions {
+        wit_source: Some(wit.clone()),
+        git: true,
+    };
+
+    let report = init::init_registry(&target, &options).unwrap();
+    assert!(!report.unchanged());
+    assert_eq!(status(&report, "registry directory"), StepStatus::Created);
+    assert_eq!(status(&report, "config"), StepStatus::Created);
+    assert_eq!(status(&report, "WIT files"), StepStatus::Created);
+    assert_eq!(status(&report, "git repository"), StepStatus::Created);
+    assert!(target.join(".git").is_dir());
+    assert!(target.join("wit/deps/runtime.wit").is_file());
+
+    // New actors copy the vendored WIT files
+    let config = fs::read_to_string(target.join(".registry.config.toml")).unwrap();
+    assert_contains(&config, &["wit_source = \"wit\"", "# [[remotes]]"]);
+    let registry = Registry::new(&target).unwrap();
+    assert_eq!(registry.wit_source(), Some(target.join("wit")));
+
+    let gitignore = fs::read_to_string(target.join(".gitignore")).unwrap();
+    for entry in init::GITIGNORE_ENTRIES {
+        assert!(
+            gitignore.lines().any(|line| line == *entry),
+            "{}",
+            gitignore
+        );
+    }
+
+    // Running it again leaves hand edits alone
+    fs::write(
+        target.join(".registry.config.toml"),
+        format!("{}\n# edited by hand\n", config),
+    )
+    .unwrap();
+    fs::write(target.join(".gitignore"), "target\n/notes\n").unwrap();
+    let report = init::init_registry(&target, &options).unwrap();
+    assert_eq!(status(&report, "config"), StepStatus::Unchanged);
+    assert_eq!(status(&report, "WIT files"), StepStatus::Unchanged);
+    assert_eq!(status(&report, "git repository"), StepStatus::Unchanged);
+    assert_eq!(status(&report, ".gitignore"), StepStatus::Updated);
+    assert_contains(
+        &fs::read_to_string(target.join(".registry.config.toml")).unwrap(),
+        &["# edited by hand"],
+    );
+    let gitignore = fs::read_to_string(target.join(".gitignore")).unwrap();
+    assert!(gitignore.starts_with("target\n/notes\n"), "{}", gitignore);
+    assert!(!gitignore.contains("target/"), "{}", gitignore);
+
+    // Changed WIT files are vendored again
+    fs::write(wit.join("actor.wit"), "package ntwk:theater@0.2.0;\n").unwrap();
+    let report = init::init_registry(&target, &options).unwrap();
+    assert_eq!(status(&report, "WIT files"), StepStatus::Updated);
+    let report = init::init_registry(
+        &target,
+        &InitOptions {
+            wit_source: None,
+            git: true,
+        },
+    )
+    .unwrap();
+    assert!(report.unchanged(), "{:?}", report);
+}
+
+#[test]
+fn init_refuses_files_and_missing_wit_sources() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let file = dir.path().join("not-a-dir");
+    fs::write(&file, "").unwrap();
+    let err = init::init_registry(&file, &InitOptions::default()).unwrap_err();
+    assert_contains(&err.to_string(), &["not a directory"]);
+
+    let options = InitOptions {
+        wit_source: Some(dir.path().join("no-wit")),
+        git: false,
+    };
+    let err = init::init_registry(&dir.path().join("registry"), &options).unwrap_err();
+    assert_contains(&err.to_string(), &["WIT source is not a directory"]);
+
+    // Without git the registry is still usable
+    let report = init::init_registry(
+        &dir.path().join("plain"),
+        &InitOptions {
+            wit_source: None,
+            git: false,
+        },
+    )
+    .unwrap();
+    assert!(matches!(
+        status(&report, "git repository"),
+        StepStatus::Skipped(_)
+    ));
+    assert!(!dir.path().join("plain/.git").exists());
+    assert!(Registry::new(dir.path().join("plain")).is_ok());
+}
+
+#[test]
+fn init_command_creates_the_registry_it_is_pointed_at() {
+    let env = TestEnv::new();
+    let target = env.path().join("new-registry");
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env("THEATER_ACTORS_PATH", &target);
+    assert_contains(&run_err(cmd), &["run `init` to create it"]);
+
+    let mut cmd = cli(&env, &["init", "--no-git"]);
+    cmd.env("THEATER_ACTORS_PATH", &target);
+    assert_contains(&run_ok(cmd), &["Initialized registry", "- config: created"]);
+    assert!(target.join(".registry.config.toml").is_file());
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env("THEATER_ACTORS_PATH", &target);
+    run_ok(cmd);
+
+    let stdout = run_ok(cli(&env, &["init", target.to_str().unwrap(), "--no-git"]));
+    assert_contains(&stdout, &["already set up"]);
+}
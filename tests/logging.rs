@@ -0,0 +1,182 @@
+mod common;
+
+use common::{assert_contains, cli, run_ok, TestEnv};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+fn read_log(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("No log at {:?}: {}", path, e))
+}
+
+fn is_json_lines(log: &str) -> bool {
+    !log.is_empty()
+        && log
+            .lines()
+            .all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok())
+}
+
+/// Names of the files in `dir`
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn logs_go_to_the_state_directory_and_never_to_stdout() {
+    let env = TestEnv::new();
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env("RUST_LOG", "debug");
+    let stdout = run_ok(cmd);
+    assert!(!stdout.contains("DEBUG"), "{}", stdout);
+
+    let log = read_log(&env.path().join("state/actor-registry/actor-registry.log"));
+    assert_contains(&log, &["DEBUG", "Logging initialized to"]);
+    assert!(!is_json_lines(&log), "{}", log);
+}
+
+#[test]
+fn logging_flags_beat_env_beats_registry_config() {
+    let env = TestEnv::new();
+    let logs = env.path().join("logs");
+    fs::write(
+        env.registry.join(".registry.config.toml"),
+        format!(
+            "[logging]\nfile = {:?}\nformat = \"json\"\nfilter = \"debug\"\n",
+            logs.join("config.log").to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    run_ok(cli(&env, &["list"]));
+    let log = read_log(&logs.join("config.log"));
+    assert!(is_json_lines(&log), "{}", log);
+    assert_contains(&log, &["\"level\":\"DEBUG\""]);
+
+    let with_env = |args: &[&str]| {
+        let mut cmd = cli(&env, args);
+        cmd.env("ACTOR_REGISTRY_LOG_FILE", logs.join("env.log"))
+            .env("ACTOR_REGISTRY_LOG_FORMAT", "text");
+        cmd
+    };
+
+    run_ok(with_env(&["list"]));
+    let log = read_log(&logs.join("env.log"));
+    assert!(!is_json_lines(&log), "{}", log);
+    assert_contains(&log, &["DEBUG"]);
+
+    let flag_log = logs.join("flag.log");
+    run_ok(with_env(&[
+        "--log-file",
+        flag_log.to_str().unwrap(),
+        "--log-format",
+        "json",
+        "--log-filter",
+        "warn",
+        "list",
+    ]));
+    // Nothing at warn level happens, but the file is still created
+    let log = read_log(&flag_log);
+    assert!(!log.contains("DEBUG"), "{}", log);
+}
+
+#[test]
+fn rotation_dates_the_log_file() {
+    let env = TestEnv::new();
+
+    for (rotation, format) in [("daily", "%Y-%m-%d"), ("hourly", "%Y-%m-%d-%H")] {
+        let dir = env.path().join(rotation);
+        let log = dir.join("rotating.log");
+        run_ok(cli(
+            &env,
+            &[
+                "--log-file",
+                log.to_str().unwrap(),
+                "--log-rotation",
+                rotation,
+                "list",
+            ],
+        ));
+
+        let names = file_names(&dir);
+        assert_eq!(names.len(), 1, "{:?}", names);
+        let suffix = names[0]
+            .strip_prefix("rotating.log.")
+            .unwrap_or_else(|| panic!("{} log is not dated: {:?}", rotation, names));
+        let date_format = format.replace("-%H", "");
+        let date = suffix.get(..10).unwrap();
+        assert!(
+            chrono::NaiveDate::parse_from_str(date, &date_format).is_ok(),
+            "{} log has an unexpected suffix: {}",
+            rotation,
+            suffix
+        );
+        assert_eq!(suffix.len(), if rotation == "hourly" { 13 } else { 10 });
+    }
+
+    // Without rotation the file keeps its name
+    let plain = env.path().join("plain/registry.log");
+    run_ok(cli(&env, &["--log-file", plain.to_str().unwrap(), "list"]));
+    assert_eq!(file_names(plain.parent().unwrap()), ["registry.log"]);
+}
+
+#[test]
+fn broken_logging_settings_fall_back() {
+    let env = TestEnv::new();
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env("RUST_LOG", "info,actor_registry_mcp=loud");
+    let (ok, _, stderr) = common::run(cmd);
+    assert!(ok, "{}", stderr);
+    assert_contains(&stderr, &["Invalid log filter", "using 'info'"]);
+
+    // A log file that can't be created sends records to stderr
+    let blocker = env.path().join("not-a-dir");
+    fs::write(&blocker, "").unwrap();
+    let mut cmd = cli(
+        &env,
+        &[
+            "--log-file",
+            blocker.join("registry.log").to_str().unwrap(),
+            "list",
+        ],
+    );
+    cmd.env("RUST_LOG", "debug");
+    let (ok, stdout, stderr) = common::run(cmd);
+    assert!(ok, "{}", stderr);
+    assert_contains(
+        &stderr,
+        &["logging to stderr", "Logging initialized to stderr"],
+    );
+    assert!(!stdout.contains("DEBUG"), "{}", stdout);
+}
+
+#[test]
+fn log_records_are_forwarded_to_the_client_on_request() {
+    let env = TestEnv::new().with_env("ACTOR_REGISTRY_LOG_TO_CLIENT", "true");
+    let mut server = env.server();
+    env.create_actor(&mut server, "echo");
+
+    let notification = server.wait_for_notification("notifications/message", |params| {
+        params["data"]["message"]
+            .as_str()
+            .is_some_and(|m| m.starts_with("Actor 'echo' created"))
+    });
+    assert_eq!(notification["params"]["level"], "info");
+    assert_eq!(
+        notification["params"]["logger"],
+        "actor_registry_mcp::registry::actor"
+    );
+
+    // Responses still arrive whole between notifications
+    server.call_ok("list-actors-in-registry", json!({}));
+
+    // Only the MCP server forwards; other commands keep stdout for output
+    let stdout = run_ok(cli(&env, &["--log-to-client", "list"]));
+    assert!(!stdout.contains("notifications/message"), "{}", stdout);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use actor_registry_mcp::registry::manifest::{self, ManifestField};
+use common::{assert_contains, TestRegistry};
+use serde_json::json;
+use std::fs;
+
+const MANIFEST: &str = r#"# Hand-written, keep the comments
+name = "echo"
+version = "0.1.0"
+component = ""
+description = "Echoes what it is sent"   # short one
+save_chain = true
+
+# Handlers the actor needs
+[[handler]]
+type = "runtime"
+
+[handler.config]
+"#;
+
+fn write_manifest(test: &TestRegistry) {
+    test.create_actor("echo");
+    fs::write(test.actor_path("echo").join("manifest.toml"), MANIFEST).unwrap();
+}
+
+#[test]
+fn edits_round_trip_without_losing_formatting() {
+    let test = TestRegistry::new();
+    write_manifest(&test);
+
+    let mut doc = test.registry.manifest_document("echo").unwrap();
+    doc.set_field(ManifestField::Version, &json!("0.2.0"))
+        .unwrap();
+    doc.set_field(ManifestField::SaveChain, &json!(false))
+        .unwrap();
+    doc.set_field(ManifestField::LongDescription, &json!("Sends it back"))
+        .unwrap();
+    let handler = manifest::parse_handler(&json!({ "type": "http-client", "config": {} })).unwrap();
+    doc.add_handler(&handler).unwrap();
+    doc.save().unwrap();
+
+    let content = fs::read_to_string(test.actor_path("echo").join("manifest.toml")).unwrap();
+    assert_contains(
+        &content,
+        &[
+            "# Hand-written, keep the comments",
+            "description = \"Echoes what it is sent\"   # short one",
+            "# Handlers the actor needs",
+            "version = \"0.2.0\"",
+            "save_chain = false",
+            "long_description = \"Sends it back\"",
+        ],
+    );
+
+    // What was saved is what Theater reads back
+    let reloaded = test.registry.manifest_document("echo").unwrap();
+    assert_eq!(reloaded.to_string(), content);
+    let parsed = reloaded.manifest().unwrap();
+    assert_eq!(parsed.version, "0.2.0");
+    assert_eq!(parsed.save_chain, Some(false));
+    assert_eq!(parsed.handlers.len(), 2);
+
+    let mut doc = reloaded;
+    assert_eq!(doc.remove_handlers("http-client"), 1);
+    assert_eq!(doc.remove_handlers("http-client"), 0);
+    doc.set_field(ManifestField::LongDescription, &json!(null))
+        .unwrap();
+    doc.save().unwrap();
+
+    let content = fs::read_to_string(test.actor_path("echo").join("manifest.toml")).unwrap();
+    assert_contains(
+        &content,
+        &["# Handlers the actor needs", "type = \"runtime\""],
+    );
+    assert!(!content.contains("http-client"), "{}", content);
+    assert!(!content.contains("long_description"), "{}", content);
+}
+
+#[test]
+fn invalid_edits_are_rejected() {
+    let test = TestRegistry::new();
+    write_manifest(&test);
+    let mut doc = test.registry.manifest_document("echo").unwrap();
+
+    let err = doc
+        .set_field(ManifestField::Version, &json!(" "))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["version cannot be empty"]);
+
+    let err = doc
+        .set_field(ManifestField::Version, &json!(null))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["must be a string"]);
+
+    let err = doc
+        .set_field(ManifestField::SaveChain, &json!("yes"))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["must be a boolean"]);
+
+    let err = manifest::parse_handler(&json!({ "type": "no-such-handler" })).unwrap_err();
+    assert_contains(&err.to_string(), &["Invalid handler configuration"]);
+
+    // Nothing was written
+    let content = fs::read_to_string(test.actor_path("echo").join("manifest.toml")).unwrap();
+    assert_eq!(content, MANIFEST);
+
+    let err = test.registry.manifest_document("missing").unwrap_err();
+    assert_contains(&err.to_string(), &["missing"]);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use actor_registry_mcp::registry::migrations::{self, CURRENT_SCHEMA_VERSION};
+use actor_registry_mcp::registry::Registry;
+use common::{assert_contains, cli, run_err, run_ok, TestEnv};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const V1: &str = "# hand-written before schema versions\ndefault_template = \"http\"\n";
+
+fn backups(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with(".registry.config.toml.v1-") && name.ends_with(".bak")
+        })
+        .collect()
+}
+
+#[test]
+fn unversioned_files_are_migrated_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join(".registry.config.toml");
+    fs::write(&config_path, V1).unwrap();
+
+    let report = migrations::migrate_file(&config_path).unwrap().unwrap();
+    assert_eq!(report.from, 1);
+    assert_eq!(report.to, CURRENT_SCHEMA_VERSION);
+    assert_eq!(report.applied, ["record the schema version"]);
+    assert_eq!(fs::read_to_string(&report.backup).unwrap(), V1);
+
+    // Comments and values survive
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert_contains(
+        &config,
+        &[
+            "# hand-written before schema versions",
+            "default_template = \"http\"",
+            "schema_version = 2",
+        ],
+    );
+
+    // A current file is left alone
+    assert!(migrations::migrate_file(&config_path).unwrap().is_none());
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), config);
+    assert_eq!(backups(dir.path()).len(), 1);
+}
+
+#[test]
+fn newer_or_malformed_versions_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join(".registry.config.toml");
+
+    fs::write(&config_path, "schema_version = 99\n").unwrap();
+    let err = migrations::migrate_file(&config_path).unwrap_err();
+    assert_contains(
+        &err.to_string(),
+        &["schema version 99", "upgrade the actor registry"],
+    );
+
+    fs::write(&config_path, "schema_version = \"two\"\n").unwrap();
+    let err = migrations::migrate_file(&config_path).unwrap_err();
+    assert_contains(&format!("{:#}", err), &["must be a positive integer"]);
+
+    // Nothing was backed up or rewritten
+    assert!(backups(dir.path()).is_empty());
+    assert_eq!(
+        fs::read_to_string(&config_path).unwrap(),
+        "schema_version = \"two\"\n"
+    );
+}
+
+#[test]
+fn opening_a_registry_migrates_its_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join(".registry.config.toml");
+    fs::write(&config_path, V1).unwrap();
+
+    let registry = Registry::new(dir.path()).unwrap();
+    assert_eq!(registry.config().schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(registry.config().default_template, "http");
+    assert_eq!(backups(dir.path()).len(), 1);
+
+    // New registries start at the current version
+    let fresh = tempfile::tempdir().unwrap();
+    Registry::new(fresh.path()).unwrap();
+    assert!(backups(fresh.path()).is_empty());
+}
+
+#[test]
+fn the_server_logs_migrations_and_refuses_newer_configs() {
+    let env = TestEnv::new();
+    let config_path = env.registry.join(".registry.config.toml");
+    fs::write(&config_path, V1).unwrap();
+
+    let mut cmd = cli(&env, &["list"]);
+    cmd.env("RUST_LOG", "info");
+    run_ok(cmd);
+    let log =
+        fs::read_to_string(env.path().join("state/actor-registry/actor-registry.log")).unwrap();
+    assert_contains(
+        &log,
+        &["from schema version 1 to 2 (record the schema version)"],
+    );
+
+    fs::write(&config_path, "schema_version = 99\n").unwrap();
+    let text = run_err(cli(&env, &["list"]));
+    assert_contains(&text, &["schema version 99", "upgrade the actor registry"]);
+}
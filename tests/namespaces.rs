@@ -0,0 +1,163 @@
+mod common;
+
+use actor_registry_mcp::registry::bundle::{ExportOptions, ImportOptions};
+use actor_registry_mcp::registry::namespace;
+use common::{assert_contains, TestRegistry};
+use std::fs;
+
+#[test]
+fn namespaced_actors_nest_under_their_namespace() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    test.create_actor("team-a/logger");
+    test.create_actor("team-a/tools/counter");
+    test.create_actor("team-ab/timer");
+
+    // The registry uses the full name, the crate only the last segment
+    let logger = test.registry.find_actor("team-a/logger").unwrap();
+    assert_eq!(logger.name, "team-a/logger");
+    assert_eq!(logger.path, test.actor_path("team-a/logger"));
+    assert_eq!(logger.manifest.unwrap().name, "logger");
+
+    let mut names: Vec<String> = test
+        .registry
+        .list_actors()
+        .unwrap()
+        .into_iter()
+        .map(|a| a.name)
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "echo",
+            "team-a/logger",
+            "team-a/tools/counter",
+            "team-ab/timer"
+        ]
+    );
+
+    let mut in_team_a: Vec<String> = test
+        .registry
+        .list_namespace("team-a")
+        .unwrap()
+        .into_iter()
+        .map(|a| a.name)
+        .collect();
+    in_team_a.sort();
+    assert_eq!(in_team_a, ["team-a/logger", "team-a/tools/counter"]);
+    assert_eq!(
+        test.registry.namespaces().unwrap(),
+        ["team-a", "team-a/tools", "team-ab"]
+    );
+
+    // A namespace is not an actor
+    let err = test.registry.find_actor("team-a").unwrap_err();
+    assert_contains(&err.to_string(), &["not found"]);
+}
+
+#[test]
+fn every_segment_must_be_a_valid_name() {
+    let test = TestRegistry::new();
+
+    for name in [
+        "",
+        "team-a/../echo",
+        "/echo",
+        "team-a//echo",
+        "team-a/",
+        ".hidden/echo",
+    ] {
+        assert!(namespace::validate_name(name).is_err(), "{:?}", name);
+        assert!(
+            test.registry.create_actor(name, None).is_err(),
+            "{:?}",
+            name
+        );
+    }
+    let err = test.registry.find_actor("team-a/../echo").unwrap_err();
+    assert_contains(&err.to_string(), &["Invalid name", "'..'"]);
+
+    assert_eq!(
+        namespace::split("team-a/tools/counter"),
+        (Some("team-a/tools"), "counter")
+    );
+    assert_eq!(namespace::split("echo"), (None, "echo"));
+    assert!(namespace::in_namespace("team-a/logger", "team-a/"));
+    assert!(!namespace::in_namespace("team-ab/timer", "team-a"));
+}
+
+#[test]
+fn namespace_configs_override_the_registry_config() {
+    let test = TestRegistry::new();
+    fs::create_dir_all(test.actor_path("team-a/tools")).unwrap();
+    fs::write(
+        test.actor_path("team-a").join(namespace::NAMESPACE_CONFIG_FILE),
+        "default_interfaces = [\"ntwk:theater/actor\", \"ntwk:theater/http-server\"]\nbuild_cache_enabled = false\n",
+    )
+    .unwrap();
+    fs::write(
+        test.actor_path("team-a/tools")
+            .join(namespace::NAMESPACE_CONFIG_FILE),
+        "build_cache_enabled = true\n",
+    )
+    .unwrap();
+
+    let top = test.registry.config_for("echo").unwrap();
+    assert!(top.build_cache_enabled);
+    assert_eq!(top.default_interfaces, ["ntwk:theater/actor"]);
+
+    let team = test.registry.config_for("team-a/logger").unwrap();
+    assert!(!team.build_cache_enabled);
+    assert_eq!(team.default_interfaces.len(), 2);
+
+    // Nested namespaces inherit what they don't override
+    let tools = test.registry.config_for("team-a/tools/counter").unwrap();
+    assert!(tools.build_cache_enabled);
+    assert_eq!(tools.default_interfaces.len(), 2);
+
+    fs::write(
+        test.actor_path("team-a")
+            .join(namespace::NAMESPACE_CONFIG_FILE),
+        "build_cache_enabled = \"no\"\n",
+    )
+    .unwrap();
+    let err = test.registry.config_for("team-a/logger").unwrap_err();
+    assert_contains(&err.to_string(), &["Failed to parse namespace config"]);
+}
+
+#[test]
+fn namespaced_actors_round_trip_through_bundles() {
+    let test = TestRegistry::new();
+    test.create_built_actor("team-a/logger");
+    let bundle = test.path().join("logger.tar.zst");
+    let options = ExportOptions {
+        include_component: true,
+        checksums: true,
+    };
+    test.registry
+        .export_actor("team-a/logger", Some(&bundle), &options)
+        .unwrap();
+
+    let (actor, info) = test
+        .registry
+        .import_actor(
+            &bundle,
+            &ImportOptions {
+                name: Some("team-b/tools/logger".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(actor.name, "team-b/tools/logger");
+    assert_eq!(
+        info.component.unwrap(),
+        test.actor_path("team-b/tools/logger/component/logger.wasm")
+    );
+    assert_eq!(actor.manifest.unwrap().name, "logger");
+    let report = test
+        .registry
+        .check_compatibility("team-b/tools/logger")
+        .unwrap();
+    assert!(report.is_compatible(), "{}", report.to_text());
+}
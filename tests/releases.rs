@@ -0,0 +1,133 @@
+mod common;
+
+use actor_registry_mcp::registry::actor::BuildStatus;
+use actor_registry_mcp::registry::build::BuildOptions;
+use actor_registry_mcp::registry::release::{self, VersionBump};
+use common::{assert_contains, install_fake_theater, TestRegistry};
+use semver::Version;
+use std::fs;
+
+fn release_actor(
+    test: &TestRegistry,
+    name: &str,
+    bump: &str,
+) -> anyhow::Result<release::ReleaseInfo> {
+    install_fake_theater();
+    let actor = test.registry.find_writable_actor(name)?;
+    release::release(&actor, &bump.parse()?, &BuildOptions::default())
+}
+
+#[test]
+fn release_snapshots_the_built_component() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+
+    let info = release_actor(&test, "echo", "patch").unwrap();
+    assert_eq!(info.previous_version, "0.1.0");
+    assert_eq!(info.version, "0.1.1");
+    assert_eq!(info.path, test.actor_path("echo").join("releases/0.1.1"));
+    assert!(info.component.is_file());
+
+    // The working copy moves on to the released version
+    let actor = test.registry.find_actor("echo").unwrap();
+    assert_eq!(
+        release::current_version(&actor).unwrap(),
+        Version::new(0, 1, 1)
+    );
+    assert_eq!(actor.manifest.as_ref().unwrap().version, "0.1.1");
+
+    // The snapshot's manifest points at its own copy of the component
+    let snapshot = test.registry.find_actor("echo@0.1.1").unwrap();
+    assert_eq!(snapshot.name, "echo@0.1.1");
+    assert_eq!(snapshot.manifest.as_ref().unwrap().component, "echo.wasm");
+    assert_eq!(snapshot.build_info.build_status, BuildStatus::Success);
+    let report = test.registry.check_compatibility("echo@0.1.1").unwrap();
+    assert!(report.is_compatible(), "{}", report.to_text());
+
+    release_actor(&test, "echo", "minor").unwrap();
+    release_actor(&test, "echo", "1.0.0").unwrap();
+    let actor = test.registry.find_actor("echo").unwrap();
+    assert_eq!(
+        release::list_versions(&actor).unwrap(),
+        [
+            Version::new(0, 1, 1),
+            Version::new(0, 2, 0),
+            Version::new(1, 0, 0)
+        ]
+    );
+}
+
+#[test]
+fn release_snapshots_are_read_only() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    release_actor(&test, "echo", "patch").unwrap();
+
+    let snapshot = test.registry.find_actor("echo@0.1.1").unwrap();
+    assert!(!test.registry.is_writable(&snapshot));
+    let err = test.registry.find_writable_actor("echo@0.1.1").unwrap_err();
+    assert_contains(&err.to_string(), &["release snapshot"]);
+    let err = test
+        .registry
+        .select_writable_actors(&["echo@0.1.1".to_string()], false)
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["release snapshot"]);
+
+    let actor = test.registry.find_writable_actor("echo").unwrap();
+    assert!(test.registry.is_writable(&actor));
+}
+
+#[test]
+fn failed_builds_leave_no_trace() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+    let actor_path = test.actor_path("echo");
+    fs::write(actor_path.join("FAIL_BUILD"), "").unwrap();
+    let manifest = fs::read_to_string(actor_path.join("manifest.toml")).unwrap();
+    let cargo = fs::read_to_string(actor_path.join("Cargo.toml")).unwrap();
+
+    let err = release_actor(&test, "echo", "patch").unwrap_err();
+    assert_contains(&err.to_string(), &["Build failed", "error[E0425]"]);
+
+    assert_eq!(
+        fs::read_to_string(actor_path.join("manifest.toml")).unwrap(),
+        manifest
+    );
+    assert_eq!(
+        fs::read_to_string(actor_path.join("Cargo.toml")).unwrap(),
+        cargo
+    );
+    assert!(!actor_path.join("releases/0.1.1").exists());
+}
+
+#[test]
+fn versions_must_be_semver_and_move_forward() {
+    let test = TestRegistry::new();
+    test.create_actor("echo");
+
+    // References end up as directory names, so only semver gets through
+    for reference in ["echo@1.0", "echo@../../etc", "echo@latest"] {
+        let err = test.registry.find_actor(reference).unwrap_err();
+        assert_contains(&err.to_string(), &["Invalid version"]);
+    }
+    let err = test.registry.find_actor("echo@9.9.9").unwrap_err();
+    assert_contains(&err.to_string(), &["has no release 9.9.9"]);
+
+    let current = Version::new(0, 1, 0);
+    let bump: VersionBump = "0.1.0".parse().unwrap();
+    let err = bump.apply(&current).unwrap_err();
+    assert_contains(
+        &err.to_string(),
+        &["not newer than the current version 0.1.0"],
+    );
+    let older: VersionBump = "0.0.9".parse().unwrap();
+    assert!(older.apply(&current).is_err());
+    assert!("1.x".parse::<VersionBump>().is_err());
+    let major: VersionBump = "major".parse().unwrap();
+    assert_eq!(major.apply(&current).unwrap(), Version::new(1, 0, 0));
+
+    // Refused before the actor is touched
+    let err = release_actor(&test, "echo", "0.1.0").unwrap_err();
+    assert_contains(&err.to_string(), &["not newer"]);
+    assert!(!test.actor_path("echo").join("releases").exists());
+}
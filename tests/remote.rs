@@ -0,0 +1,140 @@
+mod common;
+
+use actor_registry_mcp::registry::config::RemoteConfig;
+use actor_registry_mcp::registry::remote::{self, RemoteCache, RemoteClient};
+use actor_registry_mcp::registry::server::{RegistryServer, ServerHandle};
+use actor_registry_mcp::registry::Registry;
+use common::{assert_contains, TestRegistry};
+use std::fs;
+
+/// Serves a fresh registry, returning it along with the running server
+fn serve() -> (TestRegistry, ServerHandle) {
+    let origin = TestRegistry::new();
+    let registry = Registry::new(origin.registry.path()).unwrap();
+    let server = RegistryServer::bind(registry, "127.0.0.1:0")
+        .unwrap()
+        .spawn()
+        .unwrap();
+    (origin, server)
+}
+
+/// Points a local registry at a remote
+fn add_remote(test: &TestRegistry, name: &str, url: &str) {
+    let mut remotes = test.registry.config().remotes;
+    remotes.push(RemoteConfig {
+        name: name.to_string(),
+        url: url.to_string(),
+    });
+    let remotes = toml::Value::try_from(&remotes).unwrap();
+    test.registry.set_config("remotes", Some(&remotes)).unwrap();
+}
+
+#[test]
+fn published_actors_are_listed_and_resolved() {
+    let (origin, server) = serve();
+    let publisher = TestRegistry::new();
+    add_remote(&publisher, "origin", &server.url());
+    publisher.create_built_actor("echo");
+
+    let summary = publisher
+        .registry
+        .publish_actor("echo", None, false)
+        .unwrap();
+    assert_eq!(summary.name, "echo");
+    assert_eq!(summary.version.as_deref(), Some("0.1.0"));
+    assert!(origin.actor_path("echo/component/echo.wasm").is_file());
+
+    // Publishing again needs overwrite
+    let err = publisher
+        .registry
+        .publish_actor("echo", Some("origin"), false)
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["409", "already exists"]);
+    publisher
+        .registry
+        .publish_actor("echo", Some("origin"), true)
+        .unwrap();
+
+    let consumer = TestRegistry::new();
+    add_remote(&consumer, "origin", &server.url());
+    let listings = consumer.registry.list_remote_actors(None).unwrap();
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].remote, "origin");
+    assert!(!listings[0].from_cache);
+    assert_eq!(listings[0].actors[0].name, "echo");
+
+    // Only lookups that ask for it go to the remote, and fetched actors are
+    // cached rather than added to the registry
+    assert!(consumer.registry.find_actor("echo").is_err());
+    let actor = consumer.registry.resolve_actor("echo").unwrap();
+    assert!(actor
+        .path
+        .starts_with(consumer.registry.path().join(".remote-cache/origin")));
+    assert!(!consumer.actor_path("echo").exists());
+    assert!(consumer.registry.resolve_actor("missing").is_err());
+
+    let err = consumer
+        .registry
+        .list_remote_actors(Some("upstream"))
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["No remote named 'upstream'"]);
+
+    server.shutdown();
+}
+
+#[test]
+fn only_plain_names_and_semver_references_are_served() {
+    let (origin, server) = serve();
+    origin.create_actor("echo");
+    let client = RemoteClient::new(RemoteConfig {
+        name: "origin".to_string(),
+        url: server.url(),
+    });
+
+    let metadata = client.metadata("echo").unwrap().unwrap();
+    assert_eq!(metadata.name, "echo");
+    assert_eq!(client.metadata("missing").unwrap(), None);
+
+    for name in ["echo@latest", "echo@..%2F..%2F..%2Fsecret", "..%2Fsecret"] {
+        let err = client.metadata(name).unwrap_err();
+        assert_contains(&err.to_string(), &["400", "Invalid actor name"]);
+    }
+
+    let bundle = origin.path().join("upload.tar.zst");
+    fs::write(&bundle, b"not a bundle").unwrap();
+    let err = client.publish("echo@0.2.0", &bundle, true).unwrap_err();
+    assert_contains(&err.to_string(), &["400"]);
+    let err = client.publish("copy", &bundle, false).unwrap_err();
+    assert_contains(&err.to_string(), &["400", "Failed to publish actor 'copy'"]);
+    assert!(!origin.actor_path("copy").exists());
+
+    server.shutdown();
+}
+
+#[test]
+fn unreachable_remotes_fall_back_to_the_cache() {
+    let (origin, server) = serve();
+    origin.create_actor("echo");
+    let url = server.url();
+
+    let test = TestRegistry::new();
+    add_remote(&test, "origin", &url);
+    test.registry.list_remote_actors(None).unwrap();
+    test.registry.resolve_actor("echo").unwrap();
+    server.shutdown();
+
+    let listings = test.registry.list_remote_actors(None).unwrap();
+    assert!(listings[0].from_cache);
+    assert_eq!(listings[0].actors[0].name, "echo");
+    let actor = test.registry.resolve_actor("echo").unwrap();
+    assert_eq!(actor.name, "echo");
+
+    // Without a cached listing the error comes through
+    let client = RemoteClient::new(RemoteConfig {
+        name: "offline".to_string(),
+        url: "http://127.0.0.1:9".to_string(),
+    });
+    let cache = RemoteCache::new(test.registry.path(), "offline");
+    let err = remote::list_remote(&client, &cache).unwrap_err();
+    assert_contains(&err.to_string(), &["Remote 'offline' is unreachable"]);
+}
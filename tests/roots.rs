@@ -0,0 +1,95 @@
+mod common;
+
+use actor_registry_mcp::registry::Registry;
+use common::{assert_contains, TestRegistry};
+use std::fs;
+
+/// A writable registry with a read-only `shared` root behind it
+fn layered(writable: &TestRegistry, shared: &TestRegistry) -> Registry {
+    Registry::with_roots(vec![
+        writable.registry.path().to_path_buf(),
+        shared.registry.path().to_path_buf(),
+    ])
+    .unwrap()
+}
+
+fn names(registry: &Registry) -> Vec<String> {
+    let mut names: Vec<String> = registry
+        .list_actors()
+        .unwrap()
+        .into_iter()
+        .map(|a| a.name)
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn earlier_roots_shadow_later_ones() {
+    let local = TestRegistry::new();
+    let shared = TestRegistry::new();
+    shared.create_actor("echo");
+    shared.create_actor("counter");
+    local.create_actor("echo");
+
+    let registry = layered(&local, &shared);
+    assert_eq!(registry.roots().len(), 2);
+    assert_eq!(names(&registry), ["counter", "echo"]);
+
+    let echo = registry.find_actor("echo").unwrap();
+    assert_eq!(echo.path, local.actor_path("echo"));
+    assert_eq!(registry.origin_root(&echo), Some(local.registry.path()));
+    let counter = registry.find_actor("counter").unwrap();
+    assert_eq!(registry.origin_root(&counter), Some(shared.registry.path()));
+
+    let shadowed = registry.shadowed_actors().unwrap();
+    assert_eq!(shadowed.len(), 1);
+    assert_eq!(shadowed[0].actor.path, shared.actor_path("echo"));
+    assert_eq!(shadowed[0].shadowed_by, local.registry.path());
+}
+
+#[test]
+fn only_the_first_root_is_writable() {
+    let local = TestRegistry::new();
+    let shared = TestRegistry::new();
+    shared.create_actor("counter");
+    let registry = layered(&local, &shared);
+
+    let counter = registry.find_actor("counter").unwrap();
+    assert!(!registry.is_writable(&counter));
+    let err = registry.find_writable_actor("counter").unwrap_err();
+    assert_contains(&err.to_string(), &["is read-only"]);
+    let err = registry
+        .select_writable_actors(&["counter".to_string()], false)
+        .unwrap_err();
+    assert_contains(&err.to_string(), &["is read-only"]);
+
+    // New actors always land in the writable root, even when they shadow
+    let created = registry.create_actor("counter", None).unwrap();
+    assert_eq!(created.path, local.actor_path("counter"));
+    assert!(registry.find_writable_actor("counter").is_ok());
+    assert_eq!(registry.shadowed_actors().unwrap().len(), 1);
+}
+
+#[test]
+fn extra_roots_come_from_the_config() {
+    let local = TestRegistry::new();
+    let shared = local.path().join("shared");
+    fs::create_dir_all(&shared).unwrap();
+    let shared_registry = Registry::new(&shared).unwrap();
+    shared_registry.create_actor("counter", None).unwrap();
+
+    // Relative roots are resolved against the registry, missing ones skipped
+    let extra_roots = toml::Value::try_from(["../shared", "missing"]).unwrap();
+    local
+        .registry
+        .set_config("extra_roots", Some(&extra_roots))
+        .unwrap();
+
+    let registry = Registry::new(local.registry.path()).unwrap();
+    assert_eq!(registry.roots().len(), 2);
+    assert_eq!(names(&registry), ["counter"]);
+    assert!(registry.find_writable_actor("counter").is_err());
+
+    assert!(Registry::with_roots(Vec::new()).is_err());
+}
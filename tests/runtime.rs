@@ -0,0 +1,655 @@
+mod common;
+
+use actor_registry_mcp::registry::smoke::SmokeScript;
+use actor_registry_mcp::theater_client::chain::{self, EventFilter};
+use actor_registry_mcp::theater_client::{
+    describe_payload, payload_from_args, ManagementCommand, TheaterClient,
+};
+use actor_registry_mcp::tools::{
+    get_actor_events, list_running_actors, request_actor, send_message, smoke_test_actor,
+    start_actor, stop_actor, test_actor,
+};
+use common::fake_theater::{chain_event, FakeTheaterServer};
+use common::{assert_contains, install_fake_theater, TestRegistry};
+use mcp_protocol::types::tool::{ToolCallResult, ToolContent};
+use serde_json::{json, Value};
+use std::fs;
+
+fn text(result: &ToolCallResult) -> String {
+    result
+        .content
+        .iter()
+        .map(|content| match content {
+            ToolContent::Text { text } => text.clone(),
+            _ => panic!("expected only text content"),
+        })
+        .collect()
+}
+
+fn ok(result: ToolCallResult) -> String {
+    assert_eq!(result.is_error, Some(false), "{}", text(&result));
+    text(&result)
+}
+
+fn err(result: ToolCallResult) -> String {
+    assert_eq!(result.is_error, Some(true), "{}", text(&result));
+    text(&result)
+}
+
+/// A registry pointed at `addr`, with a built `echo` actor
+fn registry_for(addr: &str) -> TestRegistry {
+    let test = TestRegistry::new();
+    test.registry
+        .set_config("theater_addr", Some(&toml::Value::String(addr.to_string())))
+        .unwrap();
+    test.create_built_actor("echo");
+    test
+}
+
+fn start(test: &TestRegistry, args: Value) -> ToolCallResult {
+    start_actor::handle(&test.registry, &args).unwrap()
+}
+
+/// Starts `echo` and returns its runtime id
+fn start_echo(test: &TestRegistry) -> String {
+    let text = ok(start(test, json!({ "name": "echo" })));
+    text.lines()
+        .find_map(|line| line.strip_prefix("Actor ID: "))
+        .expect("start-actor printed no actor id")
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn start_list_and_stop_an_actor() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+
+    let text = ok(start(
+        &test,
+        json!({ "name": "echo", "init_state": { "count": 1 } }),
+    ));
+    assert_contains(&text, &["Actor 'echo' started", "Actor ID: fake-"]);
+
+    let actors = theater.actors();
+    assert_eq!(actors.len(), 1);
+    let (id, actor) = actors.into_iter().next().unwrap();
+    assert_eq!(actor.name, "echo");
+    assert_eq!(
+        fs::canonicalize(&actor.manifest).unwrap(),
+        fs::canonicalize(test.actor_path("echo/manifest.toml")).unwrap()
+    );
+    let state: Value = serde_json::from_slice(actor.initial_state.as_deref().unwrap()).unwrap();
+    assert_eq!(state, json!({ "count": 1 }));
+
+    // A null init state keeps the manifest's
+    ok(start(&test, json!({ "name": "echo", "init_state": null })));
+    assert!(matches!(
+        theater.commands().last(),
+        Some(ManagementCommand::StartActor {
+            initial_state: None,
+            ..
+        })
+    ));
+
+    let text = ok(list_running_actors::handle(&test.registry).unwrap());
+    assert_contains(
+        &text,
+        &["Found 2 running actors", &format!("- {} (echo)", id)],
+    );
+
+    let text = ok(stop_actor::handle(&test.registry, &json!({ "id": id })).unwrap());
+    assert_contains(&text, &[&format!("Actor {} stopped.", id)]);
+    assert_eq!(theater.actors().len(), 1);
+
+    let text = err(stop_actor::handle(&test.registry, &json!({ "id": id })).unwrap());
+    assert_contains(&text, &["not found"]);
+}
+
+#[test]
+fn start_actor_errors() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+
+    err(start(&test, json!({ "name": "missing" })));
+    assert!(start_actor::handle(&test.registry, &json!({})).is_err());
+    assert!(stop_actor::handle(&test.registry, &json!({})).is_err());
+
+    // Theater can't start an actor that was never built
+    test.create_actor("unbuilt");
+    let text = err(start(&test, json!({ "name": "unbuilt" })));
+    assert_contains(&text, &["build the actor with build-actor first"]);
+    assert!(theater.commands().is_empty());
+}
+
+#[test]
+fn send_and_request() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+    let id = start_echo(&test);
+
+    let text = ok(send_message::handle(
+        &test.registry,
+        &json!({ "id": id, "payload": { "op": "increment" } }),
+    )
+    .unwrap());
+    assert_contains(&text, &["Sent 18 bytes", &id]);
+
+    theater
+        .respond_with(|actor, _| format!("{} messages so far", actor.received.len()).into_bytes());
+    let text =
+        ok(request_actor::handle(&test.registry, &json!({ "id": id, "text": "count?" })).unwrap());
+    assert_contains(&text, &["responded with", "1 messages so far"]);
+
+    let text =
+        ok(request_actor::handle(&test.registry, &json!({ "id": id, "base64": "aGk=" })).unwrap());
+    assert_contains(&text, &["2 messages so far"]);
+
+    let received = &theater.actors()[&id].received;
+    assert_eq!(received.len(), 3);
+    assert_eq!(
+        serde_json::from_slice::<Value>(&received[0]).unwrap(),
+        json!({ "op": "increment" })
+    );
+    assert_eq!(received[1], b"count?");
+    assert_eq!(received[2], b"hi");
+}
+
+#[test]
+fn send_and_request_errors() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+    let id = start_echo(&test);
+
+    // Exactly one payload form is accepted
+    let send = |args: Value| send_message::handle(&test.registry, &args).unwrap();
+    assert_contains(&err(send(json!({ "id": id }))), &["Missing payload"]);
+    assert_contains(
+        &err(send(json!({ "id": id, "text": "a", "payload": "b" }))),
+        &["Give only one of"],
+    );
+    let text = err(request_actor::handle(
+        &test.registry,
+        &json!({ "id": id, "base64": "not base64!" }),
+    )
+    .unwrap());
+    assert_contains(&text, &["Invalid base64 payload"]);
+    assert!(send_message::handle(&test.registry, &json!({ "text": "hi" })).is_err());
+
+    assert_contains(
+        &err(send(json!({ "id": "fake-404", "text": "hi" }))),
+        &["not found"],
+    );
+    err(request_actor::handle(&test.registry, &json!({ "id": "fake-404", "text": "hi" })).unwrap());
+    assert!(theater.actors()[&id].received.is_empty());
+}
+
+#[test]
+fn payloads_are_read_and_described() {
+    // A null payload counts as absent
+    assert_eq!(
+        payload_from_args(&json!({ "payload": null, "text": "hi" })).unwrap(),
+        b"hi"
+    );
+    assert_eq!(
+        payload_from_args(&json!({ "payload": [1, 2] })).unwrap(),
+        b"[1,2]"
+    );
+
+    assert_eq!(describe_payload(b""), "(empty)");
+    assert_eq!(describe_payload(br#"{"a":1}"#), "{\n  \"a\": 1\n}");
+    assert_eq!(describe_payload(b"plain text"), "plain text");
+    assert_eq!(describe_payload(&[0xff, 0x00]), "base64:/wA=");
+}
+
+fn events(test: &TestRegistry, args: Value) -> ToolCallResult {
+    get_actor_events::handle(&test.registry, &args).unwrap()
+}
+
+#[test]
+fn get_actor_events_from_the_server() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+    let id = start_echo(&test);
+
+    ok(send_message::handle(&test.registry, &json!({ "id": id, "text": "one" })).unwrap());
+    ok(request_actor::handle(&test.registry, &json!({ "id": id, "text": "two" })).unwrap());
+    theater
+        .push_event(
+            &id,
+            chain_event(
+                "wasm-error",
+                Vec::new(),
+                Some("handle-request failed".to_string()),
+            ),
+        )
+        .unwrap();
+
+    let text = ok(events(&test, json!({ "id": id })));
+    assert_contains(
+        &text,
+        &[
+            "5 of 5 events match",
+            "actor-started",
+            "message-received",
+            "Errors (1)",
+            "handle-request failed",
+        ],
+    );
+
+    let text = ok(events(
+        &test,
+        json!({ "id": id, "event_type": ["request", "response"], "limit": 1 }),
+    ));
+    assert_contains(&text, &["1 of 5 events match", "response-sent"]);
+    assert!(!text.contains("request-received"), "{}", text);
+
+    let text = err(events(&test, json!({ "id": id, "since": "yesterday-ish" })));
+    assert_contains(&text, &["Invalid since"]);
+    let text = err(events(&test, json!({ "id": id, "source": "cloud" })));
+    assert_contains(&text, &["Unknown source 'cloud'"]);
+}
+
+#[test]
+fn get_actor_events_from_disk() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+
+    // Relative chain stores are resolved against the registry
+    test.registry
+        .set_config(
+            "chain_store",
+            Some(&toml::Value::String("chains".to_string())),
+        )
+        .unwrap();
+    let store = test.registry.chain_store().unwrap();
+    assert_eq!(store, test.actor_path("chains"));
+    fs::create_dir_all(&store).unwrap();
+    let mut old = chain_event("state-changed", b"{\"n\":1}".to_vec(), None);
+    old.timestamp = 1_000;
+    let new = chain_event("log", b"done".to_vec(), None);
+    fs::write(
+        store.join("stopped-actor.json"),
+        serde_json::to_string(&json!({ "events": [old, new] })).unwrap(),
+    )
+    .unwrap();
+    // A bare list of events is read too
+    fs::write(
+        store.join("listed"),
+        serde_json::to_string(&json!([new])).unwrap(),
+    )
+    .unwrap();
+
+    // The fake server doesn't know the actor, so auto falls back to disk
+    let text = ok(events(&test, json!({ "id": "stopped-actor" })));
+    assert_contains(
+        &text,
+        &[
+            "2 of 2 events match",
+            "State changes (1)",
+            store.to_str().unwrap(),
+        ],
+    );
+
+    let text = ok(events(
+        &test,
+        json!({ "id": "stopped-actor", "source": "disk", "since": "2000-01-01T00:00:00Z" }),
+    ));
+    assert_contains(&text, &["1 of 2 events match", "done"]);
+    let text = ok(events(&test, json!({ "id": "listed", "source": "disk" })));
+    assert_contains(&text, &["1 of 1 events match"]);
+
+    let text = err(events(&test, json!({ "id": "never-ran" })));
+    assert_contains(&text, &["not on the server", "nor on disk"]);
+    let text = err(events(
+        &test,
+        json!({ "id": "stopped-actor", "source": "server" }),
+    ));
+    assert_contains(&text, &["not found"]);
+    let text = err(events(
+        &test,
+        json!({ "id": "../escape", "source": "disk" }),
+    ));
+    assert_contains(&text, &["Invalid actor id"]);
+}
+
+#[test]
+fn event_filters() {
+    let at = |millis: u64, event_type: &str| {
+        let mut event = chain_event(event_type, Vec::new(), None);
+        event.timestamp = millis;
+        event
+    };
+    let events = [at(1_000, "wasm"), at(2_000, "state-set"), at(3_000, "log")];
+
+    let filter = EventFilter::from_args(&json!({ "since": "1500", "until": "2500" })).unwrap();
+    let matching = filter.apply(&events);
+    assert_eq!(matching.len(), 1);
+    assert_eq!(matching[0].event_type, "state-set");
+
+    let filter = EventFilter::from_args(&json!({ "event_type": "s", "limit": 1 })).unwrap();
+    assert_eq!(filter.apply(&events)[0].event_type, "state-set");
+
+    assert!(EventFilter::from_args(&json!({ "event_type": 1 })).is_err());
+    assert!(EventFilter::from_args(&json!({ "event_type": ["log", 1] })).is_err());
+    assert_eq!(
+        chain::parse_time("1970-01-01T00:00:01Z").unwrap(),
+        chain::parse_time("1000").unwrap()
+    );
+}
+
+fn smoke_test(test: &TestRegistry, args: Value) -> ToolCallResult {
+    smoke_test_actor::handle(&test.registry, &args).unwrap()
+}
+
+#[test]
+fn smoke_test_against_the_fake_server() {
+    install_fake_theater();
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+
+    let script = test.actor_path("echo/tests/smoke.toml");
+    fs::create_dir_all(script.parent().unwrap()).unwrap();
+    fs::write(
+        &script,
+        r#"
+init_state = { count = 0 }
+
+[[step]]
+name = "ping"
+request = "ping"
+expect = "ping"
+
+[[step]]
+request = { op = "add" }
+expect = { op = "add" }
+expect_contains = "add"
+
+[[step]]
+send = { op = "reset" }
+"#,
+    )
+    .unwrap();
+
+    let text = ok(smoke_test(&test, json!({ "name": "echo" })));
+    assert_contains(
+        &text,
+        &[
+            "passed",
+            "build: PASS",
+            "start: PASS",
+            "step 1 (ping): PASS",
+            "step 3: PASS",
+            "stop: PASS",
+        ],
+    );
+    assert!(theater.actors().is_empty(), "the actor was not stopped");
+    assert!(matches!(
+        &theater.commands()[0],
+        ManagementCommand::StartActor { initial_state: Some(state), .. } if state == br#"{"count":0}"#
+    ));
+
+    // Failing steps are reported and the actor is still stopped
+    theater.respond_with(|_, _| b"pong".to_vec());
+    let text = err(smoke_test(
+        &test,
+        json!({ "name": "echo", "build": false, "init_state": { "count": 5 } }),
+    ));
+    assert_contains(
+        &text,
+        &[
+            "2 of 6 steps failed",
+            "build: SKIP: build disabled",
+            "step 1 (ping): FAIL",
+            "pong",
+        ],
+    );
+    assert!(theater.actors().is_empty(), "the actor was not stopped");
+    assert!(matches!(
+        theater.commands().iter().rev().find(|c| matches!(c, ManagementCommand::StartActor { .. })),
+        Some(ManagementCommand::StartActor { initial_state: Some(state), .. }) if state == br#"{"count":5}"#
+    ));
+}
+
+#[test]
+fn smoke_test_steps_only_see_their_own_logs() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+    theater.log_messages(true);
+
+    let script = test.path().join("logs.toml");
+    let write_script = |steps: &str| fs::write(&script, steps).unwrap();
+    let args = json!({ "name": "echo", "build": false, "script": script.to_str().unwrap() });
+
+    write_script(
+        r#"
+[[step]]
+send = "hello"
+expect_log = "hello"
+
+[[step]]
+send = "world"
+expect_log = "world"
+"#,
+    );
+    let text = ok(smoke_test(&test, args.clone()));
+    assert_contains(&text, &["Log: hello", "Log: world"]);
+
+    // The first step logged "hello" without checking for it; the second
+    // step must not count that line
+    write_script(
+        r#"
+[[step]]
+send = "hello"
+
+[[step]]
+send = "world"
+expect_log = "hello"
+"#,
+    );
+    let text = err(smoke_test(&test, args));
+    assert_contains(
+        &text,
+        &[
+            "step 1: PASS",
+            "step 2: FAIL",
+            "no log line contains 'hello'",
+        ],
+    );
+}
+
+#[test]
+fn smoke_test_errors() {
+    install_fake_theater();
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+
+    let text = err(smoke_test(&test, json!({ "name": "echo" })));
+    assert_contains(&text, &["No smoke test script"]);
+
+    let script = test.path().join("smoke.toml");
+    fs::write(&script, "[[step]]\nrequest = \"ping\"\n").unwrap();
+    let script = script.to_str().unwrap();
+
+    fs::write(test.actor_path("echo/FAIL_BUILD"), "").unwrap();
+    let text = err(smoke_test(
+        &test,
+        json!({ "name": "echo", "script": script }),
+    ));
+    assert_contains(
+        &text,
+        &["build: FAIL", "error[E0425]", "start: SKIP: build failed"],
+    );
+    assert!(
+        theater.commands().is_empty(),
+        "started despite a failed build"
+    );
+
+    // Steps that can't make sense are rejected before anything runs
+    for (steps, message) in [
+        ("name = \"nothing to do\"", "has nothing to send or check"),
+        ("send = \"a\"\nrequest = \"b\"", "has both send and request"),
+        (
+            "send = \"a\"\nexpect = \"b\"",
+            "expects a response but sends no request",
+        ),
+        ("sned = \"a\"", "unknown field"),
+    ] {
+        let bad_script = test.path().join("bad.toml");
+        fs::write(&bad_script, format!("[[step]]\n{}\n", steps)).unwrap();
+        let error = SmokeScript::load(&bad_script).unwrap_err();
+        assert_contains(&format!("{:#}", error), &[message]);
+        err(smoke_test(
+            &test,
+            json!({ "name": "echo", "build": false, "script": bad_script.to_str().unwrap() }),
+        ));
+    }
+    assert!(theater.commands().is_empty());
+
+    err(smoke_test(&test, json!({ "name": "missing" })));
+}
+
+fn harness_test(test: &TestRegistry, args: Value) -> ToolCallResult {
+    test_actor::handle(&test.registry, &args).unwrap()
+}
+
+#[test]
+fn test_actor_runs_the_component_in_process() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+
+    // Without a script it still instantiates and initializes the actor
+    let text = ok(harness_test(&test, json!({ "name": "echo" })));
+    assert_contains(
+        &text,
+        &[
+            "Harness test of 'echo' passed",
+            "instantiate: PASS",
+            "init: PASS",
+            "Log: initialized",
+            "Log: test-actor",
+        ],
+    );
+
+    let script = test.path().join("harness.toml");
+    fs::write(
+        &script,
+        r#"
+[[step]]
+name = "remember"
+send = "hello"
+expect_log = "hello"
+
+[[step]]
+name = "echo"
+request = "ping"
+expect = "ping"
+"#,
+    )
+    .unwrap();
+
+    let text = ok(harness_test(
+        &test,
+        json!({ "name": "echo", "init_state": "start", "script": script.to_str().unwrap() }),
+    ));
+    assert_contains(
+        &text,
+        &[
+            "step 1 (remember): PASS",
+            "step 2 (echo): PASS",
+            "Response: ping",
+            "Log: request-1",
+        ],
+    );
+    // Nothing reaches Theater
+    assert!(theater.commands().is_empty());
+}
+
+#[test]
+fn test_actor_reports_failures() {
+    let theater = FakeTheaterServer::start().unwrap();
+    let test = registry_for(&theater.addr().to_string());
+
+    let script = test.path().join("harness.toml");
+    fs::write(
+        &script,
+        r#"
+[[step]]
+name = "wrong answer"
+request = "ping"
+expect = "pong"
+
+[[step]]
+name = "actor error"
+request = "xplode"
+
+[[step]]
+name = "runaway"
+request = "loop"
+"#,
+    )
+    .unwrap();
+
+    let text = err(harness_test(
+        &test,
+        json!({ "name": "echo", "script": script.to_str().unwrap() }),
+    ));
+    assert_contains(
+        &text,
+        &[
+            "3 of 5 steps failed",
+            "(wrong answer): FAIL: expected response \"pong\"",
+            "(actor error): FAIL",
+            "boom",
+            "(runaway): FAIL",
+        ],
+    );
+
+    // Not built yet
+    test.create_actor("unbuilt");
+    let text = err(harness_test(&test, json!({ "name": "unbuilt" })));
+    assert_contains(&text, &["build the actor with build-actor first"]);
+    err(harness_test(&test, json!({ "name": "missing" })));
+
+    // A component the harness can't load fails the first step
+    fs::write(
+        test.actor_path("echo/target/wasm32-unknown-unknown/release/echo.wasm"),
+        b"not wasm",
+    )
+    .unwrap();
+    let text = err(harness_test(&test, json!({ "name": "echo" })));
+    assert_contains(
+        &text,
+        &["instantiate: FAIL", "init: SKIP: not instantiated"],
+    );
+}
+
+#[test]
+fn theater_not_running() {
+    // Bind and drop a listener to find a port nothing listens on
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let test = registry_for(&addr);
+
+    let text = err(start(&test, json!({ "name": "echo" })));
+    assert_contains(&text, &[&addr, "is `theater server` running?"]);
+    err(list_running_actors::handle(&test.registry).unwrap());
+    err(stop_actor::handle(&test.registry, &json!({ "id": "fake-00000001" })).unwrap());
+    err(events(
+        &test,
+        json!({ "id": "fake-00000001", "source": "server" }),
+    ));
+    err(send_message::handle(
+        &test.registry,
+        &json!({ "id": "fake-00000001", "text": "hi" }),
+    )
+    .unwrap());
+
+    let error = TheaterClient::new("not an address")
+        .list_actors()
+        .unwrap_err();
+    assert_contains(&error.to_string(), &["Invalid Theater address"]);
+}
@@ -0,0 +1,421 @@
+mod common;
+
+use common::fake_theater::{chain_event, FakeTheaterServer};
+use common::{assert_contains, McpServer, TestEnv};
+use serde_json::json;
+use std::fs;
+
+/// A registry pointed at a fake Theater server, with a built `echo` actor
+fn running_env() -> (FakeTheaterServer, TestEnv, McpServer) {
+    let theater = FakeTheaterServer::start().unwrap();
+    let env = TestEnv::new().with_env("THEATER_SERVER_ADDR", &theater.addr().to_string());
+    let mut server = env.server();
+    env.create_built_actor(&mut server, "echo");
+    (theater, env, server)
+}
+
+/// Starts `echo` and returns its runtime id
+fn start_echo(server: &mut McpServer) -> String {
+    let text = server.call_ok("start-actor", json!({ "name": "echo" }));
+    text.lines()
+        .find_map(|line| line.strip_prefix("Actor ID: "))
+        .expect("start-actor printed no actor id")
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn start_list_and_stop_an_actor() {
+    let (theater, env, mut server) = running_env();
+
+    let text = server.call_ok(
+        "start-actor",
+        json!({ "name": "echo", "init_state": { "count": 1 } }),
+    );
+    assert_contains(&text, &["Actor 'echo' started", "Actor ID: fake-"]);
+
+    let actors = theater.actors();
+    assert_eq!(actors.len(), 1);
+    let (id, actor) = actors.into_iter().next().unwrap();
+    assert_eq!(actor.name, "echo");
+    assert_eq!(
+        fs::canonicalize(&actor.manifest).unwrap(),
+        fs::canonicalize(env.actor_path("echo").join("manifest.toml")).unwrap()
+    );
+    let state: serde_json::Value =
+        serde_json::from_slice(actor.initial_state.as_deref().unwrap()).unwrap();
+    assert_eq!(state, json!({ "count": 1 }));
+
+    let text = server.call_ok("list-running-actors", json!({}));
+    assert_contains(&text, &[&format!("- {} (echo)", id)]);
+
+    let text = server.call_ok("stop-actor", json!({ "id": id }));
+    assert_contains(&text, &[&format!("Actor {} stopped.", id)]);
+    assert!(theater.actors().is_empty());
+
+    let text = server.call_err("stop-actor", json!({ "id": id }));
+    assert_contains(&text, &["not found"]);
+}
+
+#[test]
+fn start_actor_errors() {
+    let (_theater, env, mut server) = running_env();
+
+    server.call_err("start-actor", json!({ "name": "missing" }));
+    server.call_err("start-actor", json!({}));
+
+    // Theater can't start an actor that was never built
+    env.create_actor(&mut server, "unbuilt");
+    server.call_err("start-actor", json!({ "name": "unbuilt" }));
+}
+
+#[test]
+fn send_and_request() {
+    let (theater, _env, mut server) = running_env();
+    let id = start_echo(&mut server);
+
+    let text = server.call_ok(
+        "send-message",
+        json!({ "id": id, "payload": { "op": "increment" } }),
+    );
+    assert_contains(&text, &["Sent", &id]);
+
+    theater
+        .respond_with(|actor, _| format!("{} messages so far", actor.received.len()).into_bytes());
+    let text = server.call_ok("request-actor", json!({ "id": id, "text": "count?" }));
+    assert_contains(&text, &["responded with", "1 messages so far"]);
+
+    let text = server.call_ok("request-actor", json!({ "id": id, "base64": "aGk=" }));
+    assert_contains(&text, &["2 messages so far"]);
+
+    let received = &theater.actors()[&id].received;
+    assert_eq!(received.len(), 3);
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&received[0]).unwrap(),
+        json!({ "op": "increment" })
+    );
+    assert_eq!(received[1], b"count?");
+    assert_eq!(received[2], b"hi");
+}
+
+#[test]
+fn send_and_request_errors() {
+    let (_theater, _env, mut server) = running_env();
+    let id = start_echo(&mut server);
+
+    // Exactly one payload form is accepted
+    server.call_err("send-message", json!({ "id": id }));
+    server.call_err(
+        "send-message",
+        json!({ "id": id, "text": "a", "payload": "b" }),
+    );
+    server.call_err(
+        "request-actor",
+        json!({ "id": id, "base64": "not base64!" }),
+    );
+
+    let text = server.call_err("send-message", json!({ "id": "fake-404", "text": "hi" }));
+    assert_contains(&text, &["not found"]);
+    server.call_err("request-actor", json!({ "id": "fake-404", "text": "hi" }));
+}
+
+#[test]
+fn theater_not_running() {
+    // Bind and drop a listener to find a port nothing listens on
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let env = TestEnv::new().with_env("THEATER_SERVER_ADDR", &addr.to_string());
+    let mut server = env.server();
+    env.create_built_actor(&mut server, "echo");
+
+    let text = server.call_err("start-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &[&addr.to_string()]);
+    server.call_err("list-running-actors", json!({}));
+    server.call_err("stop-actor", json!({ "id": "fake-00000001" }));
+    server.call_err(
+        "get-actor-events",
+        json!({ "id": "fake-00000001", "source": "server" }),
+    );
+}
+
+#[test]
+fn get_actor_events_from_the_server() {
+    let (theater, _env, mut server) = running_env();
+    let id = start_echo(&mut server);
+
+    server.call_ok("send-message", json!({ "id": id, "text": "one" }));
+    server.call_ok("request-actor", json!({ "id": id, "text": "two" }));
+    theater
+        .push_event(
+            &id,
+            chain_event(
+                "wasm-error",
+                Vec::new(),
+                Some("handle-request failed".to_string()),
+            ),
+        )
+        .unwrap();
+
+    let text = server.call_ok("get-actor-events", json!({ "id": id }));
+    assert_contains(
+        &text,
+        &[
+            "5 of 5 events match",
+            "actor-started",
+            "message-received",
+            "Errors (1)",
+            "handle-request failed",
+        ],
+    );
+
+    let text = server.call_ok(
+        "get-actor-events",
+        json!({ "id": id, "event_type": ["request", "response"], "limit": 1 }),
+    );
+    assert_contains(&text, &["1 of 5 events match", "response-sent"]);
+    assert!(!text.contains("request-received"), "{}", text);
+
+    server.call_err(
+        "get-actor-events",
+        json!({ "id": id, "since": "yesterday-ish" }),
+    );
+    server.call_err("get-actor-events", json!({ "id": id, "source": "cloud" }));
+}
+
+#[test]
+fn get_actor_events_from_disk() {
+    let (_theater, env, mut server) = running_env();
+
+    let store = env.chain_store();
+    fs::create_dir_all(&store).unwrap();
+    let mut old = chain_event("state-changed", b"{\"n\":1}".to_vec(), None);
+    old.timestamp = 1_000;
+    let new = chain_event("log", b"done".to_vec(), None);
+    fs::write(
+        store.join("stopped-actor.json"),
+        serde_json::to_string(&json!({ "events": [old, new] })).unwrap(),
+    )
+    .unwrap();
+
+    // The fake server doesn't know the actor, so auto falls back to disk
+    let text = server.call_ok("get-actor-events", json!({ "id": "stopped-actor" }));
+    assert_contains(
+        &text,
+        &[
+            "2 of 2 events match",
+            "State changes (1)",
+            store.to_str().unwrap(),
+        ],
+    );
+
+    let text = server.call_ok(
+        "get-actor-events",
+        json!({ "id": "stopped-actor", "source": "disk", "since": "2000-01-01T00:00:00Z" }),
+    );
+    assert_contains(&text, &["1 of 2 events match", "done"]);
+
+    let text = server.call_err("get-actor-events", json!({ "id": "never-ran" }));
+    assert_contains(&text, &["not on the server", "nor on disk"]);
+    server.call_err(
+        "get-actor-events",
+        json!({ "id": "../escape", "source": "disk" }),
+    );
+}
+
+#[test]
+fn smoke_test_against_the_fake_server() {
+    let (theater, env, mut server) = running_env();
+
+    let script = env.actor_path("echo").join("tests/smoke.toml");
+    fs::create_dir_all(script.parent().unwrap()).unwrap();
+    fs::write(
+        &script,
+        r#"
+[[step]]
+name = "ping"
+request = "ping"
+expect = "ping"
+
+[[step]]
+send = { op = "reset" }
+"#,
+    )
+    .unwrap();
+
+    let text = server.call_ok("smoke-test-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["passed", "build: PASS", "step 1 (ping): PASS"]);
+    assert!(theater.actors().is_empty(), "the actor was not stopped");
+    assert_eq!(env.theater.calls().len(), 2);
+
+    theater.respond_with(|_, _| b"pong".to_vec());
+    let text = server.call_err(
+        "smoke-test-actor",
+        json!({ "name": "echo", "build": false }),
+    );
+    assert_contains(&text, &["step 1 (ping): FAIL", "pong"]);
+    assert_eq!(env.theater.calls().len(), 2);
+    assert!(theater.actors().is_empty(), "the actor was not stopped");
+}
+
+#[test]
+fn smoke_test_steps_only_see_their_own_logs() {
+    let (theater, env, mut server) = running_env();
+    theater.log_messages(true);
+
+    let script = env.path().join("logs.toml");
+    let write_script = |steps: &str| fs::write(&script, steps).unwrap();
+    let args = json!({ "name": "echo", "build": false, "script": script.to_str().unwrap() });
+
+    write_script(
+        r#"
+[[step]]
+send = "hello"
+expect_log = "hello"
+
+[[step]]
+send = "world"
+expect_log = "world"
+"#,
+    );
+    let text = server.call_ok("smoke-test-actor", args.clone());
+    assert_contains(&text, &["Log: hello", "Log: world"]);
+
+    // The first step logged "hello" without checking for it; the second
+    // step must not count that line
+    write_script(
+        r#"
+[[step]]
+send = "hello"
+
+[[step]]
+send = "world"
+expect_log = "hello"
+"#,
+    );
+    let text = server.call_err("smoke-test-actor", args);
+    assert_contains(
+        &text,
+        &[
+            "step 1: PASS",
+            "step 2: FAIL",
+            "no log line contains 'hello'",
+        ],
+    );
+}
+
+#[test]
+fn smoke_test_errors() {
+    let (theater, env, mut server) = running_env();
+
+    let text = server.call_err("smoke-test-actor", json!({ "name": "echo" }));
+    assert_contains(&text, &["No smoke test script"]);
+
+    let script = env.path().join("smoke.toml");
+    fs::write(&script, "[[step]]\nrequest = \"ping\"\n").unwrap();
+    let script = script.to_str().unwrap();
+
+    env.theater.fail_builds(true);
+    let text = server.call_err(
+        "smoke-test-actor",
+        json!({ "name": "echo", "script": script }),
+    );
+    assert_contains(&text, &["build: FAIL", "error[E0425]"]);
+    assert!(
+        theater.commands().is_empty(),
+        "started despite a failed build"
+    );
+
+    let bad_script = env.path().join("bad.toml");
+    fs::write(&bad_script, "[[step]]\nname = \"nothing to do\"\n").unwrap();
+    server.call_err(
+        "smoke-test-actor",
+        json!({ "name": "echo", "build": false, "script": bad_script.to_str().unwrap() }),
+    );
+
+    server.call_err("smoke-test-actor", json!({ "name": "missing" }));
+}
+
+#[test]
+fn test_actor_runs_the_component_in_process() {
+    let (theater, env, mut server) = running_env();
+
+    let script = env.path().join("harness.toml");
+    fs::write(
+        &script,
+        r#"
+[[step]]
+name = "remember"
+send = "hello"
+expect_log = "hello"
+
+[[step]]
+name = "echo"
+request = "ping"
+expect = "ping"
+"#,
+    )
+    .unwrap();
+
+    let text = server.call_ok(
+        "test-actor",
+        json!({ "name": "echo", "init_state": "start", "script": script.to_str().unwrap() }),
+    );
+    assert_contains(
+        &text,
+        &[
+            "Harness test of 'echo' passed",
+            "instantiate: PASS",
+            "init: PASS",
+            "step 1 (remember): PASS",
+            "step 2 (echo): PASS",
+        ],
+    );
+    // Nothing reaches Theater
+    assert!(theater.commands().is_empty());
+}
+
+#[test]
+fn test_actor_reports_failures() {
+    let (_theater, env, mut server) = running_env();
+
+    let script = env.path().join("harness.toml");
+    fs::write(
+        &script,
+        r#"
+[[step]]
+name = "wrong answer"
+request = "ping"
+expect = "pong"
+
+[[step]]
+name = "actor error"
+request = "xplode"
+
+[[step]]
+name = "runaway"
+request = "loop"
+"#,
+    )
+    .unwrap();
+
+    let text = server.call_err(
+        "test-actor",
+        json!({ "name": "echo", "script": script.to_str().unwrap() }),
+    );
+    assert_contains(
+        &text,
+        &[
+            "(wrong answer): FAIL",
+            "(actor error): FAIL",
+            "boom",
+            "(runaway): FAIL",
+        ],
+    );
+
+    // Not built yet
+    env.create_actor(&mut server, "unbuilt");
+    server.call_err("test-actor", json!({ "name": "unbuilt" }));
+    server.call_err("test-actor", json!({ "name": "missing" }));
+}
@@ -0,0 +1,404 @@
+mod common;
+
+use common::{assert_contains, McpServer, TestEnv};
+use git2::{Repository, RepositoryInitOptions, Signature};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = r#"name = "echo"
+version = "0.1.0"
+component = ""
+description = "Echoes what it is sent"
+save_chain = true
+
+[[handler]]
+type = "runtime"
+
+[handler.config]
+"#;
+
+const CARGO_TOML: &str = r#"[package]
+name = "echo"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+crate-type = ["cdylib"]
+"#;
+
+/// An upstream repository: commits are made in a work tree on `main` and
+/// pushed to a bare repository, which is what the registry clones
+struct Upstream {
+    work: Repository,
+    bare: PathBuf,
+}
+
+impl Upstream {
+    fn new(dir: &Path) -> Self {
+        let mut options = RepositoryInitOptions::new();
+        options.initial_head("main");
+        let work = Repository::init_opts(dir.join("work"), &options).unwrap();
+
+        let bare = dir.join("upstream.git");
+        options.bare(true);
+        Repository::init_opts(&bare, &options).unwrap();
+
+        Self { work, bare }
+    }
+
+    /// The echo actor at `prefix` ("" for the repository root)
+    fn with_actor(dir: &Path, prefix: &str) -> Self {
+        let upstream = Self::new(dir);
+        upstream.commit(
+            &[
+                (&format!("{}manifest.toml", prefix), Some(MANIFEST)),
+                (&format!("{}Cargo.toml", prefix), Some(CARGO_TOML)),
+                (&format!("{}src/lib.rs", prefix), Some("// v1\n")),
+                (&format!("{}notes.md", prefix), Some("notes\n")),
+            ],
+            "Add echo",
+        );
+        upstream
+    }
+
+    fn source(&self) -> &str {
+        self.bare.to_str().unwrap()
+    }
+
+    /// Writes (or with `None` deletes) files, commits them and pushes `main`
+    fn commit(&self, files: &[(&str, Option<&str>)], message: &str) {
+        let root = self.work.workdir().unwrap().to_path_buf();
+        let mut index = self.work.index().unwrap();
+        for (path, content) in files {
+            let full = root.join(path);
+            match content {
+                Some(content) => {
+                    fs::create_dir_all(full.parent().unwrap()).unwrap();
+                    fs::write(&full, content).unwrap();
+                    index.add_path(Path::new(path)).unwrap();
+                }
+                None => {
+                    fs::remove_file(&full).unwrap();
+                    index.remove_path(Path::new(path)).unwrap();
+                }
+            }
+        }
+        index.write().unwrap();
+
+        let tree = self.work.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Upstream", "upstream@example.com").unwrap();
+        let parent = self.work.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        self.work
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap();
+
+        self.work
+            .remote_anonymous(self.source())
+            .unwrap()
+            .push(&["+refs/heads/main:refs/heads/main"], None)
+            .unwrap();
+    }
+
+    fn tag(&self, name: &str) {
+        let head = self.work.head().unwrap().peel_to_commit().unwrap();
+        self.work
+            .tag_lightweight(name, head.as_object(), false)
+            .unwrap();
+        self.work
+            .remote_anonymous(self.source())
+            .unwrap()
+            .push(&[&format!("refs/tags/{}:refs/tags/{}", name, name)], None)
+            .unwrap();
+    }
+
+    fn head(&self) -> String {
+        self.work.head().unwrap().target().unwrap().to_string()
+    }
+}
+
+fn source_record(env: &TestEnv, name: &str) -> toml::Table {
+    let path = env.actor_path(name).join(".actor-source.toml");
+    toml::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn cloned_env() -> (TestEnv, McpServer, Upstream) {
+    let env = TestEnv::new();
+    let upstream = Upstream::with_actor(env.path(), "");
+    let mut server = env.server();
+    server.call_ok("clone-actor", json!({ "source": upstream.source() }));
+    (env, server, upstream)
+}
+
+#[test]
+fn clone_actor_from_a_bare_repository() {
+    let (env, mut server, upstream) = cloned_env();
+
+    let actor = env.actor_path("echo");
+    assert_eq!(
+        fs::read_to_string(actor.join("src/lib.rs")).unwrap(),
+        "// v1\n"
+    );
+    assert!(!actor.join(".git").exists());
+
+    let record = source_record(&env, "echo");
+    assert_eq!(record["commit"].as_str().unwrap(), upstream.head());
+    assert!(record.get("ref").is_none());
+    let url = fs::canonicalize(&upstream.bare).unwrap();
+    assert_eq!(record["url"].as_str().unwrap(), url.to_str().unwrap());
+
+    let text = server.call_ok("list-actors-in-registry", json!({}));
+    assert_contains(&text, &["echo"]);
+
+    // No scratch directories are left in the registry
+    let leftovers: Vec<_> = fs::read_dir(&env.registry)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with(".clone-") || name.starts_with(".source-"))
+        .collect();
+    assert!(leftovers.is_empty(), "{:?}", leftovers);
+}
+
+#[test]
+fn clone_a_tag_from_a_subdirectory_under_a_new_name() {
+    let env = TestEnv::new();
+    let upstream = Upstream::with_actor(env.path(), "actors/echo/");
+    upstream.tag("v1");
+    let v1 = upstream.head();
+    upstream.commit(&[("actors/echo/src/lib.rs", Some("// v2\n"))], "v2");
+
+    let mut server = env.server();
+    let text = server.call_ok(
+        "clone-actor",
+        json!({
+            "source": upstream.source(),
+            "ref": "v1",
+            "subdirectory": "actors/echo",
+            "name": "team/echo-v1"
+        }),
+    );
+    assert_contains(&text, &["Actor 'team/echo-v1' cloned", &v1]);
+
+    let actor = env.actor_path("team/echo-v1");
+    assert_eq!(
+        fs::read_to_string(actor.join("src/lib.rs")).unwrap(),
+        "// v1\n"
+    );
+    assert_contains(
+        &fs::read_to_string(actor.join("manifest.toml")).unwrap(),
+        &["name = \"echo-v1\""],
+    );
+    let record = source_record(&env, "team/echo-v1");
+    assert_eq!(record["ref"].as_str(), Some("v1"));
+    assert_eq!(record["subdirectory"].as_str(), Some("actors/echo"));
+
+    // A tag doesn't move, so there is nothing to update
+    let text = server.call_ok(
+        "update-actor-from-source",
+        json!({ "name": "team/echo-v1" }),
+    );
+    assert_contains(&text, &["up to date"]);
+
+    // Switching to the branch picks up v2
+    let text = server.call_ok(
+        "update-actor-from-source",
+        json!({ "name": "team/echo-v1", "ref": "main" }),
+    );
+    assert_contains(&text, &["Updated actor 'team/echo-v1'", "- src/lib.rs"]);
+    assert_eq!(
+        fs::read_to_string(actor.join("src/lib.rs")).unwrap(),
+        "// v2\n"
+    );
+    assert_contains(
+        &fs::read_to_string(actor.join("manifest.toml")).unwrap(),
+        &["name = \"echo-v1\""],
+    );
+    assert_eq!(
+        source_record(&env, "team/echo-v1")["ref"].as_str(),
+        Some("main")
+    );
+}
+
+#[test]
+fn clone_actor_validates_the_source() {
+    let env = TestEnv::new();
+    let mut server = env.server();
+
+    let not_actor = Upstream::new(&env.path().join("not-actor"));
+    not_actor.commit(&[("README.md", Some("hello\n"))], "Init");
+    let text = server.call_err("clone-actor", json!({ "source": not_actor.source() }));
+    assert_contains(&text, &["not a Theater actor", "no manifest.toml"]);
+
+    let library = Upstream::new(&env.path().join("library"));
+    library.commit(
+        &[
+            ("manifest.toml", Some(MANIFEST)),
+            (
+                "Cargo.toml",
+                Some("[package]\nname = \"echo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+            ),
+        ],
+        "Init",
+    );
+    let text = server.call_err("clone-actor", json!({ "source": library.source() }));
+    assert_contains(&text, &["cdylib"]);
+
+    let upstream = Upstream::with_actor(&env.path().join("echo"), "");
+    let text = server.call_err(
+        "clone-actor",
+        json!({ "source": upstream.source(), "ref": "no-such-branch" }),
+    );
+    assert_contains(&text, &["'no-such-branch' is not a branch, tag or commit"]);
+    server.call_err(
+        "clone-actor",
+        json!({ "source": upstream.source(), "subdirectory": "../escape" }),
+    );
+    server.call_err(
+        "clone-actor",
+        json!({ "source": upstream.source(), "subdirectory": "missing" }),
+    );
+    server.call_err(
+        "clone-actor",
+        json!({ "source": env.path().join("nowhere").to_str().unwrap() }),
+    );
+
+    server.call_ok("clone-actor", json!({ "source": upstream.source() }));
+    let text = server.call_err("clone-actor", json!({ "source": upstream.source() }));
+    assert_contains(&text, &["already exists"]);
+    server.call_err("clone-actor", json!({}));
+
+    // Nothing but the one clone was left behind
+    let entries: Vec<_> = fs::read_dir(&env.registry)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name != ".registry.config.toml")
+        .collect();
+    assert_eq!(entries, ["echo"]);
+}
+
+#[test]
+fn update_pulls_upstream_changes_and_keeps_local_ones() {
+    let (env, mut server, upstream) = cloned_env();
+    let actor = env.actor_path("echo");
+    let previous = upstream.head();
+
+    let text = server.call_ok("update-actor-from-source", json!({ "name": "echo" }));
+    assert_contains(&text, &["up to date"]);
+
+    upstream.commit(
+        &[
+            ("src/lib.rs", Some("// v2\n")),
+            ("src/util.rs", Some("// util\n")),
+            ("notes.md", None),
+        ],
+        "v2",
+    );
+
+    // A build rewrites the manifest's component, and the README is a local
+    // edit upstream didn't touch; neither is in the way
+    env.create_built_actor(&mut server, "other");
+    server.call_ok("build-actor", json!({ "name": "echo" }));
+    fs::write(actor.join("README.local"), "mine\n").unwrap();
+
+    let text = server.call_ok(
+        "update-actor-from-source",
+        json!({ "name": "echo", "dry_run": true }),
+    );
+    assert_contains(
+        &text,
+        &[
+            "Would update actor 'echo'",
+            &previous[..7],
+            "Added:\n- src/util.rs",
+            "Modified:\n- src/lib.rs",
+            "Removed:\n- notes.md",
+        ],
+    );
+    assert_eq!(
+        fs::read_to_string(actor.join("src/lib.rs")).unwrap(),
+        "// v1\n"
+    );
+
+    let text = server.call_ok("update-actor-from-source", json!({ "name": "echo" }));
+    assert_contains(&text, &["Updated actor 'echo'", &upstream.head()[..7]]);
+    assert_eq!(
+        fs::read_to_string(actor.join("src/lib.rs")).unwrap(),
+        "// v2\n"
+    );
+    assert!(actor.join("src/util.rs").is_file());
+    assert!(!actor.join("notes.md").exists());
+    assert!(actor.join("README.local").is_file());
+    assert_eq!(
+        source_record(&env, "echo")["commit"].as_str().unwrap(),
+        upstream.head()
+    );
+
+    // Upstream changes the manifest; the local build output survives
+    upstream.commit(
+        &[(
+            "manifest.toml",
+            Some(&MANIFEST.replace("Echoes what it is sent", "Echoes")),
+        )],
+        "Shorter description",
+    );
+    server.call_ok("update-actor-from-source", json!({ "name": "echo" }));
+    let manifest = fs::read_to_string(actor.join("manifest.toml")).unwrap();
+    assert_contains(
+        &manifest,
+        &["description = \"Echoes\"", "target/wasm32-unknown-unknown"],
+    );
+}
+
+#[test]
+fn update_refuses_to_overwrite_local_changes_without_force() {
+    let (env, mut server, upstream) = cloned_env();
+    let actor = env.actor_path("echo");
+
+    fs::write(actor.join("src/lib.rs"), "// local\n").unwrap();
+    upstream.commit(&[("src/lib.rs", Some("// v2\n"))], "v2");
+
+    let text = server.call_err("update-actor-from-source", json!({ "name": "echo" }));
+    assert_contains(&text, &["Local changes to src/lib.rs", "force"]);
+    assert_eq!(
+        fs::read_to_string(actor.join("src/lib.rs")).unwrap(),
+        "// local\n"
+    );
+
+    let text = server.call_ok(
+        "update-actor-from-source",
+        json!({ "name": "echo", "force": true }),
+    );
+    assert_contains(&text, &["Local changes overwritten:\n- src/lib.rs"]);
+    assert_eq!(
+        fs::read_to_string(actor.join("src/lib.rs")).unwrap(),
+        "// v2\n"
+    );
+}
+
+#[test]
+fn update_errors() {
+    let (env, mut server, upstream) = cloned_env();
+
+    env.create_actor(&mut server, "local");
+    let text = server.call_err("update-actor-from-source", json!({ "name": "local" }));
+    assert_contains(&text, &["not cloned from a git source"]);
+    server.call_err("update-actor-from-source", json!({ "name": "missing" }));
+
+    // Upstream breaks the actor; nothing is applied
+    upstream.commit(&[("manifest.toml", None)], "Oops");
+    let text = server.call_err("update-actor-from-source", json!({ "name": "echo" }));
+    assert_contains(&text, &["not a Theater actor"]);
+    assert!(env.actor_path("echo").join("manifest.toml").is_file());
+
+    fs::remove_dir_all(&upstream.bare).unwrap();
+    let text = server.call_err("update-actor-from-source", json!({ "name": "echo" }));
+    assert_contains(&text, &["Failed to fetch"]);
+}
@@ -0,0 +1,206 @@
+use actor_registry_mcp::registry::actor::Actor;
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// Templates `Actor::create` can render
+const TEMPLATES: &[&str] = &["basic", "http", "supervisor"];
+
+/// Actor names each template is rendered with, by the directory their
+/// golden tree is kept in
+const NAMES: &[(&str, &str)] = &[
+    ("simple", "echo"),
+    ("hyphenated", "chat-room"),
+    ("underscored", "chat_room"),
+    ("unicode", "café"),
+];
+
+/// Set to rewrite the golden trees from the current templates
+const UPDATE_VAR: &str = "UPDATE_GOLDEN";
+
+/// Set to skip running `cargo metadata` on the rendered actors
+const SKIP_CARGO_VAR: &str = "SKIP_CARGO_METADATA";
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn golden_dir(template: &str, label: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(template)
+        .join(label)
+}
+
+/// Every file under `dir`, by its `/`-separated path relative to `dir`
+fn read_tree(dir: &Path) -> BTreeMap<String, String> {
+    WalkDir::new(dir)
+        .into_iter()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let relative = entry.path().strip_prefix(dir).unwrap();
+            let key = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            (key, fs::read_to_string(entry.path()).unwrap())
+        })
+        .collect()
+}
+
+fn write_tree(dir: &Path, files: &BTreeMap<String, String>) {
+    if dir.exists() {
+        fs::remove_dir_all(dir).unwrap();
+    }
+    for (file, content) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
+/// Describes how `actual` differs from `expected`, if it does
+fn compare_trees(
+    expected: &BTreeMap<String, String>,
+    actual: &BTreeMap<String, String>,
+) -> Option<String> {
+    let mut report = String::new();
+    for file in expected.keys().filter(|f| !actual.contains_key(*f)) {
+        report.push_str(&format!("missing file {}\n", file));
+    }
+    for (file, content) in actual {
+        match expected.get(file) {
+            None => report.push_str(&format!("unexpected file {}\n", file)),
+            Some(golden) if golden != content => {
+                let diff = TextDiff::from_lines(golden.as_str(), content.as_str());
+                report.push_str(
+                    &diff
+                        .unified_diff()
+                        .header(&format!("golden/{}", file), &format!("rendered/{}", file))
+                        .to_string(),
+                );
+            }
+            Some(_) => {}
+        }
+    }
+    (!report.is_empty()).then_some(report)
+}
+
+/// Checks that Cargo accepts the rendered manifest, without resolving
+/// dependencies so it works offline
+fn check_cargo_metadata(actor: &Path) -> Result<(), String> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args([
+            "metadata",
+            "--no-deps",
+            "--format-version",
+            "1",
+            "--offline",
+        ])
+        .current_dir(actor)
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).to_string()),
+        Err(e) => {
+            eprintln!("Skipping cargo metadata check, cargo did not run: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Checks that the rendered world resolves against the Theater interfaces
+/// copied next to it
+fn check_wit(actor: &Path) -> Result<(), String> {
+    let mut resolve = wit_parser::Resolve::default();
+    resolve
+        .push_dir(actor.join("wit"))
+        .map(|_| ())
+        .map_err(|e| format!("{:?}", e))
+}
+
+#[test]
+fn templates_match_golden_files() {
+    let update = env::var_os(UPDATE_VAR).is_some();
+    let check_cargo = env::var_os(SKIP_CARGO_VAR).is_none();
+    let wit_source = fixtures().join("wit");
+    let mut failures = Vec::new();
+
+    for template in TEMPLATES {
+        for (label, name) in NAMES {
+            let case = format!("{} template as '{}' ({})", template, name, label);
+            let scratch = tempfile::tempdir().unwrap();
+            let actor_path = scratch.path().join(name);
+
+            if let Err(e) = Actor::create(name, &actor_path, Some(template), Some(&wit_source)) {
+                failures.push(format!("{}: failed to render: {:#}", case, e));
+                continue;
+            }
+            let rendered = read_tree(&actor_path);
+
+            let golden = golden_dir(template, label);
+            if update {
+                write_tree(&golden, &rendered);
+            } else if !golden.is_dir() {
+                failures.push(format!("{}: no golden tree at {}", case, golden.display()));
+            } else if let Some(diff) = compare_trees(&read_tree(&golden), &rendered) {
+                failures.push(format!(
+                    "{}: differs from {}\n{}",
+                    case,
+                    golden.display(),
+                    diff
+                ));
+            }
+
+            if check_cargo {
+                if let Err(e) = check_cargo_metadata(&actor_path) {
+                    failures.push(format!("{}: Cargo.toml is invalid: {}", case, e));
+                }
+            }
+            if let Err(e) = check_wit(&actor_path) {
+                failures.push(format!("{}: WIT does not parse: {}", case, e));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\n\nIf the change is intended, rerun with {}=1 and review the updated tests/golden",
+        failures.join("\n\n"),
+        UPDATE_VAR
+    );
+}
+
+#[test]
+fn world_names_drop_accents() {
+    let scratch = tempfile::tempdir().unwrap();
+    let cases = [
+        ("café", "cafe"),
+        ("Ærøskøbing", "aeroskobing"),
+        ("straße_bot", "strasse-bot"),
+        // `e` followed by a combining acute accent
+        ("cafe\u{301}-ordre", "cafe-ordre"),
+        ("日本", "actor"),
+    ];
+
+    for (name, world) in cases {
+        let actor_path = scratch.path().join(name);
+        Actor::create(name, &actor_path, None, None).unwrap();
+        let wit = fs::read_to_string(actor_path.join("wit/world.wit")).unwrap();
+        assert!(
+            wit.contains(&format!("world {} {{", world)),
+            "'{}' should have world '{}':\n{}",
+            name,
+            world,
+            wit
+        );
+    }
+}
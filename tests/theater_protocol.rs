@@ -0,0 +1,98 @@
+use actor_registry_mcp::theater_client::{ChainEvent, ManagementCommand, ManagementResponse};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use theater::id::TheaterId;
+use theater::theater_server::{
+    ManagementCommand as TheaterCommand, ManagementResponse as TheaterResponse,
+};
+
+const ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+fn theater_id() -> TheaterId {
+    TheaterId::parse(ID).unwrap()
+}
+
+/// Converts `value` to `T` through JSON and checks that `T` writes the
+/// same JSON back, so a variant or field renamed on either side of the
+/// registry's copy of the protocol fails here instead of against a server
+fn convert<T: Serialize + DeserializeOwned>(value: &impl Serialize) -> T {
+    let json = serde_json::to_value(value).unwrap();
+    let converted: T = serde_json::from_value(json.clone())
+        .unwrap_or_else(|e| panic!("{} does not convert: {}", json, e));
+    assert_eq!(serde_json::to_value(&converted).unwrap(), json);
+    converted
+}
+
+#[test]
+fn commands_match_theaters() {
+    let commands = [
+        ManagementCommand::StartActor {
+            manifest: "/registry/echo/manifest.toml".to_string(),
+            initial_state: Some(br#"{"count":1}"#.to_vec()),
+            parent: false,
+            subscribe: false,
+        },
+        ManagementCommand::StartActor {
+            manifest: "/registry/echo/manifest.toml".to_string(),
+            initial_state: None,
+            parent: false,
+            subscribe: false,
+        },
+        ManagementCommand::StopActor { id: ID.to_string() },
+        ManagementCommand::ListActors,
+        ManagementCommand::SendActorMessage {
+            id: ID.to_string(),
+            data: b"hello".to_vec(),
+        },
+        ManagementCommand::RequestActorMessage {
+            id: ID.to_string(),
+            data: b"ping".to_vec(),
+        },
+        ManagementCommand::GetActorEvents { id: ID.to_string() },
+    ];
+
+    for command in &commands {
+        let _: TheaterCommand = convert(command);
+    }
+}
+
+#[test]
+fn responses_match_theaters() {
+    let event = theater::chain::ChainEvent {
+        hash: vec![1, 2, 3],
+        parent_hash: Some(vec![0]),
+        event_type: "wasm".to_string(),
+        data: br#"{"function":"init"}"#.to_vec(),
+        timestamp: 1_700_000_000_000,
+        description: Some("init".to_string()),
+    };
+
+    let responses = [
+        TheaterResponse::ActorStarted { id: theater_id() },
+        TheaterResponse::ActorStopped { id: theater_id() },
+        TheaterResponse::ActorList {
+            actors: vec![(theater_id(), "echo".to_string())],
+        },
+        TheaterResponse::SentMessage { id: theater_id() },
+        TheaterResponse::RequestedMessage {
+            id: theater_id(),
+            message: b"pong".to_vec(),
+        },
+        TheaterResponse::ActorEvents {
+            id: theater_id(),
+            events: vec![event],
+        },
+    ];
+
+    for response in &responses {
+        let _: ManagementResponse = convert(response);
+    }
+
+    // Events keep every field Theater records
+    let events: Vec<ChainEvent> = match convert(&responses[5]) {
+        ManagementResponse::ActorEvents { events, .. } => events,
+        other => panic!("Unexpected response {:?}", other),
+    };
+    assert_eq!(events[0].event_type, "wasm");
+    assert_eq!(events[0].parent_hash, Some(vec![0]));
+}